@@ -1,12 +1,32 @@
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 
-use crate::data::{json::*, state::GameState};
-use bevy::prelude::*;
+use crate::{
+    data::{
+        json::*,
+        state::{advance, GameState, Transition},
+        theme::Theme,
+    },
+    entities::weapon::Weapons,
+};
+use bevy::{
+    asset::LoadState,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
 use serde::Deserialize;
 
 pub struct AnimationLoadPlugin;
 
+const ANIMATION_LIST_VERSION: u32 = 2;
+const DEFAULT_FRAME_TIME: f32 = 0.1;
+// optional enemy animation swapped in for the walk once it notices the hero
+pub const ALERT_ANIM: &str = "alert";
+
 #[derive(Asset, TypePath, Debug, Deserialize, Default)]
+#[serde(default)]
 pub struct TilesetData {
     pub width: i32,
     pub height: i32,
@@ -15,30 +35,266 @@ pub struct TilesetData {
 }
 
 #[derive(Asset, TypePath, Debug, Deserialize, Default)]
+#[serde(default)]
 pub struct EnemyAnimationEntry {
     pub name: String,
     pub anim_names: Vec<String>,
     pub height: f32,
+    #[serde(default = "default_enemy_health")]
+    pub health: i32,
+    #[serde(default = "default_enemy_speed")]
+    pub speed: f32,
+    #[serde(default = "default_enemy_reward")]
+    pub reward: i32,
+    #[serde(default = "default_enemy_weight")]
+    pub weight: f32,
+    #[serde(default = "default_enemy_wall_damage")]
+    pub wall_damage: i32,
+    pub unlock_score: Option<i32>,
+    pub max_concurrent: Option<usize>,
+    pub frenzy: Option<f32>,
+    pub drain_aura: Option<DrainAuraEntry>,
+    pub blinker: Option<BlinkerEntry>,
+    pub aggro: Option<AggroEntry>,
+    pub ranged: Option<RangedEntry>,
+    #[serde(default = "default_enemy_drops")]
+    pub drops: Vec<DropEntry>,
+    pub death_style: DeathStyle,
+    pub contact_behavior: ContactBehavior,
+    pub frame_times: FrameTimes,
+}
+
+#[derive(Component, Debug, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeathStyle {
+    #[default]
+    Flash,
+    Launch,
+}
+
+#[derive(Component, Debug, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ContactBehavior {
+    #[default]
+    PassThrough,
+    Linger,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct DrainAuraEntry {
+    pub radius: f32,
+    pub drain_per_sec: f32,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct BlinkerEntry {
+    pub interval: f32,
+    pub distance: f32,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct AggroEntry {
+    pub range: f32,
+    #[serde(default = "default_alert_speed_mult")]
+    pub speed_mult: f32,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct RangedEntry {
+    pub interval: f32,
+    pub speed: f32,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DropKind {
+    Health,
+    Bomb,
+    Shield,
+    MaxHealth,
+}
+
+// chances are tried in order against a single roll, so at most one drop
+// comes out per kill and entries past a running total of 1.0 never drop
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct DropEntry {
+    pub kind: DropKind,
+    pub chance: f64,
+}
+
+fn default_enemy_health() -> i32 {
+    1
+}
+
+fn default_enemy_speed() -> f32 {
+    75.0
+}
+
+fn default_enemy_reward() -> i32 {
+    1
+}
+
+fn default_alert_speed_mult() -> f32 {
+    1.0
+}
+
+// the rates every enemy used before drops could be set per type
+fn default_enemy_drops() -> Vec<DropEntry> {
+    vec![
+        DropEntry {
+            kind: DropKind::Health,
+            chance: 0.1,
+        },
+        DropEntry {
+            kind: DropKind::Bomb,
+            chance: 0.02,
+        },
+        DropEntry {
+            kind: DropKind::Shield,
+            chance: 0.03,
+        },
+        DropEntry {
+            kind: DropKind::MaxHealth,
+            chance: 0.01,
+        },
+    ]
+}
+
+fn default_enemy_weight() -> f32 {
+    1.0
+}
+
+fn default_enemy_wall_damage() -> i32 {
+    1
 }
 
 #[derive(Asset, TypePath, Debug, Deserialize, Default)]
+#[serde(default)]
 pub struct PlayerAnimationEntry {
     pub name: String,
     pub anim_names: Vec<String>,
+    pub frame_times: FrameTimes,
+}
+
+// seconds per frame keyed by animation name, e.g. `{ "walk": 0.1, "die": 0.15 }`
+#[derive(Component, Debug, Deserialize, Default, Clone)]
+pub struct FrameTimes(HashMap<String, f32>);
+
+impl FrameTimes {
+    pub fn get(&self, state: AnimState) -> f32 {
+        self.0
+            .get(&state.to_string())
+            .copied()
+            .filter(|time| *time > 0.0)
+            .unwrap_or(DEFAULT_FRAME_TIME)
+    }
 }
 
 #[derive(Asset, TypePath, Debug, Deserialize, Default)]
+#[serde(from = "RawAnimationListAsset")]
 pub struct AnimationListAsset {
     pub tileset: TilesetData,
     pub enemies: Vec<EnemyAnimationEntry>,
     pub player: PlayerAnimationEntry,
 }
 
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawAnimationListAsset {
+    version: u32,
+    tileset: TilesetData,
+    enemies: Vec<EnemyAnimationEntry>,
+    player: PlayerAnimationEntry,
+    enemy_anim_names: Option<Vec<String>>,
+    player_anim_names: Option<Vec<String>>,
+}
+
+impl Default for RawAnimationListAsset {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            tileset: TilesetData::default(),
+            enemies: Vec::new(),
+            player: PlayerAnimationEntry::default(),
+            enemy_anim_names: None,
+            player_anim_names: None,
+        }
+    }
+}
+
+impl From<RawAnimationListAsset> for AnimationListAsset {
+    fn from(raw: RawAnimationListAsset) -> Self {
+        if raw.version < ANIMATION_LIST_VERSION {
+            warn!(
+                "animation list is version {}, migrating to version {}",
+                raw.version, ANIMATION_LIST_VERSION
+            );
+        }
+        let mut enemies = raw.enemies;
+        if let Some(names) = raw.enemy_anim_names {
+            warn!("`enemy_anim_names` is deprecated, list `anim_names` on each enemy instead");
+            for enemy in enemies.iter_mut().filter(|e| e.anim_names.is_empty()) {
+                enemy.anim_names = names.clone();
+            }
+        }
+        for enemy in enemies.iter_mut().filter(|e| e.height <= 0.0) {
+            warn!(
+                "enemy `{}` has no `height`, using the tileset height",
+                enemy.name
+            );
+            enemy.height = raw.tileset.height as f32;
+        }
+        let mut player = raw.player;
+        if let Some(names) = raw.player_anim_names {
+            warn!("`player_anim_names` is deprecated, list `anim_names` on the player instead");
+            if player.anim_names.is_empty() {
+                player.anim_names = names;
+            }
+        }
+        Self {
+            tileset: raw.tileset,
+            enemies,
+            player,
+        }
+    }
+}
+
+impl AnimationListAsset {
+    // collects every problem in one pass so a broken list can be fixed in one
+    // go instead of one panic at a time
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.tileset.width <= 0 || self.tileset.height <= 0 {
+            errors.push(format!(
+                "tileset size must be positive, got {}x{}",
+                self.tileset.width, self.tileset.height
+            ));
+        }
+        let mut seen = HashSet::new();
+        for (i, enemy) in self.enemies.iter().enumerate() {
+            if enemy.name.is_empty() {
+                errors.push(format!("enemy #{} has no name", i));
+            } else if !seen.insert(enemy.name.as_str()) {
+                errors.push(format!("enemy `{}` is listed more than once", enemy.name));
+            }
+            if enemy.anim_names.is_empty() {
+                errors.push(format!("enemy `{}` has no anim_names", enemy.name));
+            }
+        }
+        if self.player.anim_names.is_empty() {
+            errors.push("player has no anim_names".to_string());
+        }
+        errors
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct AnimationList {
     pub handle: Handle<AnimationListAsset>,
     pub loaded_enemies: bool,
     pub loaded_players: bool,
+    pub validated: bool,
+    pub errors: Vec<String>,
 }
 
 impl AnimationList {
@@ -47,6 +303,11 @@ impl AnimationList {
     }
 }
 
+// atlases are only built once the list has passed validation
+pub fn animation_list_valid(list: Res<AnimationList>) -> bool {
+    list.validated && list.errors.is_empty()
+}
+
 #[allow(dead_code)]
 #[derive(Default, Clone, Copy, PartialEq)]
 pub enum AnimState {
@@ -54,6 +315,7 @@ pub enum AnimState {
     Walking,
     Idle,
     Hurting,
+    Attacking,
     Dying,
     Flashing,
     Dead,
@@ -66,6 +328,7 @@ impl AnimState {
             AnimState::Idle => true,
             AnimState::Dying => true,
             AnimState::Hurting => true,
+            AnimState::Attacking => true,
             AnimState::Flashing => false,
             AnimState::Dead => false,
         }
@@ -79,27 +342,32 @@ impl AnimState {
     }
 }
 
-impl ToString for AnimState {
-    fn to_string(&self) -> String {
-        match self {
-            AnimState::Walking => "walk".to_string(),
-            AnimState::Idle => "idle".to_string(),
-            AnimState::Dying => "die".to_string(),
-            AnimState::Hurting => "hurt".to_string(),
-            AnimState::Flashing => "flash".to_string(),
-            AnimState::Dead => "dead".to_string(),
-        }
+impl std::fmt::Display for AnimState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            AnimState::Walking => "walk",
+            AnimState::Idle => "idle",
+            AnimState::Dying => "die",
+            AnimState::Hurting => "hurt",
+            AnimState::Attacking => "attack",
+            AnimState::Flashing => "flash",
+            AnimState::Dead => "dead",
+        })
     }
 }
 
 #[derive(Default)]
 pub struct AnimationHandles {
     handles: HashMap<String, Handle<TextureAtlas>>,
+    pub frame_times: FrameTimes,
 }
 
 impl AnimationHandles {
-    pub fn new(handles: HashMap<String, Handle<TextureAtlas>>) -> Self {
-        Self { handles }
+    pub fn new(handles: HashMap<String, Handle<TextureAtlas>>, frame_times: FrameTimes) -> Self {
+        Self {
+            handles,
+            frame_times,
+        }
     }
 
     pub fn get_handle(&self, state: AnimState) -> Option<Handle<TextureAtlas>> {
@@ -110,6 +378,12 @@ impl AnimationHandles {
         }
     }
 
+    // for animations outside the usual states, None if the enemy has no such
+    // sheet listed
+    pub fn get_named(&self, name: &str) -> Option<Handle<TextureAtlas>> {
+        self.handles.get(name).cloned()
+    }
+
     pub fn add_handle(&mut self, key: String, handle: Handle<TextureAtlas>) {
         self.handles.insert(key, handle);
     }
@@ -125,6 +399,7 @@ pub struct AnimationComponent {
     pub max_flashes: usize,
     pub flash_count: usize,
     pub state: AnimState,
+    timed_state: Option<AnimState>,
 }
 
 impl AnimationComponent {
@@ -134,6 +409,20 @@ impl AnimationComponent {
             ..Default::default()
         }
     }
+
+    // for entities that come back instead of being spawned fresh, so a
+    // leftover flash_count or half run dying_timer can't carry over
+    pub fn reset(&mut self, state: AnimState) {
+        let defaults = Self::default();
+        self.first = defaults.first;
+        self.last = defaults.last;
+        self.timer.reset();
+        self.dying_timer.reset();
+        self.flashing_timer.reset();
+        self.flash_count = 0;
+        self.state = state;
+        self.timed_state = None;
+    }
 }
 
 impl Default for AnimationComponent {
@@ -141,12 +430,16 @@ impl Default for AnimationComponent {
         Self {
             first: 0,
             last: 3,
-            timer: Timer::new(Duration::from_secs_f32(0.1), TimerMode::Repeating),
+            timer: Timer::new(
+                Duration::from_secs_f32(DEFAULT_FRAME_TIME),
+                TimerMode::Repeating,
+            ),
             dying_timer: Timer::new(Duration::from_secs_f32(0.5), TimerMode::Repeating),
             flashing_timer: Timer::new(Duration::from_secs_f32(0.2), TimerMode::Repeating),
             max_flashes: 6,
             flash_count: 0,
             state: AnimState::default(),
+            timed_state: None,
         }
     }
 }
@@ -178,7 +471,10 @@ impl Plugin for AnimationLoadPlugin {
         .init_resource::<PlayerAnimation>()
         .init_resource::<ImagesToLoad>()
         .add_systems(Startup, setup)
-        .add_systems(Update, stop_waiting.run_if(in_state(GameState::Loading)))
+        .add_systems(
+            Update,
+            (validate_animation_list, stop_waiting).run_if(in_state(GameState::Loading)),
+        )
         .add_systems(
             Update,
             wait_for_assets_to_load.run_if(in_state(GameState::Waiting)),
@@ -190,6 +486,19 @@ impl Plugin for AnimationLoadPlugin {
     }
 }
 
+pub fn placeholder_image(width: u32, height: u32) -> Image {
+    Image::new_fill(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[255, 0, 255, 255],
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
 fn setup(mut list: ResMut<AnimationList>, asset_server: Res<AssetServer>) {
     if list.is_loaded() {
         return;
@@ -197,26 +506,83 @@ fn setup(mut list: ResMut<AnimationList>, asset_server: Res<AssetServer>) {
     list.handle = asset_server.load("sprites/list.animinfo.json");
 }
 
+fn validate_animation_list(
+    mut commands: Commands,
+    mut list: ResMut<AnimationList>,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    anim_assets: Res<Assets<AnimationListAsset>>,
+) {
+    if list.validated {
+        return;
+    }
+    let Some(anim_list) = anim_assets.get(&list.handle) else {
+        return;
+    };
+    list.errors = anim_list.validate();
+    list.validated = true;
+    if list.errors.is_empty() {
+        return;
+    }
+    let report = list
+        .errors
+        .iter()
+        .map(|error| format!("  - {}", error))
+        .collect::<Vec<_>>()
+        .join("\n");
+    error!(
+        "sprites/list.animinfo.json has {} problem(s):\n{}",
+        list.errors.len(),
+        report
+    );
+    let mut style = theme.hud_style(&asset_server);
+    style.color = Color::RED;
+    commands.spawn(
+        TextBundle::from_section(
+            format!("Could not load sprites/list.animinfo.json:\n{}", report),
+            style,
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.0),
+            top: Val::Px(10.0),
+            ..default()
+        }),
+    );
+}
+
 fn stop_waiting(
     list: ResMut<AnimationList>,
+    weapons: Res<Weapons>,
     state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    if list.is_loaded() {
-        next_state.set(state.transition());
+    if list.is_loaded() && weapons.loaded {
+        advance(&state, &mut next_state, Transition::DataLoaded);
     }
 }
 
 fn animate_sprite(
     time: Res<Time>,
-    mut query: Query<(&mut TextureAtlasSprite, &mut AnimationComponent)>,
+    mut query: Query<(
+        &mut TextureAtlasSprite,
+        &mut AnimationComponent,
+        Option<&DeathStyle>,
+        Option<&FrameTimes>,
+    )>,
 ) {
-    for (mut sprite, mut anim) in &mut query {
+    for (mut sprite, mut anim, death_style, frame_times) in &mut query {
+        if anim.timed_state != Some(anim.state) {
+            let frame_time = frame_times.map_or(DEFAULT_FRAME_TIME, |times| times.get(anim.state));
+            anim.timer.set_duration(Duration::from_secs_f32(frame_time));
+            anim.timed_state = Some(anim.state);
+        }
         if anim.state.should_anim() {
             anim.timer.tick(time.delta());
             if anim.state == AnimState::Dying && sprite.index == anim.last {
                 anim.dying_timer.tick(time.delta());
-                if anim.dying_timer.just_finished() {
+                // launched corpses hold their last frame until they leave the screen
+                if anim.dying_timer.just_finished() && death_style != Some(&DeathStyle::Launch) {
                     anim.state = AnimState::Flashing;
                 }
                 continue;
@@ -254,6 +620,8 @@ fn flash_sprite(time: Res<Time>, mut query: Query<(&mut AnimationComponent, &mut
 fn wait_for_assets_to_load(
     mut events: EventReader<AssetEvent<Image>>,
     mut images_to_load: ResMut<ImagesToLoad>,
+    mut images: ResMut<Assets<Image>>,
+    asset_server: Res<AssetServer>,
     anim_list: Res<AnimationList>,
     state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<GameState>>,
@@ -266,8 +634,179 @@ fn wait_for_assets_to_load(
                 }
             }
         }
+        // a missing sprite never finishes loading, so stand a magenta square in
+        // for it rather than waiting forever
+        images_to_load.images.retain(|id| {
+            if asset_server.get_load_state(*id) != Some(LoadState::Failed) {
+                return true;
+            }
+            error!(
+                "failed to load sprite {:?}, using a placeholder",
+                asset_server.get_path(*id)
+            );
+            images.insert(*id, placeholder_image(1, 1));
+            false
+        });
         if images_to_load.images.is_empty() {
-            next_state.set(state.transition());
+            advance(&state, &mut next_state, Transition::ImagesLoaded);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{advance, enter_state, headless_app};
+
+    // a version 1 list: shared anim names, no heights and none of the
+    // per-enemy stats added since
+    const OLD_LIST: &str = r#"{
+        "tileset": { "width": 16, "height": 16, "padding_x": 0, "padding_y": 0 },
+        "enemy_anim_names": ["walk", "die"],
+        "player_anim_names": ["idle", "walk", "die"],
+        "enemies": [{ "name": "demon" }, { "name": "bat", "anim_names": ["walk"] }],
+        "player": { "name": "hero" }
+    }"#;
+
+    #[test]
+    fn old_animation_list_is_migrated() {
+        let list: AnimationListAsset = serde_json::from_str(OLD_LIST).unwrap();
+
+        let demon = &list.enemies[0];
+        assert_eq!(demon.anim_names, vec!["walk", "die"]);
+        assert_eq!(demon.height, 16.0);
+        assert_eq!(demon.health, 1);
+        assert_eq!(demon.speed, 75.0);
+        assert_eq!(demon.drops.len(), default_enemy_drops().len());
+        // its own names win over the shared ones
+        assert_eq!(list.enemies[1].anim_names, vec!["walk"]);
+        assert_eq!(list.player.anim_names, vec!["idle", "walk", "die"]);
+        assert!(list.validate().is_empty());
+    }
+
+    #[test]
+    fn every_problem_in_the_list_is_reported_together() {
+        let list: AnimationListAsset = serde_json::from_str(
+            r#"{
+                "version": 2,
+                "tileset": { "width": 0, "height": 16 },
+                "enemies": [
+                    { "name": "demon", "anim_names": ["walk"] },
+                    { "name": "demon", "anim_names": ["walk"] },
+                    { "name": "bat" }
+                ],
+                "player": { "name": "hero", "anim_names": ["idle"] }
+            }"#,
+        )
+        .unwrap();
+
+        let errors = list.validate();
+        assert_eq!(errors.len(), 3, "{errors:?}");
+        assert!(errors[0].contains("tileset"));
+        assert!(errors[1].contains("`demon`"));
+        assert!(errors[2].contains("`bat`"));
+    }
+
+    #[test]
+    fn reset_clears_a_finished_death() {
+        let mut anim = AnimationComponent::new(AnimState::Walking);
+        anim.first = 4;
+        anim.last = 7;
+        anim.flash_count = anim.max_flashes;
+        for timer in [
+            &mut anim.timer,
+            &mut anim.dying_timer,
+            &mut anim.flashing_timer,
+        ] {
+            let duration = timer.duration();
+            timer.tick(duration);
+        }
+        anim.state = AnimState::Dead;
+
+        anim.reset(AnimState::Idle);
+        let fresh = AnimationComponent::default();
+        assert_eq!(anim.flash_count, 0);
+        assert_eq!((anim.first, anim.last), (fresh.first, fresh.last));
+        assert!(anim.state == AnimState::Idle);
+        for timer in [&anim.timer, &anim.dying_timer, &anim.flashing_timer] {
+            assert!(!timer.finished());
+            assert_eq!(timer.elapsed(), Duration::ZERO);
+        }
+    }
+
+    #[test]
+    fn dying_switches_to_the_configured_frame_time() {
+        let mut app = headless_app();
+        app.add_systems(Update, animate_sprite);
+        let times: FrameTimes = serde_json::from_str(r#"{ "walk": 0.1, "die": 0.15 }"#).unwrap();
+        let enemy = app
+            .world
+            .spawn((
+                TextureAtlasSprite::default(),
+                AnimationComponent::new(AnimState::Walking),
+                times,
+            ))
+            .id();
+        advance(&mut app, 1);
+        let timer = |app: &App| {
+            app.world
+                .get::<AnimationComponent>(enemy)
+                .unwrap()
+                .timer
+                .duration()
+        };
+        assert_eq!(timer(&app), Duration::from_secs_f32(0.1));
+
+        app.world
+            .get_mut::<AnimationComponent>(enemy)
+            .unwrap()
+            .state = AnimState::Dying;
+        advance(&mut app, 1);
+        assert_eq!(timer(&app), Duration::from_secs_f32(0.15));
+    }
+
+    #[test]
+    fn missing_frame_times_fall_back_to_the_default() {
+        let times: FrameTimes = serde_json::from_str(r#"{ "die": 0.15 }"#).unwrap();
+        assert_eq!(times.get(AnimState::Walking), DEFAULT_FRAME_TIME);
+    }
+
+    #[test]
+    fn a_missing_sprite_does_not_hold_up_loading() {
+        let mut app = headless_app();
+        app.init_asset::<Image>()
+            .insert_resource(AnimationList {
+                loaded_enemies: true,
+                loaded_players: true,
+                ..default()
+            })
+            .init_resource::<ImagesToLoad>()
+            .add_systems(
+                Update,
+                wait_for_assets_to_load.run_if(in_state(GameState::Waiting)),
+            );
+        let missing: Handle<Image> = app
+            .world
+            .resource::<AssetServer>()
+            .load("sprites/missing.png");
+        app.world
+            .resource_mut::<ImagesToLoad>()
+            .images
+            .push(missing.id());
+        enter_state(&mut app, GameState::Waiting);
+
+        // the failure is reported from the io pool, give it a moment
+        for _ in 0..600 {
+            if *app.world.resource::<State<GameState>>() != GameState::Waiting {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+            advance(&mut app, 1);
+        }
+        assert_eq!(
+            *app.world.resource::<State<GameState>>(),
+            GameState::MainMenu
+        );
+        assert!(app.world.resource::<Assets<Image>>().contains(&missing));
+    }
+}
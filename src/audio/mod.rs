@@ -0,0 +1,399 @@
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::asset::io::file::FileAssetReader;
+use bevy::{audio::Volume, prelude::*};
+use rand::prelude::*;
+
+use crate::{
+    data::state::GameState,
+    entities::enemy::{Enemy, SpawnConfig},
+};
+
+const MIN_SFX_SPEED: f32 = 0.5;
+const MUSIC_FADE_SECS: f32 = 1.0;
+const TENSION_MAX_VOLUME: f32 = 0.6;
+// how quickly the tension layer closes in on its target, per second
+const TENSION_FOLLOW_RATE: f32 = 1.5;
+
+pub struct SfxPlugin;
+
+pub struct MusicPlugin;
+
+#[derive(Event, Clone, Copy)]
+pub enum Sfx {
+    Attack,
+    Hit,
+    Death,
+}
+
+// each is None when its file isn't in assets/, see assets/README.md
+#[derive(Resource, Default)]
+pub struct SoundEffects {
+    pub attack: Option<Handle<AudioSource>>,
+    pub hit: Option<Handle<AudioSource>>,
+    pub death: Option<Handle<AudioSource>>,
+}
+
+impl SoundEffects {
+    pub fn handle(&self, sfx: Sfx) -> Option<Handle<AudioSource>> {
+        match sfx {
+            Sfx::Attack => self.attack.clone(),
+            Sfx::Hit => self.hit.clone(),
+            Sfx::Death => self.death.clone(),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct PitchVariation {
+    pub range: f32,
+}
+
+impl Default for PitchVariation {
+    fn default() -> Self {
+        Self { range: 0.1 }
+    }
+}
+
+impl PitchVariation {
+    pub fn sample(&self, rng: &mut impl Rng) -> f32 {
+        let range = self.range.abs();
+        (1.0 + rng.gen_range(-range..=range)).max(MIN_SFX_SPEED)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MusicTrack {
+    Menu,
+    GamePlay,
+}
+
+impl MusicTrack {
+    pub fn for_state(state: &GameState) -> Option<Self> {
+        match state {
+            GameState::Waiting | GameState::MainMenu => Some(MusicTrack::Menu),
+            GameState::TransitionToGamePlay | GameState::GamePlay | GameState::Pause => {
+                Some(MusicTrack::GamePlay)
+            }
+            GameState::Loading | GameState::GameOver | GameState::Cleanup => None,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct MusicPlayer {
+    pub current: Option<Entity>,
+    pub menu: Option<Handle<AudioSource>>,
+    pub gameplay: Option<Handle<AudioSource>>,
+    pub tension: Option<Handle<AudioSource>>,
+}
+
+impl MusicPlayer {
+    pub fn handle(&self, track: MusicTrack) -> Option<Handle<AudioSource>> {
+        match track {
+            MusicTrack::Menu => self.menu.clone(),
+            MusicTrack::GamePlay => self.gameplay.clone(),
+        }
+    }
+}
+
+// looped on top of the gameplay music, louder the closer the lane is to its
+// live enemy cap
+#[derive(Component, Default)]
+struct TensionLayer {
+    volume: f32,
+}
+
+pub fn tension_volume(live: usize, max_live: usize) -> f32 {
+    (live as f32 / max_live.max(1) as f32).min(1.0) * TENSION_MAX_VOLUME
+}
+
+#[derive(Component)]
+struct Music {
+    track: MusicTrack,
+    volume: f32,
+    fade: f32,
+}
+
+impl Plugin for SfxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Sfx>()
+            .init_resource::<SoundEffects>()
+            .init_resource::<PitchVariation>()
+            .add_systems(Startup, setup)
+            .add_systems(Update, play_sfx_events);
+    }
+}
+
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MusicPlayer>()
+            .add_systems(Startup, setup_music)
+            .add_systems(
+                Update,
+                (
+                    select_music.run_if(state_changed::<GameState>()),
+                    fade_music,
+                )
+                    .chain(),
+            )
+            .add_systems(OnEnter(GameState::GamePlay), start_tension)
+            .add_systems(Update, follow_tension.run_if(in_state(GameState::GamePlay)))
+            .add_systems(OnEnter(GameState::GameOver), stop_tension);
+    }
+}
+
+// the audio isn't checked in, so a file nobody has dropped into assets/ yet
+// leaves its sound silent instead of failing to load every run. the web build
+// can't look before it asks, there a missing file just logs the failed load
+pub fn load_optional(
+    asset_server: &AssetServer,
+    path: &'static str,
+) -> Option<Handle<AudioSource>> {
+    #[cfg(not(target_arch = "wasm32"))]
+    if !FileAssetReader::get_base_path()
+        .join("assets")
+        .join(path)
+        .exists()
+    {
+        info!("{} is missing, playing without it", path);
+        return None;
+    }
+    Some(asset_server.load(path))
+}
+
+fn setup(mut sounds: ResMut<SoundEffects>, asset_server: Res<AssetServer>) {
+    sounds.attack = load_optional(&asset_server, "sounds/attack.ogg");
+    sounds.hit = load_optional(&asset_server, "sounds/hit.ogg");
+    sounds.death = load_optional(&asset_server, "sounds/death.ogg");
+}
+
+pub fn play_sfx(
+    commands: &mut Commands,
+    handle: Handle<AudioSource>,
+    variation: &PitchVariation,
+    rng: &mut impl Rng,
+) {
+    commands.spawn(AudioBundle {
+        source: handle,
+        settings: PlaybackSettings::DESPAWN.with_speed(variation.sample(rng)),
+    });
+}
+
+fn play_sfx_events(
+    mut commands: Commands,
+    mut events: EventReader<Sfx>,
+    sounds: Res<SoundEffects>,
+    variation: Res<PitchVariation>,
+) {
+    let mut rng = thread_rng();
+    for sfx in events.read() {
+        if let Some(handle) = sounds.handle(*sfx) {
+            play_sfx(&mut commands, handle, &variation, &mut rng);
+        }
+    }
+}
+
+fn setup_music(mut music: ResMut<MusicPlayer>, asset_server: Res<AssetServer>) {
+    music.menu = load_optional(&asset_server, "music/menu.ogg");
+    music.gameplay = load_optional(&asset_server, "music/gameplay.ogg");
+    music.tension = load_optional(&asset_server, "music/tension.ogg");
+}
+
+fn select_music(
+    mut commands: Commands,
+    state: Res<State<GameState>>,
+    mut player: ResMut<MusicPlayer>,
+    mut tracks: Query<(Entity, &mut Music)>,
+) {
+    let wanted = MusicTrack::for_state(state.get());
+    player.current = None;
+    // a track that is still fading out is faded back in rather than started
+    // again, so quick state flips never stack copies of the same song
+    for (entity, mut music) in &mut tracks {
+        if Some(music.track) == wanted && player.current.is_none() {
+            music.fade = 1.0;
+            player.current = Some(entity);
+        } else {
+            music.fade = -1.0;
+        }
+    }
+    // a track whose file is missing just leaves the old one fading out
+    let source = wanted.and_then(|track| Some((track, player.handle(track)?)));
+    if let (Some((track, source)), None) = (source, player.current) {
+        let entity = commands
+            .spawn((
+                AudioBundle {
+                    source,
+                    settings: PlaybackSettings::LOOP.with_volume(Volume::new_relative(0.0)),
+                },
+                Music {
+                    track,
+                    volume: 0.0,
+                    fade: 1.0,
+                },
+            ))
+            .id();
+        player.current = Some(entity);
+    }
+}
+
+fn fade_music(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut tracks: Query<(Entity, &mut Music, Option<&AudioSink>)>,
+) {
+    for (entity, mut music, sink) in &mut tracks {
+        if music.fade == 0.0 {
+            continue;
+        }
+        music.volume =
+            (music.volume + music.fade * time.delta_seconds() / MUSIC_FADE_SECS).clamp(0.0, 1.0);
+        if let Some(sink) = sink {
+            sink.set_volume(music.volume);
+        }
+        if music.fade < 0.0 && music.volume <= 0.0 {
+            commands.entity(entity).despawn();
+        } else if music.fade > 0.0 && music.volume >= 1.0 {
+            music.fade = 0.0;
+        }
+    }
+}
+
+// entering gameplay again after a pause keeps the layer that is already going
+fn start_tension(
+    mut commands: Commands,
+    player: Res<MusicPlayer>,
+    layers: Query<(), With<TensionLayer>>,
+) {
+    let Some(source) = player.tension.clone() else {
+        return;
+    };
+    if !layers.is_empty() {
+        return;
+    }
+    commands.spawn((
+        AudioBundle {
+            source,
+            settings: PlaybackSettings::LOOP.with_volume(Volume::new_relative(0.0)),
+        },
+        TensionLayer::default(),
+    ));
+}
+
+fn follow_tension(
+    time: Res<Time>,
+    config: Res<SpawnConfig>,
+    enemies: Query<(), With<Enemy>>,
+    mut layers: Query<(&mut TensionLayer, Option<&AudioSink>)>,
+) {
+    let target = tension_volume(enemies.iter().count(), config.max_live_enemies);
+    let blend = 1.0 - (-TENSION_FOLLOW_RATE * time.delta_seconds()).exp();
+    for (mut layer, sink) in &mut layers {
+        layer.volume += (target - layer.volume) * blend;
+        if let Some(sink) = sink {
+            sink.set_volume(layer.volume);
+        }
+    }
+}
+
+fn stop_tension(mut commands: Commands, layers: Query<Entity, With<TensionLayer>>) {
+    for entity in &layers {
+        commands.entity(entity).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entities::enemy::EnemyTypes,
+        testing::{advance, advance_secs, enter_state, headless_app, TEST_ENEMY},
+    };
+
+    #[test]
+    fn missing_audio_is_skipped_rather_than_loaded() {
+        let app = headless_app();
+        let asset_server = app.world.resource::<AssetServer>();
+        assert!(load_optional(asset_server, "sounds/not_recorded_yet.ogg").is_none());
+    }
+
+    fn tracks(app: &mut App) -> Vec<(MusicTrack, f32, f32)> {
+        app.world
+            .query::<&Music>()
+            .iter(&app.world)
+            .map(|music| (music.track, music.fade, music.volume))
+            .collect()
+    }
+
+    #[test]
+    fn state_change_crossfades_the_music() {
+        let mut app = headless_app();
+        app.insert_resource(MusicPlayer {
+            menu: Some(Handle::weak_from_u128(1)),
+            gameplay: Some(Handle::weak_from_u128(2)),
+            ..default()
+        })
+        .add_systems(
+            Update,
+            (
+                select_music.run_if(state_changed::<GameState>()),
+                fade_music,
+            )
+                .chain(),
+        );
+        enter_state(&mut app, GameState::MainMenu);
+        advance_secs(&mut app, MUSIC_FADE_SECS + 0.1);
+        assert!(tracks(&mut app) == vec![(MusicTrack::Menu, 0.0, 1.0)]);
+
+        enter_state(&mut app, GameState::TransitionToGamePlay);
+        let fading = tracks(&mut app);
+        assert_eq!(fading.len(), 2);
+        assert!(fading
+            .iter()
+            .any(|(track, fade, _)| *track == MusicTrack::Menu && *fade < 0.0));
+        assert!(fading
+            .iter()
+            .any(|(track, fade, _)| *track == MusicTrack::GamePlay && *fade > 0.0));
+
+        advance_secs(&mut app, MUSIC_FADE_SECS + 0.1);
+        assert!(tracks(&mut app) == vec![(MusicTrack::GamePlay, 0.0, 1.0)]);
+    }
+
+    fn tension(app: &mut App) -> f32 {
+        app.world.query::<&TensionLayer>().single(&app.world).volume
+    }
+
+    fn spawn_enemies(app: &mut App, count: usize) {
+        let info = app.world.resource::<EnemyTypes>().types[TEST_ENEMY].clone();
+        for _ in 0..count {
+            app.world.spawn(Enemy::new(TEST_ENEMY, &info));
+        }
+    }
+
+    #[test]
+    fn more_enemies_raise_the_tension_smoothly_until_game_over() {
+        let mut app = headless_app();
+        app.add_systems(Update, follow_tension.run_if(in_state(GameState::GamePlay)))
+            .add_systems(OnEnter(GameState::GameOver), stop_tension);
+        app.world.spawn(TensionLayer::default());
+        let max_live = app.world.resource::<SpawnConfig>().max_live_enemies;
+        assert!(tension_volume(max_live / 2, max_live) > tension_volume(1, max_live));
+
+        spawn_enemies(&mut app, 1);
+        advance_secs(&mut app, 3.0);
+        let few = tension(&mut app);
+        assert!(few > 0.0);
+
+        spawn_enemies(&mut app, max_live);
+        advance(&mut app, 1);
+        // eases towards the louder target instead of jumping to it
+        let rising = tension(&mut app);
+        assert!(rising > few && rising < TENSION_MAX_VOLUME / 2.0);
+        advance_secs(&mut app, 3.0);
+        assert!(tension(&mut app) > TENSION_MAX_VOLUME * 0.95);
+
+        enter_state(&mut app, GameState::GameOver);
+        let layers = app.world.query::<&TensionLayer>().iter(&app.world).count();
+        assert_eq!(layers, 0);
+    }
+}
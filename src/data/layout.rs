@@ -0,0 +1,196 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{
+    data::{
+        playfield::{DefenseLine, DefenseLineSprite, PlayfieldBounds, PlayfieldOrientation},
+        state::GameState,
+        theme::TitleText,
+    },
+    entities::player::Player,
+    interpolation::Interpolated,
+    CameraConfig, GameplayStart,
+};
+
+// the positions in CameraConfig were authored against this window
+const BASE_SIZE: Vec2 = Vec2::new(800.0, 600.0);
+const BASE_TITLE_Y: f32 = 200.0;
+
+pub struct LayoutPlugin;
+
+#[derive(Resource)]
+pub struct ScreenLayout {
+    pub size: Vec2,
+}
+
+impl Default for ScreenLayout {
+    fn default() -> Self {
+        Self { size: BASE_SIZE }
+    }
+}
+
+impl ScreenLayout {
+    // the line keeps the same place on screen, so a wider window pushes the
+    // camera further into the lane instead of showing empty space behind it
+    pub fn gameplay_pos(&self, camera: &CameraConfig, line: &DefenseLine) -> Vec3 {
+        let offset = (camera.gameplay_pos.x - line.x) * self.size.x / BASE_SIZE.x;
+        Vec3::new(
+            line.x + offset,
+            camera.gameplay_pos.y,
+            camera.gameplay_pos.z,
+        )
+    }
+
+    // the menu view sits beside the gameplay view with the authored gap
+    // between them, whatever the width of either
+    pub fn menu_pos(&self, camera: &CameraConfig, line: &DefenseLine) -> Vec3 {
+        let authored = camera.gameplay_pos.x - camera.menu_pos.x;
+        let gap = authored.abs() - BASE_SIZE.x;
+        let gameplay = self.gameplay_pos(camera, line);
+        Vec3::new(
+            gameplay.x - (self.size.x + gap) * authored.signum(),
+            camera.menu_pos.y,
+            camera.menu_pos.z,
+        )
+    }
+
+    pub fn title_pos(&self, camera: &CameraConfig, line: &DefenseLine) -> Vec3 {
+        Vec3::new(
+            self.menu_pos(camera, line).x,
+            BASE_TITLE_Y * self.size.y / BASE_SIZE.y,
+            0.0,
+        )
+    }
+}
+
+impl Plugin for LayoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScreenLayout>()
+            .add_systems(PreStartup, (fit_defense_line, fit_layout).chain())
+            .add_systems(
+                Update,
+                (
+                    fit_defense_line.run_if(resource_changed::<PlayfieldOrientation>()),
+                    fit_layout,
+                    reflow_layout.run_if(
+                        resource_changed::<ScreenLayout>()
+                            .or_else(resource_changed::<DefenseLine>()),
+                    ),
+                    move_defense_line.run_if(resource_changed::<DefenseLine>()),
+                )
+                    .chain(),
+            );
+    }
+}
+
+// the line sits on the far side of the lane from where enemies arrive, so
+// mirroring the playfield moves it and the view that frames it
+fn fit_defense_line(
+    camera: Res<CameraConfig>,
+    bounds: Res<PlayfieldBounds>,
+    orientation: Res<PlayfieldOrientation>,
+    layout: Res<ScreenLayout>,
+    mut line: ResMut<DefenseLine>,
+    mut gameplay_start: ResMut<GameplayStart>,
+) {
+    *line = DefenseLine::new(camera.gameplay_pos.x, &bounds, *orientation);
+    gameplay_start.camera_endpos = layout.gameplay_pos(&camera, &line);
+}
+
+fn fit_layout(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera: Res<CameraConfig>,
+    line: Res<DefenseLine>,
+    mut layout: ResMut<ScreenLayout>,
+    mut gameplay_start: ResMut<GameplayStart>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let size = Vec2::new(window.width(), window.height());
+    if layout.size != size {
+        layout.size = size;
+        gameplay_start.camera_endpos = layout.gameplay_pos(&camera, &line);
+    }
+}
+
+// the player only stands at the menu position until the run starts, after
+// that it is walking or fighting and gets left alone
+fn reflow_layout(
+    layout: Res<ScreenLayout>,
+    camera: Res<CameraConfig>,
+    line: Res<DefenseLine>,
+    state: Res<State<GameState>>,
+    gameplay_start: Res<GameplayStart>,
+    mut cameras: Query<&mut Transform, With<Camera2d>>,
+    mut titles: Query<&mut Transform, (With<TitleText>, Without<Camera2d>)>,
+    mut players: Query<
+        (&mut Transform, &mut Interpolated),
+        (With<Player>, Without<Camera2d>, Without<TitleText>),
+    >,
+) {
+    let menu_x = layout.menu_pos(&camera, &line).x;
+    let camera_x = match state.get() {
+        GameState::Loading | GameState::Waiting | GameState::MainMenu => Some(menu_x),
+        GameState::TransitionToGamePlay if !gameplay_start.camera_inplace => None,
+        _ => Some(gameplay_start.camera_endpos.x),
+    };
+    if let Some(x) = camera_x {
+        for mut transform in &mut cameras {
+            transform.translation.x = x;
+        }
+    }
+    for mut transform in &mut titles {
+        transform.translation = layout.title_pos(&camera, &line);
+    }
+    if *state.get() == GameState::MainMenu {
+        for (mut transform, mut interpolated) in &mut players {
+            transform.translation.x = menu_x;
+            *interpolated = Interpolated::new(transform.translation);
+        }
+    }
+}
+
+fn move_defense_line(
+    line: Res<DefenseLine>,
+    mut sprites: Query<&mut Transform, With<DefenseLineSprite>>,
+) {
+    for mut transform in &mut sprites {
+        transform.translation.x = line.x;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{advance, enter_state, headless_app};
+
+    fn title_at(app: &mut App, window: Entity, width: f32, height: f32) -> Vec3 {
+        let mut window = app.world.get_mut::<Window>(window).unwrap();
+        window.resolution.set(width, height);
+        advance(app, 1);
+        app.world
+            .query_filtered::<&Transform, With<TitleText>>()
+            .single(&app.world)
+            .translation
+    }
+
+    #[test]
+    fn title_follows_the_window_shape() {
+        let mut app = headless_app();
+        app.add_plugins(LayoutPlugin);
+        enter_state(&mut app, GameState::MainMenu);
+        let window = app.world.spawn((Window::default(), PrimaryWindow)).id();
+        app.world.spawn((Transform::default(), TitleText));
+
+        let base = title_at(&mut app, window, BASE_SIZE.x, BASE_SIZE.y);
+        assert_eq!(base.y, BASE_TITLE_Y);
+
+        // wider only moves it across, taller only moves it up
+        let wide = title_at(&mut app, window, BASE_SIZE.x * 2.0, BASE_SIZE.y);
+        assert_ne!(wide.x, base.x);
+        assert_eq!(wide.y, base.y);
+        let tall = title_at(&mut app, window, BASE_SIZE.x, BASE_SIZE.y * 1.5);
+        assert_eq!(tall.x, base.x);
+        assert_eq!(tall.y, BASE_TITLE_Y * 1.5);
+    }
+}
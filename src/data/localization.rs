@@ -0,0 +1,177 @@
+use bevy::{asset::LoadState, prelude::*, utils::HashMap};
+use serde::Deserialize;
+
+use crate::data::{json::*, settings::Language};
+
+pub const LANGUAGES: [&str; 2] = ["en", "fr"];
+
+// used for any key a string table leaves out, so a partial translation still
+// reads sensibly
+const ENGLISH: &[(&str, &str)] = &[
+    ("title", "Hold The Line"),
+    ("language_name", "English"),
+    ("on", "On"),
+    ("off", "Off"),
+    ("menu_continue", "Continue"),
+    ("menu_start", "Start"),
+    ("menu_waves", "Waves"),
+    ("menu_time_attack", "Time Attack"),
+    ("menu_villagers", "Protect the Village"),
+    ("menu_invert_vertical", "Invert Vertical"),
+    ("menu_aim_assist", "Aim Assist"),
+    ("menu_text_scale", "Text Size"),
+    ("menu_language", "Language"),
+    ("menu_endless", "Endless Waves"),
+    ("seed", "Seed"),
+    ("seed_random", "Random"),
+    ("menu_quit", "Quit"),
+    ("hud_entities_spawned", "Entities Spawned"),
+    ("hud_player_life", "Player Life"),
+    ("hud_lives", "Lives"),
+    ("hud_time", "Time"),
+    ("hud_enemies_left", "Enemies left"),
+    ("paused", "Paused\n[Q] Save & Quit"),
+    ("game_over_prompt", "Press any key to quit"),
+    ("achievement_unlocked", "Achievement unlocked"),
+    ("achievement_kills", "Kill 100 enemies"),
+    ("achievement_survive", "Survive 2 minutes"),
+    (
+        "achievement_no_damage_wave",
+        "Clear a wave without taking damage",
+    ),
+];
+
+pub struct LocalizationPlugin;
+
+#[derive(Asset, Resource, TypePath, Debug, Deserialize, Clone, Default)]
+#[serde(transparent)]
+pub struct Localization {
+    strings: HashMap<String, String>,
+}
+
+impl Localization {
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .get(key)
+            .map(String::as_str)
+            .or_else(|| {
+                ENGLISH
+                    .iter()
+                    .find(|(english_key, _)| *english_key == key)
+                    .map(|(_, value)| *value)
+            })
+            .unwrap_or(key)
+    }
+}
+
+#[derive(Resource, Default)]
+struct LocalizationHandle {
+    handle: Handle<Localization>,
+    resolved: bool,
+}
+
+// text that is only a looked up string, redone whenever the language changes
+#[derive(Component)]
+pub struct LocalizedText(pub &'static str);
+
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(JsonPlugin::<Localization> {
+            extensions: vec!["strings.json"],
+            ..default()
+        })
+        .init_resource::<Localization>()
+        .init_resource::<LocalizationHandle>()
+        .add_systems(
+            Update,
+            (
+                load_strings.run_if(resource_changed::<Language>()),
+                resolve_strings,
+                relocalize_texts.run_if(resource_changed::<Localization>()),
+            )
+                .chain(),
+        );
+    }
+}
+
+fn load_strings(
+    asset_server: Res<AssetServer>,
+    language: Res<Language>,
+    mut handle: ResMut<LocalizationHandle>,
+) {
+    handle.handle = asset_server.load(format!("{}.strings.json", language.0));
+    handle.resolved = false;
+}
+
+fn resolve_strings(
+    asset_server: Res<AssetServer>,
+    tables: Res<Assets<Localization>>,
+    mut handle: ResMut<LocalizationHandle>,
+    mut localization: ResMut<Localization>,
+) {
+    if handle.resolved {
+        return;
+    }
+    if let Some(loaded) = tables.get(&handle.handle) {
+        *localization = loaded.clone();
+        handle.resolved = true;
+    } else if asset_server.get_load_state(&handle.handle) == Some(LoadState::Failed) {
+        warn!("failed to load strings, using the built-in English ones");
+        *localization = Localization::default();
+        handle.resolved = true;
+    }
+}
+
+fn relocalize_texts(
+    localization: Res<Localization>,
+    mut texts: Query<(&LocalizedText, &mut Text)>,
+) {
+    for (localized, mut text) in &mut texts {
+        text.sections[0].value = localization.get(localized.0).to_string();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::testing::{advance, headless_app};
+
+    // string tables come off disk, so wait for the title to become `expected`
+    fn title_becomes(app: &mut App, title: Entity, expected: &str) -> bool {
+        for _ in 0..600 {
+            advance(app, 1);
+            if app.world.get::<Text>(title).unwrap().sections[0].value == expected {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        false
+    }
+
+    #[test]
+    fn switching_language_changes_the_title() {
+        let mut app = headless_app();
+        app.insert_resource(Language("en".to_string()))
+            .add_plugins(LocalizationPlugin);
+        let title = app
+            .world
+            .spawn((
+                Text::from_section("", TextStyle::default()),
+                LocalizedText("title"),
+            ))
+            .id();
+        assert!(title_becomes(&mut app, title, "Hold The Line"));
+
+        app.world.resource_mut::<Language>().0 = "fr".to_string();
+        assert!(title_becomes(&mut app, title, "Tenez la ligne"));
+    }
+
+    #[test]
+    fn a_missing_key_falls_back_to_english_then_the_key() {
+        let localization = Localization::default();
+        assert_eq!(localization.get("title"), "Hold The Line");
+        assert_eq!(localization.get("no_such_key"), "no_such_key");
+    }
+}
@@ -1,2 +1,10 @@
 pub mod json;
+pub mod layout;
+pub mod localization;
+pub mod mode;
+pub mod persist;
+pub mod playfield;
+pub mod rng;
+pub mod settings;
 pub mod state;
+pub mod theme;
@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+const GLASS_CANNON_MAX_HEALTH: i32 = 3;
+const GLASS_CANNON_PIERCE_MULT: i32 = 2;
+pub const VILLAGER_LIMIT: i32 = 10;
+
+#[derive(Resource, Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameMode {
+    pub waves: bool,
+    pub villager_limit: Option<i32>,
+    pub time_attack: Option<f32>,
+}
+
+impl GameMode {
+    pub fn villagers_exhausted(&self, villagers_lost: i32) -> bool {
+        self.villager_limit
+            .is_some_and(|limit| villagers_lost >= limit)
+    }
+}
+
+// picked on the menu for the next run only, cleared whenever the menu opens
+#[derive(Resource, Default)]
+pub struct RunModifiers {
+    pub glass_cannon: bool,
+}
+
+impl RunModifiers {
+    // caps rather than scales, so it never leaves the hero better off than
+    // whatever the run would have started with
+    pub fn max_health(&self, base: i32) -> i32 {
+        if self.glass_cannon {
+            base.min(GLASS_CANNON_MAX_HEALTH)
+        } else {
+            base
+        }
+    }
+
+    pub fn pierce(&self, base: i32) -> i32 {
+        if self.glass_cannon {
+            base * GLASS_CANNON_PIERCE_MULT
+        } else {
+            base
+        }
+    }
+}
+
+pub fn waves_enabled(mode: Res<GameMode>) -> bool {
+    mode.waves
+}
@@ -0,0 +1,55 @@
+use std::{fs, path::PathBuf};
+
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+const SAVE_DIR: &str = "saves";
+
+#[cfg(not(test))]
+fn save_dir() -> PathBuf {
+    PathBuf::from(SAVE_DIR)
+}
+
+// tests never see an earlier run's files or the ones a real game left behind,
+// systems run on the shared task pool so this can't be narrowed to one test
+#[cfg(test)]
+fn save_dir() -> PathBuf {
+    std::env::temp_dir()
+        .join("holdtheline")
+        .join(std::process::id().to_string())
+        .join(SAVE_DIR)
+}
+
+fn save_path(name: &str) -> PathBuf {
+    save_dir().join(name)
+}
+
+pub fn load<T: DeserializeOwned + Default>(name: &str) -> T {
+    let Ok(bytes) = fs::read(save_path(name)) else {
+        return T::default();
+    };
+    serde_json::from_slice(&bytes).unwrap_or_else(|err| {
+        warn!("could not parse {}, using defaults: {}", name, err);
+        T::default()
+    })
+}
+
+pub fn exists(name: &str) -> bool {
+    save_path(name).exists()
+}
+
+pub fn delete(name: &str) {
+    if let Err(err) = fs::remove_file(save_path(name)) {
+        warn!("could not delete {}: {}", name, err);
+    }
+}
+
+pub fn save<T: Serialize>(name: &str, value: &T) {
+    let result = fs::create_dir_all(save_dir()).and_then(|_| {
+        let json = serde_json::to_vec_pretty(value)?;
+        fs::write(save_path(name), json)
+    });
+    if let Err(err) = result {
+        warn!("could not save {}: {}", name, err);
+    }
+}
@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+const PLAYER_LINE_OFFSET: f32 = 100.0;
+const BASE_MARGIN_X: f32 = 450.0;
+const OFFSCREEN_PADDING: f32 = 50.0;
+
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayfieldOrientation {
+    #[default]
+    FacingRight,
+    FacingLeft,
+}
+
+impl PlayfieldOrientation {
+    pub fn sign(&self) -> f32 {
+        match self {
+            PlayfieldOrientation::FacingRight => 1.0,
+            PlayfieldOrientation::FacingLeft => -1.0,
+        }
+    }
+
+    pub fn flip_x(&self) -> bool {
+        *self == PlayfieldOrientation::FacingLeft
+    }
+
+    pub fn flipped(&self) -> Self {
+        match self {
+            PlayfieldOrientation::FacingRight => PlayfieldOrientation::FacingLeft,
+            PlayfieldOrientation::FacingLeft => PlayfieldOrientation::FacingRight,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct PlayfieldBounds {
+    pub min_y: f32,
+    pub max_y: f32,
+    pub margin_x: f32,
+}
+
+impl PlayfieldBounds {
+    pub fn clamp_y(&self, y: f32) -> f32 {
+        y.clamp(self.min_y, self.max_y)
+    }
+
+    pub fn depth(&self, y: f32) -> f32 {
+        ((self.max_y - y) / (self.max_y - self.min_y)).clamp(0.0, 1.0)
+    }
+
+    pub fn spawn_x(&self, center_x: f32, orientation: PlayfieldOrientation) -> f32 {
+        center_x + self.margin_x * orientation.sign()
+    }
+
+    pub fn exit_x(&self, center_x: f32, orientation: PlayfieldOrientation) -> f32 {
+        center_x - self.margin_x * orientation.sign()
+    }
+
+    pub fn has_crossed(&self, x: f32, center_x: f32, orientation: PlayfieldOrientation) -> bool {
+        (x - self.exit_x(center_x, orientation)) * orientation.sign() <= 0.0
+    }
+
+    // centre of one of `slots` equal bands across the lane, so enemies placed
+    // in different slots never start on top of each other
+    pub fn lane_slot(&self, slot: usize, slots: usize) -> f32 {
+        let band = (self.max_y - self.min_y) / slots.max(1) as f32;
+        self.min_y + band * (slot as f32 + 0.5)
+    }
+
+    pub fn fit_to_view(&mut self, half_width: f32) {
+        self.margin_x = BASE_MARGIN_X.max(half_width + OFFSCREEN_PADDING);
+    }
+
+    pub fn has_escaped(&self, x: f32, center_x: f32, orientation: PlayfieldOrientation) -> bool {
+        (x - self.spawn_x(center_x, orientation)) * orientation.sign() > 0.0
+    }
+}
+
+impl Default for PlayfieldBounds {
+    fn default() -> Self {
+        Self {
+            min_y: -250.0,
+            max_y: 250.0,
+            margin_x: BASE_MARGIN_X,
+        }
+    }
+}
+
+// the faint marker drawn along the line, moved with it
+#[derive(Component)]
+pub struct DefenseLineSprite;
+
+#[derive(Resource)]
+pub struct DefenseLine {
+    pub x: f32,
+}
+
+impl DefenseLine {
+    pub fn new(center_x: f32, bounds: &PlayfieldBounds, orientation: PlayfieldOrientation) -> Self {
+        Self {
+            x: bounds.exit_x(center_x, orientation),
+        }
+    }
+
+    pub fn has_crossed(&self, x: f32, orientation: PlayfieldOrientation) -> bool {
+        (x - self.x) * orientation.sign() <= 0.0
+    }
+
+    pub fn player_x(&self, orientation: PlayfieldOrientation) -> f32 {
+        self.x + PLAYER_LINE_OFFSET * orientation.sign()
+    }
+}
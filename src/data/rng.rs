@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+// gameplay randomness goes through one seeded generator so a recorded demo
+// replays the same spawns and drops
+#[derive(Resource)]
+pub struct GameRng {
+    pub seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+}
+
+// an empty or unreadable entry means the run gets a random seed
+pub fn parse_seed(entry: &str) -> Option<u64> {
+    entry.trim().parse().ok()
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::from_seed(rand::thread_rng().gen())
+    }
+}
@@ -0,0 +1,330 @@
+use std::time::Duration;
+
+use bevy::{app::AppExit, prelude::*, window::PrimaryWindow};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::{
+        persist,
+        playfield::{PlayfieldBounds, PlayfieldOrientation},
+        theme::TextScale,
+    },
+    effects::EffectBudget,
+};
+
+const SETTINGS_FILE: &str = "settings.json";
+const MIN_ZOOM: f32 = 0.5;
+const MAX_ZOOM: f32 = 2.0;
+// the menu only offers zooming out, closer in would crop the menu itself
+const ZOOM_STEPS: [f32; 4] = [1.0, 1.25, 1.5, 2.0];
+const SAVE_DEBOUNCE_SECS: f32 = 0.5;
+
+pub struct SettingsPlugin;
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub invert_vertical: bool,
+    pub text_scale: f32,
+    pub zoom: f32,
+    pub aim_assist: bool,
+    pub language: String,
+    pub endless: bool,
+    pub max_effects: usize,
+    pub difficulty: Difficulty,
+    pub orientation: PlayfieldOrientation,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            invert_vertical: false,
+            text_scale: 1.0,
+            zoom: 1.0,
+            aim_assist: false,
+            language: "en".to_string(),
+            endless: false,
+            max_effects: EffectBudget::default().max,
+            difficulty: Difficulty::default(),
+            orientation: PlayfieldOrientation::default(),
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct InvertVertical(pub bool);
+
+#[derive(Resource, Default)]
+pub struct AimAssist(pub bool);
+
+#[derive(Resource)]
+pub struct Language(pub String);
+
+// keep generating waves after the authored ones run out
+#[derive(Resource, Default)]
+pub struct Endless(pub bool);
+
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn next(&self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Easy,
+        }
+    }
+
+    pub fn name_key(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "difficulty_easy",
+            Difficulty::Normal => "difficulty_normal",
+            Difficulty::Hard => "difficulty_hard",
+        }
+    }
+
+    // how much quicker the camera pans and the hero walks in before a run
+    pub fn transition_speed(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.8,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.5,
+        }
+    }
+}
+
+// settings are written once they have stopped changing for a moment, so
+// holding a key on a slider doesn't rewrite the file every frame
+#[derive(Resource)]
+struct PendingSave {
+    timer: Timer,
+    pending: bool,
+}
+
+impl Default for PendingSave {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(SAVE_DEBOUNCE_SECS, TimerMode::Once),
+            pending: false,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct ZoomSetting(pub f32);
+
+impl ZoomSetting {
+    pub fn scale(&self) -> f32 {
+        self.0.clamp(MIN_ZOOM, MAX_ZOOM)
+    }
+
+    // the next step out from wherever the setting is, wrapping to the closest
+    pub fn next_step(&self) -> f32 {
+        ZOOM_STEPS
+            .into_iter()
+            .find(|step| *step > self.0)
+            .unwrap_or(ZOOM_STEPS[0])
+    }
+}
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        let settings = persist::load::<Settings>(SETTINGS_FILE);
+        app.insert_resource(InvertVertical(settings.invert_vertical))
+            .insert_resource(TextScale {
+                setting: settings.text_scale,
+            })
+            .insert_resource(ZoomSetting(settings.zoom))
+            .insert_resource(AimAssist(settings.aim_assist))
+            .insert_resource(Language(settings.language))
+            .insert_resource(Endless(settings.endless))
+            .insert_resource(settings.difficulty)
+            .insert_resource(settings.orientation)
+            .insert_resource(EffectBudget {
+                max: settings.max_effects,
+            })
+            .init_resource::<PendingSave>()
+            .add_systems(Update, apply_zoom.run_if(resource_changed::<ZoomSetting>()))
+            .add_systems(Last, save_settings)
+            .add_systems(
+                Update,
+                schedule_save.run_if(
+                    edited::<InvertVertical>
+                        .or_else(edited::<TextScale>)
+                        .or_else(edited::<ZoomSetting>)
+                        .or_else(edited::<AimAssist>)
+                        .or_else(edited::<Language>)
+                        .or_else(edited::<Endless>)
+                        .or_else(edited::<EffectBudget>)
+                        .or_else(edited::<Difficulty>)
+                        .or_else(edited::<PlayfieldOrientation>),
+                ),
+            );
+    }
+}
+
+// zooming out shows more of the lane, so the spawn margin grows with the view
+// to keep enemies from popping in on screen
+fn apply_zoom(
+    zoom: Res<ZoomSetting>,
+    mut bounds: ResMut<PlayfieldBounds>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut projections: Query<&mut OrthographicProjection, With<Camera2d>>,
+) {
+    for mut projection in &mut projections {
+        projection.scale = zoom.scale();
+    }
+    if let Ok(window) = windows.get_single() {
+        bounds.fit_to_view(window.width() / 2.0 * zoom.scale());
+    }
+}
+
+impl PendingSave {
+    fn schedule(&mut self) {
+        self.pending = true;
+        self.timer.reset();
+    }
+
+    // true once, when a pending save has been quiet for the whole debounce
+    fn ready(&mut self, delta: Duration, exiting: bool) -> bool {
+        if !self.pending || !(self.timer.tick(delta).finished() || exiting) {
+            return false;
+        }
+        self.pending = false;
+        true
+    }
+}
+
+// inserting the loaded values counts as a change too, that isn't worth a save
+fn edited<T: Resource>(setting: Res<T>) -> bool {
+    setting.is_changed() && !setting.is_added()
+}
+
+fn schedule_save(mut save: ResMut<PendingSave>) {
+    save.schedule();
+}
+
+// quitting inside the debounce window still writes the latest values
+fn save_settings(
+    time: Res<Time<Real>>,
+    mut exit: EventReader<AppExit>,
+    mut save: ResMut<PendingSave>,
+    invert_vertical: Res<InvertVertical>,
+    text_scale: Res<TextScale>,
+    zoom: Res<ZoomSetting>,
+    aim_assist: Res<AimAssist>,
+    language: Res<Language>,
+    endless: Res<Endless>,
+    effect_budget: Res<EffectBudget>,
+    difficulty: Res<Difficulty>,
+    orientation: Res<PlayfieldOrientation>,
+) {
+    let exiting = exit.read().next().is_some();
+    if !save.ready(time.delta(), exiting) {
+        return;
+    }
+    persist::save(
+        SETTINGS_FILE,
+        &Settings {
+            invert_vertical: invert_vertical.0,
+            text_scale: text_scale.setting,
+            zoom: zoom.0,
+            aim_assist: aim_assist.0,
+            language: language.0.clone(),
+            endless: endless.0,
+            max_effects: effect_budget.max,
+            difficulty: *difficulty,
+            orientation: *orientation,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{advance, advance_secs, headless_app};
+
+    const STEP: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn changes_in_quick_succession_save_once() {
+        let mut save = PendingSave::default();
+        let mut saves = 0;
+        for frame in 0..20 {
+            // a slider dragged for the first second
+            if frame < 10 {
+                save.schedule();
+            }
+            saves += save.ready(STEP, false) as usize;
+        }
+        assert_eq!(saves, 1);
+    }
+
+    #[test]
+    fn nothing_is_saved_before_the_debounce_ends() {
+        let mut save = PendingSave::default();
+        save.schedule();
+        assert!(!save.ready(STEP, false));
+        assert!(save.ready(Duration::from_secs_f32(SAVE_DEBOUNCE_SECS), false));
+    }
+
+    #[test]
+    fn exiting_flushes_a_pending_save() {
+        let mut save = PendingSave::default();
+        assert!(!save.ready(STEP, true));
+        save.schedule();
+        assert!(save.ready(STEP, true));
+        assert!(!save.ready(STEP, true));
+    }
+
+    #[test]
+    fn zoom_scales_the_view_and_the_spawn_margin() {
+        let mut app = headless_app();
+        app.insert_resource(ZoomSetting(1.0))
+            .add_systems(Update, apply_zoom.run_if(resource_changed::<ZoomSetting>()));
+        let window = app.world.spawn((Window::default(), PrimaryWindow)).id();
+        let camera = app.world.spawn(Camera2dBundle::default()).id();
+        advance(&mut app, 1);
+        let margin = app.world.resource::<PlayfieldBounds>().margin_x;
+
+        let mut zoom = app.world.resource_mut::<ZoomSetting>();
+        zoom.0 = zoom.next_step();
+        let scale = zoom.scale();
+        advance(&mut app, 1);
+
+        assert!(scale > 1.0);
+        let projection = app.world.get::<OrthographicProjection>(camera).unwrap();
+        assert_eq!(projection.scale, scale);
+        let half_width = app.world.get::<Window>(window).unwrap().width() / 2.0;
+        let bounds = app.world.resource::<PlayfieldBounds>();
+        assert!(bounds.margin_x > margin);
+        assert!(bounds.margin_x > half_width * scale);
+    }
+
+    #[test]
+    fn an_edited_setting_is_written_out_after_the_debounce() {
+        let mut app = headless_app();
+        app.add_plugins(SettingsPlugin);
+        // loading the settings is not an edit, nothing gets written for it
+        advance_secs(&mut app, SAVE_DEBOUNCE_SECS * 2.0);
+        assert!(!persist::exists(SETTINGS_FILE));
+
+        app.world.resource_mut::<AimAssist>().0 = true;
+        advance(&mut app, 1);
+        assert!(app.world.resource::<PendingSave>().pending);
+        assert!(!persist::exists(SETTINGS_FILE));
+
+        advance_secs(&mut app, SAVE_DEBOUNCE_SECS + 0.1);
+        assert!(!app.world.resource::<PendingSave>().pending);
+        let saved = persist::load::<Settings>(SETTINGS_FILE);
+        assert!(saved.aim_assist);
+        assert!(!saved.invert_vertical);
+    }
+}
@@ -13,17 +13,119 @@ pub enum GameState {
     Cleanup,
 }
 
+// what moves the game from one state to the next. systems ask for the one
+// they mean instead of assuming which state comes after theirs
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Transition {
+    DataLoaded,
+    ImagesLoaded,
+    StartRun,
+    IntroFinished,
+    Pause,
+    Resume,
+    Cleanup,
+    ReturnToMenu,
+}
+
+const TRANSITIONS: [(GameState, Transition, GameState); 8] = [
+    (
+        GameState::Loading,
+        Transition::DataLoaded,
+        GameState::Waiting,
+    ),
+    (
+        GameState::Waiting,
+        Transition::ImagesLoaded,
+        GameState::MainMenu,
+    ),
+    (
+        GameState::MainMenu,
+        Transition::StartRun,
+        GameState::TransitionToGamePlay,
+    ),
+    (
+        GameState::TransitionToGamePlay,
+        Transition::IntroFinished,
+        GameState::GamePlay,
+    ),
+    (GameState::GamePlay, Transition::Pause, GameState::Pause),
+    (GameState::Pause, Transition::Resume, GameState::GamePlay),
+    (GameState::GameOver, Transition::Cleanup, GameState::Cleanup),
+    (
+        GameState::Cleanup,
+        Transition::ReturnToMenu,
+        GameState::MainMenu,
+    ),
+];
+
 impl GameState {
-    pub fn transition(&self) -> Self {
-        match self {
-            GameState::Loading => GameState::Waiting,
-            GameState::Waiting => GameState::MainMenu,
-            GameState::MainMenu => GameState::TransitionToGamePlay,
-            GameState::TransitionToGamePlay => GameState::GamePlay,
-            GameState::GamePlay => GameState::Pause,
-            GameState::Pause => GameState::GamePlay,
-            GameState::GameOver => GameState::Cleanup,
-            GameState::Cleanup => GameState::MainMenu,
+    // None when `transition` doesn't lead anywhere from this state
+    pub fn after(&self, transition: Transition) -> Option<Self> {
+        TRANSITIONS
+            .iter()
+            .find(|(from, via, _)| from == self && *via == transition)
+            .map(|(_, _, to)| *to)
+    }
+}
+
+// queues the move, or warns and stays put if it isn't allowed from here
+pub fn advance(
+    state: &State<GameState>,
+    next_state: &mut NextState<GameState>,
+    transition: Transition,
+) {
+    match state.after(transition) {
+        Some(next) => next_state.set(next),
+        None => warn!(
+            "no {:?} transition out of {:?}, staying put",
+            transition,
+            state.get()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::testing::{advance as advance_frames, headless_app};
+
+    #[test]
+    fn each_transition_leads_where_it_says() {
+        use GameState::*;
+        for (from, transition, to) in [
+            (Loading, Transition::DataLoaded, Waiting),
+            (Waiting, Transition::ImagesLoaded, MainMenu),
+            (MainMenu, Transition::StartRun, TransitionToGamePlay),
+            (TransitionToGamePlay, Transition::IntroFinished, GamePlay),
+            (GamePlay, Transition::Pause, Pause),
+            (Pause, Transition::Resume, GamePlay),
+            (GameOver, Transition::Cleanup, Cleanup),
+            (Cleanup, Transition::ReturnToMenu, MainMenu),
+        ] {
+            assert_eq!(from.after(transition), Some(to), "{from:?} {transition:?}");
         }
+        assert_eq!(MainMenu.after(Transition::Pause), None);
+        assert_eq!(GamePlay.after(Transition::StartRun), None);
+    }
+
+    fn request(app: &mut App, transition: Transition) -> GameState {
+        app.world.run_system_once(
+            move |state: Res<State<GameState>>, mut next_state: ResMut<NextState<GameState>>| {
+                advance(&state, &mut next_state, transition);
+            },
+        );
+        advance_frames(app, 1);
+        *app.world.resource::<State<GameState>>().get()
+    }
+
+    #[test]
+    fn a_transition_that_does_not_apply_stays_put() {
+        let mut app = headless_app();
+        assert_eq!(request(&mut app, Transition::StartRun), GameState::GamePlay);
+        assert_eq!(request(&mut app, Transition::Pause), GameState::Pause);
+        assert_eq!(request(&mut app, Transition::Pause), GameState::Pause);
+        assert_eq!(request(&mut app, Transition::Resume), GameState::GamePlay);
     }
 }
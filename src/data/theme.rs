@@ -0,0 +1,217 @@
+use bevy::{asset::LoadState, prelude::*};
+use serde::Deserialize;
+
+use crate::data::json::*;
+
+const MIN_TEXT_SCALE: f32 = 0.5;
+const MAX_TEXT_SCALE: f32 = 3.0;
+// what the menu steps through, a hand edited settings file can still pick
+// anything between the limits
+const TEXT_SCALE_STEPS: [f32; 5] = [0.75, 1.0, 1.25, 1.5, 2.0];
+
+pub struct ThemePlugin;
+
+#[derive(Asset, Resource, TypePath, Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct Theme {
+    pub title_color: [f32; 3],
+    pub hud_color: [f32; 3],
+    pub font: String,
+    pub title_font_size: f32,
+    pub hud_font_size: f32,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            title_color: [1.0, 1.0, 0.0],
+            hud_color: [1.0, 1.0, 1.0],
+            font: "fonts/plop.ttf".to_string(),
+            title_font_size: 99.0,
+            hud_font_size: 25.0,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct TextScale {
+    pub setting: f32,
+}
+
+impl Default for TextScale {
+    fn default() -> Self {
+        Self { setting: 1.0 }
+    }
+}
+
+impl TextScale {
+    pub fn factor(&self) -> f32 {
+        self.setting.clamp(MIN_TEXT_SCALE, MAX_TEXT_SCALE)
+    }
+
+    pub fn font_size(&self, base: f32) -> f32 {
+        base * self.factor()
+    }
+
+    // the next step up from wherever the setting is, wrapping to the smallest
+    pub fn next_step(&self) -> f32 {
+        TEXT_SCALE_STEPS
+            .into_iter()
+            .find(|step| *step > self.setting)
+            .unwrap_or(TEXT_SCALE_STEPS[0])
+    }
+}
+
+impl Theme {
+    pub fn title_style(&self, asset_server: &AssetServer, scale: &TextScale) -> TextStyle {
+        let [r, g, b] = self.title_color;
+        TextStyle {
+            font: asset_server.load(self.font.clone()),
+            font_size: scale.font_size(self.title_font_size),
+            color: Color::rgb(r, g, b),
+        }
+    }
+
+    pub fn hud_style(&self, asset_server: &AssetServer) -> TextStyle {
+        let [r, g, b] = self.hud_color;
+        TextStyle {
+            font: asset_server.load(self.font.clone()),
+            font_size: self.hud_font_size,
+            color: Color::rgb(r, g, b),
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+struct ThemeHandle {
+    handle: Handle<Theme>,
+    resolved: bool,
+}
+
+#[derive(Component)]
+pub struct TitleText;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(JsonPlugin::<Theme> {
+            extensions: vec!["theme.json"],
+            ..default()
+        })
+        .init_resource::<Theme>()
+        .init_resource::<TextScale>()
+        .init_resource::<ThemeHandle>()
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                load_theme,
+                sync_ui_scale.run_if(resource_changed::<TextScale>()),
+                apply_title_theme
+                    .run_if(resource_changed::<Theme>().or_else(resource_changed::<TextScale>())),
+            )
+                .chain(),
+        );
+    }
+}
+
+fn setup(mut theme_handle: ResMut<ThemeHandle>, asset_server: Res<AssetServer>) {
+    theme_handle.handle = asset_server.load("default.theme.json");
+}
+
+fn load_theme(
+    asset_server: Res<AssetServer>,
+    themes: Res<Assets<Theme>>,
+    mut theme_handle: ResMut<ThemeHandle>,
+    mut theme: ResMut<Theme>,
+) {
+    if theme_handle.resolved {
+        return;
+    }
+    if let Some(loaded) = themes.get(&theme_handle.handle) {
+        *theme = loaded.clone();
+        theme_handle.resolved = true;
+    } else if asset_server.get_load_state(&theme_handle.handle) == Some(LoadState::Failed) {
+        warn!("failed to load theme, using the built-in one");
+        theme_handle.resolved = true;
+    }
+}
+
+// UI nodes (the HUD) are laid out again by bevy when UiScale changes, world
+// space text like the title is resized by hand. bevy multiplies UiScale by the
+// window's scale factor itself, so a HiDPI screen is already taken care of and
+// folding it in here as well would scale everything twice
+fn sync_ui_scale(text_scale: Res<TextScale>, mut ui_scale: ResMut<UiScale>) {
+    ui_scale.0 = text_scale.factor() as f64;
+}
+
+fn apply_title_theme(
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    text_scale: Res<TextScale>,
+    mut titles: Query<&mut Text, With<TitleText>>,
+) {
+    for mut text in &mut titles {
+        for section in text.sections.iter_mut() {
+            section.style = theme.title_style(&asset_server, &text_scale);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{advance, headless_app};
+
+    #[test]
+    fn text_scale_steps_up_and_wraps() {
+        let steps: Vec<f32> = TEXT_SCALE_STEPS
+            .iter()
+            .map(|setting| TextScale { setting: *setting }.next_step())
+            .collect();
+        assert_eq!(steps, vec![1.0, 1.25, 1.5, 2.0, 0.75]);
+        // a hand edited value rejoins at the next step
+        assert_eq!(TextScale { setting: 1.1 }.next_step(), 1.25);
+        assert_eq!(TextScale { setting: 2.5 }.next_step(), 0.75);
+    }
+
+    #[test]
+    fn ui_scale_follows_the_text_scale_alone() {
+        let mut app = App::new();
+        app.init_resource::<UiScale>()
+            .insert_resource(TextScale { setting: 1.5 })
+            .add_systems(Update, sync_ui_scale);
+        app.update();
+        assert_eq!(app.world.resource::<UiScale>().0, 1.5);
+    }
+
+    #[test]
+    fn loaded_theme_colors_the_title() {
+        let mut app = headless_app();
+        app.init_asset::<Theme>()
+            .init_resource::<TextScale>()
+            .add_systems(
+                Update,
+                (
+                    load_theme,
+                    apply_title_theme.run_if(resource_changed::<Theme>()),
+                )
+                    .chain(),
+            );
+        let handle = app.world.resource_mut::<Assets<Theme>>().add(Theme {
+            title_color: [0.2, 0.4, 0.6],
+            ..default()
+        });
+        app.insert_resource(ThemeHandle {
+            handle,
+            resolved: false,
+        });
+        let title = app
+            .world
+            .spawn((Text::from_section("title", TextStyle::default()), TitleText))
+            .id();
+        advance(&mut app, 1);
+
+        let text = app.world.get::<Text>(title).unwrap();
+        assert_eq!(text.sections[0].style.color, Color::rgb(0.2, 0.4, 0.6));
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    animation::EnemyAnimations,
+    data::{
+        playfield::{DefenseLine, PlayfieldBounds, PlayfieldOrientation},
+        rng::GameRng,
+        state::GameState,
+    },
+    entities::{
+        collision::is_sensor_started,
+        enemy::{
+            spawn_named_enemy, Enemy, EnemySpawnData, EnemyTypes, ManualSpawn, SpawningEnabled,
+        },
+        player::{GameStats, Player},
+        unlock::Unlocks,
+        wave::WaveState,
+    },
+    GameplayStart,
+};
+
+pub struct DebugPlugin;
+
+#[derive(Resource, Default)]
+pub struct CollisionLogging {
+    pub enabled: bool,
+    pub logged: usize,
+}
+
+#[derive(Resource, Default)]
+pub struct ShowSpawnLanes(pub bool);
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RapierDebugRenderPlugin::default())
+            .init_resource::<CollisionLogging>()
+            .init_resource::<ShowSpawnLanes>()
+            .add_systems(
+                Update,
+                (
+                    toggle_collision_logging,
+                    log_collisions.run_if(collision_logging_enabled),
+                    toggle_spawn_lanes,
+                    draw_spawn_lanes.run_if(spawn_lanes_shown),
+                    toggle_manual_spawn,
+                    toggle_spawning,
+                    practice_spawn.run_if(
+                        in_state(GameState::GamePlay).and_then(|manual: Res<ManualSpawn>| manual.0),
+                    ),
+                ),
+            );
+    }
+}
+
+fn toggle_collision_logging(keys: Res<Input<KeyCode>>, mut logging: ResMut<CollisionLogging>) {
+    if keys.just_pressed(KeyCode::F3) {
+        logging.enabled = !logging.enabled;
+        info!(
+            "collision logging {}",
+            if logging.enabled {
+                "enabled"
+            } else {
+                "disabled"
+            }
+        );
+    }
+}
+
+fn collision_logging_enabled(logging: Res<CollisionLogging>) -> bool {
+    logging.enabled
+}
+
+// gameplay handlers only ever see what is_sensor_started lets through, so
+// those are called out and everything else is logged as is
+fn log_collisions(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut logging: ResMut<CollisionLogging>,
+) {
+    for event in collision_events.read() {
+        match is_sensor_started(event) {
+            Some((a, b)) => info!("sensor collision started: {:?} <-> {:?}", a, b),
+            None => info!("collision: {:?}", event),
+        }
+        logging.logged += 1;
+    }
+}
+
+fn toggle_spawn_lanes(keys: Res<Input<KeyCode>>, mut lanes: ResMut<ShowSpawnLanes>) {
+    if keys.just_pressed(KeyCode::F6) {
+        lanes.0 = !lanes.0;
+    }
+}
+
+fn spawn_lanes_shown(lanes: Res<ShowSpawnLanes>) -> bool {
+    lanes.0
+}
+
+// the edges of the spawn band from where enemies appear to where they leave,
+// with the spawn and exit columns across it
+fn spawn_lane_lines(
+    bounds: &PlayfieldBounds,
+    orientation: PlayfieldOrientation,
+    gameplay_start: &GameplayStart,
+    defense_line: &DefenseLine,
+) -> [(Vec2, Vec2, Color); 4] {
+    let spawn_x = bounds.spawn_x(gameplay_start.camera_endpos.x, orientation);
+    let exit_x = defense_line.x;
+    [
+        (
+            Vec2::new(exit_x, bounds.min_y),
+            Vec2::new(spawn_x, bounds.min_y),
+            Color::YELLOW,
+        ),
+        (
+            Vec2::new(exit_x, bounds.max_y),
+            Vec2::new(spawn_x, bounds.max_y),
+            Color::YELLOW,
+        ),
+        (
+            Vec2::new(spawn_x, bounds.min_y),
+            Vec2::new(spawn_x, bounds.max_y),
+            Color::GREEN,
+        ),
+        (
+            Vec2::new(exit_x, bounds.min_y),
+            Vec2::new(exit_x, bounds.max_y),
+            Color::RED,
+        ),
+    ]
+}
+
+fn draw_spawn_lanes(
+    mut gizmos: Gizmos,
+    bounds: Res<PlayfieldBounds>,
+    orientation: Res<PlayfieldOrientation>,
+    gameplay_start: Res<GameplayStart>,
+    defense_line: Res<DefenseLine>,
+) {
+    for (start, end, color) in
+        spawn_lane_lines(&bounds, *orientation, &gameplay_start, &defense_line)
+    {
+        gizmos.line_2d(start, end, color);
+    }
+}
+
+fn toggle_manual_spawn(keys: Res<Input<KeyCode>>, mut manual: ResMut<ManualSpawn>) {
+    if keys.just_pressed(KeyCode::F7) {
+        manual.0 = !manual.0;
+        info!(
+            "manual spawning {}",
+            if manual.0 { "enabled" } else { "disabled" }
+        );
+    }
+}
+
+// turning spawning back on starts the timers over instead of letting them
+// fire straight away
+fn toggle_spawning(
+    keys: Res<Input<KeyCode>>,
+    mut spawning: ResMut<SpawningEnabled>,
+    mut spawn_data: ResMut<EnemySpawnData>,
+    mut waves: ResMut<WaveState>,
+) {
+    if keys.just_pressed(KeyCode::B) {
+        spawning.0 = !spawning.0;
+        if spawning.0 {
+            spawn_data.reset_timer();
+            waves.timer.reset();
+        }
+        info!(
+            "enemy spawning {}",
+            if spawning.0 { "enabled" } else { "disabled" }
+        );
+    }
+}
+
+// one enemy per press, lined up with the hero so the lane can be picked by
+// walking to it first
+fn practice_spawn(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    bounds: Res<PlayfieldBounds>,
+    orientation: Res<PlayfieldOrientation>,
+    gameplay_start: Res<GameplayStart>,
+    enemy_anims: Res<EnemyAnimations>,
+    enemy_types: Res<EnemyTypes>,
+    unlocks: Res<Unlocks>,
+    mut game_rng: ResMut<GameRng>,
+    mut spawn_data: ResMut<EnemySpawnData>,
+    mut stats: ResMut<GameStats>,
+    player: Query<&Transform, With<Player>>,
+) {
+    if !keys.just_pressed(KeyCode::Return) {
+        return;
+    }
+    // practice spawns ignore the per-type caps
+    let Some(enemy_name) = enemy_types.choose(&unlocks, &HashMap::new(), game_rng.rng()) else {
+        return;
+    };
+    let y = player
+        .get_single()
+        .map_or(0.0, |transform| transform.translation.y)
+        .clamp(bounds.min_y, bounds.max_y);
+    let info = &enemy_types.types[enemy_name];
+    spawn_named_enemy(
+        &mut commands,
+        &enemy_anims,
+        Enemy::new(enemy_name, info),
+        info,
+        Vec3::new(
+            bounds.spawn_x(gameplay_start.camera_endpos.x, *orientation),
+            y,
+            0.0,
+        ),
+        *orientation,
+    );
+    spawn_data.curr_spawned += 1;
+    stats.entites_spawned += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_rapier2d::rapier::geometry::CollisionEventFlags;
+
+    use super::*;
+    use crate::testing::{advance, collide, headless_app, press, release};
+
+    #[test]
+    fn only_enabled_logging_counts_collisions() {
+        let mut app = headless_app();
+        app.init_resource::<CollisionLogging>()
+            .add_systems(Update, log_collisions.run_if(collision_logging_enabled));
+        let a = app.world.spawn_empty().id();
+        let b = app.world.spawn_empty().id();
+        // long enough for the unread event to be dropped
+        collide(&mut app, a, b);
+        advance(&mut app, 10);
+        assert_eq!(app.world.resource::<CollisionLogging>().logged, 0);
+
+        app.world.resource_mut::<CollisionLogging>().enabled = true;
+        collide(&mut app, a, b);
+        app.world
+            .send_event(CollisionEvent::Stopped(a, b, CollisionEventFlags::SENSOR));
+        advance(&mut app, 1);
+        assert_eq!(app.world.resource::<CollisionLogging>().logged, 2);
+    }
+
+    fn band(app: &App) -> (f32, f32) {
+        let lines = spawn_lane_lines(
+            app.world.resource::<PlayfieldBounds>(),
+            *app.world.resource::<PlayfieldOrientation>(),
+            app.world.resource::<GameplayStart>(),
+            app.world.resource::<DefenseLine>(),
+        );
+        let ys = lines.iter().flat_map(|(start, end, _)| [start.y, end.y]);
+        (
+            ys.clone().fold(f32::MAX, f32::min),
+            ys.fold(f32::MIN, f32::max),
+        )
+    }
+
+    #[test]
+    fn spawn_lanes_follow_the_configured_band() {
+        let mut app = headless_app();
+        app.init_resource::<ShowSpawnLanes>()
+            .add_systems(Update, draw_spawn_lanes.run_if(spawn_lanes_shown));
+        app.world.resource_mut::<ShowSpawnLanes>().0 = true;
+        advance(&mut app, 1);
+        assert_eq!(band(&app), (-250.0, 250.0));
+
+        let mut bounds = app.world.resource_mut::<PlayfieldBounds>();
+        bounds.min_y = -100.0;
+        bounds.max_y = 150.0;
+        advance(&mut app, 1);
+        assert_eq!(band(&app), (-100.0, 150.0));
+    }
+
+    fn enemies(app: &mut App) -> usize {
+        app.world.query::<&Enemy>().iter(&app.world).count()
+    }
+
+    #[test]
+    fn each_practice_press_spawns_one_enemy() {
+        let mut app = headless_app();
+        app.insert_resource(ManualSpawn(true)).add_systems(
+            Update,
+            practice_spawn.run_if(|manual: Res<ManualSpawn>| manual.0),
+        );
+        app.world
+            .spawn((Player, Transform::from_xyz(0.0, 40.0, 0.0)));
+        advance(&mut app, 10);
+        assert_eq!(enemies(&mut app), 0);
+
+        press(&mut app, KeyCode::Return);
+        advance(&mut app, 1);
+        release(&mut app, KeyCode::Return);
+        advance(&mut app, 10);
+        assert_eq!(enemies(&mut app), 1);
+    }
+}
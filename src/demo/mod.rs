@@ -0,0 +1,290 @@
+use std::{fs, path::PathBuf, time::Duration};
+
+use bevy::{app::AppExit, input::InputSystem, prelude::*, time::TimeUpdateStrategy};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::rng::GameRng,
+    input::{Action, Actions},
+};
+
+const DEMO_EXTENSION: &str = "demo";
+const RECORD_FLAG: &str = "--record-demo";
+const PLAY_FLAG: &str = "--play-demo";
+const DEMO_ENV: &str = "HOLDTHELINE_DEMO";
+
+pub struct DemoPlugin;
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct DemoFrame {
+    pub delta: f32,
+    pub pressed: Vec<Action>,
+    pub just_pressed: Vec<Action>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Demo {
+    pub seed: u64,
+    pub frames: Vec<DemoFrame>,
+}
+
+#[derive(Resource)]
+pub struct DemoRecorder {
+    path: PathBuf,
+    demo: Demo,
+}
+
+#[derive(Resource)]
+pub struct DemoPlayer {
+    demo: Demo,
+    frame: Option<usize>,
+}
+
+impl DemoPlayer {
+    fn current(&self) -> Option<&DemoFrame> {
+        self.frame.and_then(|frame| self.demo.frames.get(frame))
+    }
+
+    pub fn pressed(&self, action: Action) -> bool {
+        self.current()
+            .is_some_and(|frame| frame.pressed.contains(&action))
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.current()
+            .is_some_and(|frame| frame.just_pressed.contains(&action))
+    }
+}
+
+enum DemoMode {
+    Record(PathBuf),
+    Play(PathBuf),
+}
+
+// `--record-demo <name>` / `--play-demo <name>` on the command line, or
+// HOLDTHELINE_DEMO=record:<name> / play:<name> for builds started elsewhere
+fn demo_mode() -> Option<DemoMode> {
+    let args: Vec<String> = std::env::args().collect();
+    let flag = |name: &str| {
+        args.iter()
+            .position(|arg| arg == name)
+            .and_then(|i| args.get(i + 1))
+            .cloned()
+    };
+    let mode = if let Some(name) = flag(RECORD_FLAG) {
+        DemoMode::Record(demo_path(&name))
+    } else if let Some(name) = flag(PLAY_FLAG) {
+        DemoMode::Play(demo_path(&name))
+    } else {
+        let value = std::env::var(DEMO_ENV).ok()?;
+        match value.split_once(':')? {
+            ("record", name) => DemoMode::Record(demo_path(name)),
+            ("play", name) => DemoMode::Play(demo_path(name)),
+            _ => {
+                warn!("{} should be record:<name> or play:<name>", DEMO_ENV);
+                return None;
+            }
+        }
+    };
+    Some(mode)
+}
+
+fn demo_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(name);
+    if path.extension().is_none() {
+        path.set_extension(DEMO_EXTENSION);
+    }
+    path
+}
+
+fn load_demo(path: &PathBuf) -> Option<Demo> {
+    let bytes = fs::read(path)
+        .map_err(|err| warn!("could not read demo {}: {}", path.display(), err))
+        .ok()?;
+    serde_json::from_slice(&bytes)
+        .map_err(|err| warn!("could not parse demo {}: {}", path.display(), err))
+        .ok()
+}
+
+impl Plugin for DemoPlugin {
+    fn build(&self, app: &mut App) {
+        match demo_mode() {
+            Some(DemoMode::Record(path)) => {
+                let rng = GameRng::default();
+                app.insert_resource(DemoRecorder {
+                    path,
+                    demo: Demo {
+                        seed: rng.seed,
+                        frames: Vec::new(),
+                    },
+                })
+                .insert_resource(rng)
+                .add_systems(PreUpdate, record_frame.after(InputSystem))
+                .add_systems(Last, write_demo);
+            }
+            Some(DemoMode::Play(path)) => {
+                let Some(demo) = load_demo(&path) else {
+                    return;
+                };
+                // replay the recorded frame times too, otherwise the same
+                // inputs land at different points of the run
+                let first_delta = demo.frames.first().map_or(0.0, |frame| frame.delta);
+                app.insert_resource(GameRng::from_seed(demo.seed))
+                    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+                        first_delta,
+                    )))
+                    .insert_resource(DemoPlayer { demo, frame: None })
+                    .add_systems(
+                        PreUpdate,
+                        advance_playback
+                            .after(InputSystem)
+                            .run_if(resource_exists::<DemoPlayer>()),
+                    );
+            }
+            None => {}
+        }
+    }
+}
+
+fn record_frame(time: Res<Time>, actions: Actions, mut recorder: ResMut<DemoRecorder>) {
+    recorder.demo.frames.push(DemoFrame {
+        delta: time.delta_seconds(),
+        pressed: Action::ALL
+            .into_iter()
+            .filter(|action| actions.pressed(*action))
+            .collect(),
+        just_pressed: Action::ALL
+            .into_iter()
+            .filter(|action| actions.just_pressed(*action))
+            .collect(),
+    });
+}
+
+fn write_demo(mut exit: EventReader<AppExit>, recorder: Res<DemoRecorder>) {
+    if exit.read().last().is_none() {
+        return;
+    }
+    let result = serde_json::to_vec(&recorder.demo)
+        .map_err(std::io::Error::from)
+        .and_then(|json| fs::write(&recorder.path, json));
+    match result {
+        Ok(()) => info!("recorded demo to {}", recorder.path.display()),
+        Err(err) => warn!("could not write demo {}: {}", recorder.path.display(), err),
+    }
+}
+
+fn advance_playback(
+    mut commands: Commands,
+    mut player: ResMut<DemoPlayer>,
+    mut strategy: ResMut<TimeUpdateStrategy>,
+) {
+    let frame = player.frame.map_or(0, |frame| frame + 1);
+    if frame >= player.demo.frames.len() {
+        // out of frames, live input and the real clock take over again
+        info!("demo playback finished");
+        commands.remove_resource::<DemoPlayer>();
+        *strategy = TimeUpdateStrategy::Automatic;
+        return;
+    }
+    player.frame = Some(frame);
+    let next_delta = player
+        .demo
+        .frames
+        .get(frame + 1)
+        .map_or(0.0, |next| next.delta);
+    *strategy = TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(next_delta));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{advance, headless_app, press, release};
+
+    const SPEED: f32 = 100.0;
+    const DASH: f32 = 10.0;
+
+    #[derive(Component)]
+    struct Mover;
+
+    // stands in for the hero, moved by whatever Actions reports
+    fn move_by_actions(
+        time: Res<Time>,
+        actions: Actions,
+        mut movers: Query<&mut Transform, With<Mover>>,
+    ) {
+        for mut transform in &mut movers {
+            if actions.pressed(Action::MoveUp) {
+                transform.translation.y += SPEED * time.delta_seconds();
+            } else if actions.pressed(Action::MoveDown) {
+                transform.translation.y -= SPEED * time.delta_seconds();
+            }
+            if actions.just_pressed(Action::Dash) {
+                transform.translation.x += DASH;
+            }
+        }
+    }
+
+    fn mover_app() -> (App, Entity) {
+        let mut app = headless_app();
+        app.add_systems(Update, move_by_actions);
+        let mover = app.world.spawn((Mover, Transform::default())).id();
+        (app, mover)
+    }
+
+    fn position(app: &App, mover: Entity) -> Vec3 {
+        app.world.get::<Transform>(mover).unwrap().translation
+    }
+
+    #[test]
+    fn playing_back_a_recording_retraces_the_same_positions() {
+        let (mut app, mover) = mover_app();
+        app.insert_resource(DemoRecorder {
+            path: PathBuf::new(),
+            demo: Demo::default(),
+        })
+        .add_systems(PreUpdate, record_frame.after(InputSystem));
+        let script = [KeyCode::Up; 10]
+            .into_iter()
+            .chain([KeyCode::ShiftLeft])
+            .chain([KeyCode::Down; 4])
+            .map(Some)
+            .chain([None; 3]);
+        let mut recorded = Vec::new();
+        for key in script {
+            match key {
+                Some(key) => press(&mut app, key),
+                None => release(&mut app, KeyCode::Down),
+            }
+            advance(&mut app, 1);
+            recorded.push(position(&app, mover));
+        }
+        let demo = app.world.remove_resource::<DemoRecorder>().unwrap().demo;
+        let frames = demo.frames.len();
+        assert_eq!(frames, recorded.len());
+
+        let (mut app, mover) = mover_app();
+        let first_delta = Duration::from_secs_f32(demo.frames[0].delta);
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(first_delta))
+            .insert_resource(DemoPlayer { demo, frame: None })
+            .add_systems(
+                PreUpdate,
+                advance_playback
+                    .after(InputSystem)
+                    .run_if(resource_exists::<DemoPlayer>()),
+            );
+        let mut played = Vec::new();
+        for _ in 0..frames {
+            advance(&mut app, 1);
+            played.push(position(&app, mover));
+        }
+        assert_eq!(played, recorded);
+
+        // past the end live input takes over again
+        advance(&mut app, 1);
+        assert!(!app.world.contains_resource::<DemoPlayer>());
+        assert!(matches!(
+            app.world.resource::<TimeUpdateStrategy>(),
+            TimeUpdateStrategy::Automatic
+        ));
+    }
+}
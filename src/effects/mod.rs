@@ -0,0 +1,596 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use rand::prelude::*;
+
+use crate::data::state::GameState;
+
+const SCREEN_FLASH_ALPHA: f32 = 0.35;
+const SHAKE_DECAY: f32 = 2.5;
+const SHAKE_MAX_OFFSET: f32 = 12.0;
+const HIT_FLASH_COLOR: Color = Color::rgb(1.0, 0.35, 0.35);
+const HIT_FLASH_SECONDS: f32 = 0.1;
+const LETTERBOX_Z: i32 = 10;
+const DEFAULT_MAX_EFFECTS: usize = 200;
+
+// gameplay code only flips these triggers, the plugin does the drawing
+pub struct EffectsPlugin;
+
+#[derive(Resource)]
+pub struct FlashScreen {
+    pub color: Color,
+    pub timer: Timer,
+}
+
+impl Default for FlashScreen {
+    fn default() -> Self {
+        let mut timer = Timer::from_seconds(0.3, TimerMode::Once);
+        timer.tick(timer.duration());
+        Self {
+            color: Color::RED,
+            timer,
+        }
+    }
+}
+
+impl FlashScreen {
+    // restarting rather than adding keeps back to back losses from piling up
+    // into a solid screen
+    pub fn trigger(&mut self, color: Color) {
+        self.color = color;
+        self.timer.reset();
+    }
+
+    pub fn alpha(&self) -> f32 {
+        if self.timer.finished() {
+            0.0
+        } else {
+            SCREEN_FLASH_ALPHA * (1.0 - self.timer.percent())
+        }
+    }
+}
+
+#[derive(Component)]
+struct FlashOverlay;
+
+// trauma decays over time and the offset grows with its square, so small
+// knocks barely move the view while big ones rattle it
+#[derive(Resource, Default)]
+pub struct CameraShake {
+    pub trauma: f32,
+    offset: Vec2,
+}
+
+impl CameraShake {
+    pub fn add(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).min(1.0);
+    }
+}
+
+#[derive(Component)]
+pub struct HitFlash {
+    timer: Timer,
+    restore: Color,
+}
+
+impl HitFlash {
+    // `restore` is the sprite's color once the flash is over
+    pub fn new(restore: Color) -> Self {
+        Self {
+            timer: Timer::from_seconds(HIT_FLASH_SECONDS, TimerMode::Once),
+            restore,
+        }
+    }
+
+    pub fn restore(&self) -> Color {
+        self.restore
+    }
+}
+
+// black bars framing the camera pan into the lane
+#[derive(Resource)]
+pub struct LetterboxConfig {
+    pub enabled: bool,
+    pub height: f32,
+    pub slide_seconds: f32,
+}
+
+impl Default for LetterboxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            height: 60.0,
+            slide_seconds: 0.5,
+        }
+    }
+}
+
+// how far the bar has slid in, 0 is fully retracted
+#[derive(Component, Default)]
+pub struct Letterbox(f32);
+
+// set by scripted moments during play to bring the letterbox back in
+#[derive(Resource, Default)]
+pub struct Cinematic(pub bool);
+
+// purely cosmetic entities that can be dropped when too many are alive, never
+// put on anything the player needs to read like the HUD
+#[derive(Component)]
+pub struct Effect;
+
+#[derive(Resource)]
+pub struct EffectBudget {
+    pub max: usize,
+}
+
+impl Default for EffectBudget {
+    fn default() -> Self {
+        Self {
+            max: DEFAULT_MAX_EFFECTS,
+        }
+    }
+}
+
+// recounted every frame, and bumped as effects are claimed so a single busy
+// frame can't spawn past the cap before its commands are applied
+#[derive(Resource, Default)]
+pub struct LiveEffects(usize);
+
+#[derive(SystemParam)]
+pub struct EffectSlots<'w> {
+    budget: Res<'w, EffectBudget>,
+    live: ResMut<'w, LiveEffects>,
+}
+
+impl EffectSlots<'_> {
+    pub fn claim(&mut self) -> bool {
+        if self.live.0 >= self.budget.max {
+            return false;
+        }
+        self.live.0 += 1;
+        true
+    }
+}
+
+#[derive(Component)]
+pub struct FadingText {
+    pub lifetime: Timer,
+    pub velocity: Vec2,
+}
+
+impl FadingText {
+    pub fn new(velocity: Vec2, lifetime: f32) -> Self {
+        Self {
+            lifetime: Timer::from_seconds(lifetime, TimerMode::Once),
+            velocity,
+        }
+    }
+
+    pub fn alpha(&self) -> f32 {
+        1.0 - self.lifetime.percent()
+    }
+}
+
+impl Plugin for EffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlashScreen>()
+            .init_resource::<CameraShake>()
+            .init_resource::<LetterboxConfig>()
+            .init_resource::<Cinematic>()
+            .init_resource::<EffectBudget>()
+            .init_resource::<LiveEffects>()
+            .add_systems(First, count_effects)
+            .add_systems(OnExit(GameState::TransitionToGamePlay), spawn_flash_overlay)
+            .add_systems(OnEnter(GameState::TransitionToGamePlay), spawn_letterbox)
+            .add_systems(
+                Update,
+                animate_letterbox.run_if(
+                    in_state(GameState::TransitionToGamePlay)
+                        .or_else(in_state(GameState::GamePlay)),
+                ),
+            )
+            .add_systems(
+                Update,
+                (update_flash_overlay, fade_text, shake_camera, hit_flash)
+                    .run_if(not(in_state(GameState::Pause))),
+            );
+    }
+}
+
+pub fn spawn_fading_text(
+    commands: &mut Commands,
+    position: Vec3,
+    value: impl Into<String>,
+    style: TextStyle,
+    velocity: Vec2,
+    lifetime: f32,
+) -> Entity {
+    commands
+        .spawn((
+            Text2dBundle {
+                text: Text::from_section(value, style).with_alignment(TextAlignment::Center),
+                transform: Transform::from_translation(position),
+                ..default()
+            },
+            FadingText::new(velocity, lifetime),
+        ))
+        .id()
+}
+
+// same as spawn_fading_text but skipped once the effect budget is used up, for
+// popups that are only there for flavour
+pub fn spawn_effect_text(
+    commands: &mut Commands,
+    slots: &mut EffectSlots,
+    position: Vec3,
+    value: impl Into<String>,
+    style: TextStyle,
+    velocity: Vec2,
+    lifetime: f32,
+) -> Option<Entity> {
+    if !slots.claim() {
+        return None;
+    }
+    let entity = spawn_fading_text(commands, position, value, style, velocity, lifetime);
+    commands.entity(entity).insert(Effect);
+    Some(entity)
+}
+
+fn count_effects(mut live: ResMut<LiveEffects>, effects: Query<(), With<Effect>>) {
+    live.0 = effects.iter().count();
+}
+
+// world text moves through its transform, UI text through its style since
+// layout overwrites the transform of UI nodes
+fn fade_text(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut texts: Query<(
+        Entity,
+        &mut FadingText,
+        &mut Text,
+        &mut Transform,
+        Option<&mut Style>,
+    )>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut fading, mut text, mut transform, style) in &mut texts {
+        fading.lifetime.tick(time.delta());
+        if fading.lifetime.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let step = fading.velocity * delta;
+        match style {
+            Some(mut style) => {
+                style.left = offset(style.left, step.x);
+                style.top = offset(style.top, -step.y);
+            }
+            None => transform.translation += step.extend(0.0),
+        }
+        let alpha = fading.alpha();
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(alpha);
+        }
+    }
+}
+
+fn offset(val: Val, by: f32) -> Val {
+    match val {
+        Val::Px(px) => Val::Px(px + by),
+        Val::Auto => Val::Px(by),
+        other => other,
+    }
+}
+
+fn spawn_flash_overlay(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            background_color: Color::NONE.into(),
+            z_index: ZIndex::Global(-1),
+            ..default()
+        },
+        FlashOverlay,
+    ));
+}
+
+fn update_flash_overlay(
+    time: Res<Time>,
+    mut flash: ResMut<FlashScreen>,
+    mut overlays: Query<&mut BackgroundColor, With<FlashOverlay>>,
+) {
+    flash.timer.tick(time.delta());
+    let mut color = flash.color;
+    color.set_a(flash.alpha());
+    for mut background in &mut overlays {
+        *background = color.into();
+    }
+}
+
+fn spawn_letterbox(mut commands: Commands, config: Res<LetterboxConfig>) {
+    if !config.enabled {
+        return;
+    }
+    for top in [true, false] {
+        commands.spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Px(0.0),
+                    top: if top { Val::Px(0.0) } else { Val::Auto },
+                    bottom: if top { Val::Auto } else { Val::Px(0.0) },
+                    ..default()
+                },
+                background_color: Color::BLACK.into(),
+                z_index: ZIndex::Global(LETTERBOX_Z),
+                ..default()
+            },
+            Letterbox::default(),
+        ));
+    }
+}
+
+// the bars slide in while the camera pans and back out once play starts,
+// then drop out of the layout entirely so nothing is left covering the lane
+fn animate_letterbox(
+    time: Res<Time>,
+    config: Res<LetterboxConfig>,
+    cinematic: Res<Cinematic>,
+    state: Res<State<GameState>>,
+    mut bars: Query<(&mut Letterbox, &mut Style)>,
+) {
+    let step = time.delta_seconds() / config.slide_seconds;
+    let opening = *state.get() == GameState::TransitionToGamePlay || cinematic.0;
+    for (mut bar, mut style) in &mut bars {
+        bar.0 = if opening {
+            (bar.0 + step).min(1.0)
+        } else {
+            (bar.0 - step).max(0.0)
+        };
+        style.display = if bar.0 > 0.0 {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        style.height = Val::Px(config.height * bar.0);
+    }
+}
+
+// the previous frame's offset is taken back out first so whatever else moves
+// the camera keeps working from the unshaken position
+fn shake_camera(
+    time: Res<Time>,
+    mut shake: ResMut<CameraShake>,
+    mut cameras: Query<&mut Transform, With<Camera2d>>,
+) {
+    shake.trauma = (shake.trauma - SHAKE_DECAY * time.delta_seconds()).max(0.0);
+    let previous = shake.offset;
+    let strength = SHAKE_MAX_OFFSET * shake.trauma * shake.trauma;
+    shake.offset = if strength > 0.0 {
+        let mut rng = thread_rng();
+        Vec2::new(
+            rng.gen_range(-strength..=strength),
+            rng.gen_range(-strength..=strength),
+        )
+    } else {
+        Vec2::ZERO
+    };
+    if previous == shake.offset {
+        return;
+    }
+    for mut transform in &mut cameras {
+        transform.translation += (shake.offset - previous).extend(0.0);
+    }
+}
+
+fn hit_flash(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut flashes: Query<(Entity, &mut HitFlash, &mut TextureAtlasSprite)>,
+) {
+    for (entity, mut flash, mut sprite) in &mut flashes {
+        if flash.timer.tick(time.delta()).finished() {
+            sprite.color = flash.restore;
+            commands.entity(entity).remove::<HitFlash>();
+        } else {
+            sprite.color = HIT_FLASH_COLOR;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{ecs::system::RunSystemOnce, time::TimeUpdateStrategy};
+
+    use super::*;
+    use crate::testing::{advance, advance_secs, enter_state, headless_app, TEST_STEP};
+
+    fn alpha(app: &App, entity: Entity) -> f32 {
+        app.world.get::<Text>(entity).unwrap().sections[0]
+            .style
+            .color
+            .a()
+    }
+
+    fn overlay_alpha(app: &App, overlay: Entity) -> f32 {
+        app.world.get::<BackgroundColor>(overlay).unwrap().0.a()
+    }
+
+    #[test]
+    fn back_to_back_flashes_never_stack() {
+        let mut app = headless_app();
+        app.init_resource::<FlashScreen>()
+            .add_systems(Update, update_flash_overlay);
+        let overlay = app
+            .world
+            .spawn((BackgroundColor(Color::NONE), FlashOverlay))
+            .id();
+
+        let mut brightest: f32 = 0.0;
+        for _ in 0..3 {
+            app.world.resource_mut::<FlashScreen>().trigger(Color::RED);
+            advance(&mut app, 3);
+            brightest = brightest.max(overlay_alpha(&app, overlay));
+        }
+        assert!(brightest > 0.0 && brightest <= SCREEN_FLASH_ALPHA);
+
+        let duration = app.world.resource::<FlashScreen>().timer.duration();
+        advance_secs(&mut app, duration.as_secs_f32() + 0.1);
+        assert_eq!(overlay_alpha(&app, overlay), 0.0);
+    }
+
+    #[test]
+    fn fading_text_fades_and_despawns_in_world_and_ui() {
+        let mut app = headless_app();
+        app.add_systems(Update, fade_text);
+        let world_text = app.world.run_system_once(|mut commands: Commands| {
+            spawn_fading_text(
+                &mut commands,
+                Vec3::ZERO,
+                "+1",
+                TextStyle::default(),
+                Vec2::new(0.0, 40.0),
+                1.0,
+            )
+        });
+        let ui_text = app
+            .world
+            .spawn((
+                TextBundle::from_section("+1", TextStyle::default()),
+                FadingText::new(Vec2::new(0.0, 40.0), 1.0),
+            ))
+            .id();
+
+        advance(&mut app, 2);
+        let (world_alpha, ui_alpha) = (alpha(&app, world_text), alpha(&app, ui_text));
+        advance_secs(&mut app, 0.5);
+        assert!(alpha(&app, world_text) < world_alpha);
+        assert!(alpha(&app, ui_text) < ui_alpha);
+        assert!(
+            app.world
+                .get::<Transform>(world_text)
+                .unwrap()
+                .translation
+                .y
+                > 0.0
+        );
+        assert!(matches!(
+            app.world.get::<Style>(ui_text).unwrap().top,
+            Val::Px(top) if top < 0.0
+        ));
+
+        advance_secs(&mut app, 0.6);
+        assert!(app.world.get_entity(world_text).is_none());
+        assert!(app.world.get_entity(ui_text).is_none());
+    }
+
+    #[derive(Component)]
+    struct Gameplay;
+
+    // every frame tries two cosmetic popups alongside one thing play needs
+    fn spawn_burst(mut commands: Commands, mut slots: EffectSlots) {
+        for _ in 0..2 {
+            spawn_effect_text(
+                &mut commands,
+                &mut slots,
+                Vec3::ZERO,
+                "+1",
+                TextStyle::default(),
+                Vec2::ZERO,
+                10.0,
+            );
+        }
+        commands.spawn(Gameplay);
+    }
+
+    #[test]
+    fn effects_stop_at_the_cap_while_gameplay_keeps_spawning() {
+        let mut app = headless_app();
+        app.insert_resource(EffectBudget { max: 3 })
+            .init_resource::<LiveEffects>()
+            .add_systems(First, count_effects)
+            .add_systems(Update, spawn_burst);
+        advance(&mut app, 4);
+
+        let effects = app.world.query::<&Effect>().iter(&app.world).count();
+        let gameplay = app.world.query::<&Gameplay>().iter(&app.world).count();
+        assert_eq!(effects, 3);
+        assert_eq!(gameplay, 4);
+    }
+
+    fn effects_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, EffectsPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+                TEST_STEP,
+            )))
+            .add_state::<GameState>();
+        app
+    }
+
+    #[test]
+    fn effects_plugin_alone_shakes_the_camera() {
+        let mut app = effects_app();
+        let camera = app
+            .world
+            .spawn((Camera2d::default(), Transform::default()))
+            .id();
+        let offset = |app: &App| app.world.get::<Transform>(camera).unwrap().translation;
+        advance(&mut app, 1);
+
+        app.world.resource_mut::<CameraShake>().add(1.0);
+        advance(&mut app, 1);
+        assert_ne!(offset(&app), Vec3::ZERO);
+
+        // held while paused, then settles back where it started
+        enter_state(&mut app, GameState::Pause);
+        let trauma = app.world.resource::<CameraShake>().trauma;
+        advance(&mut app, 10);
+        assert_eq!(app.world.resource::<CameraShake>().trauma, trauma);
+        enter_state(&mut app, GameState::GamePlay);
+        advance_secs(&mut app, 5.0);
+        assert_eq!(app.world.resource::<CameraShake>().trauma, 0.0);
+        assert!(offset(&app).length() < 1e-3);
+    }
+
+    fn bar_heights(app: &mut App) -> Vec<(Val, Display)> {
+        app.world
+            .query_filtered::<&Style, With<Letterbox>>()
+            .iter(&app.world)
+            .map(|style| (style.height, style.display))
+            .collect()
+    }
+
+    #[test]
+    fn letterbox_slides_in_for_the_intro_and_is_gone_in_play() {
+        let mut app = effects_app();
+        enter_state(&mut app, GameState::TransitionToGamePlay);
+        let config = app.world.resource::<LetterboxConfig>();
+        let (height, slide) = (config.height, config.slide_seconds);
+
+        advance_secs(&mut app, slide / 2.0);
+        let bars = bar_heights(&mut app);
+        assert_eq!(bars.len(), 2);
+        for (bar, display) in bars {
+            assert!(matches!(bar, Val::Px(px) if px > 0.0 && px < height));
+            assert_eq!(display, Display::Flex);
+        }
+        advance_secs(&mut app, slide);
+        assert!(bar_heights(&mut app)
+            .iter()
+            .all(|(bar, _)| *bar == Val::Px(height)));
+
+        enter_state(&mut app, GameState::GamePlay);
+        advance_secs(&mut app, slide + 0.1);
+        assert!(bar_heights(&mut app)
+            .iter()
+            .all(|(_, display)| *display == Display::None));
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::{localization::Localization, persist, state::GameState, theme::Theme},
+    effects::spawn_fading_text,
+    entities::{
+        player::{ElapsedGameplay, GameStats},
+        wave::WaveCleared,
+    },
+    pause::RestartSet,
+};
+
+const ACHIEVEMENTS_FILE: &str = "achievements.json";
+const KILL_TARGET: i32 = 100;
+const SURVIVE_SECONDS: f32 = 120.0;
+const TOAST_OFFSET: Vec2 = Vec2::new(0.0, 200.0);
+const TOAST_COLOR: Color = Color::rgb(1.0, 0.85, 0.2);
+
+pub struct AchievementPlugin;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Achievement {
+    Kills,
+    Survive,
+    NoDamageWave,
+}
+
+impl Achievement {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Achievement::Kills => "kills",
+            Achievement::Survive => "survive",
+            Achievement::NoDamageWave => "no_damage_wave",
+        }
+    }
+
+    pub fn name_key(&self) -> &'static str {
+        match self {
+            Achievement::Kills => "achievement_kills",
+            Achievement::Survive => "achievement_survive",
+            Achievement::NoDamageWave => "achievement_no_damage_wave",
+        }
+    }
+}
+
+// kills add up over every run, the rest have to be done within a single one
+#[derive(Resource, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Achievements {
+    pub unlocked: HashSet<String>,
+    pub total_kills: i32,
+    #[serde(skip)]
+    counted_kills: i32,
+}
+
+impl Achievements {
+    // true only the first time, so nothing gets announced twice
+    pub fn unlock(&mut self, achievement: Achievement) -> bool {
+        self.unlocked.insert(achievement.id().to_string())
+    }
+}
+
+impl Plugin for AchievementPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(persist::load::<Achievements>(ACHIEVEMENTS_FILE))
+            .add_systems(OnEnter(GameState::TransitionToGamePlay), reset_run)
+            .add_systems(OnEnter(GameState::GamePlay), reset_run.in_set(RestartSet))
+            .add_systems(
+                Update,
+                check_achievements.run_if(in_state(GameState::GamePlay)),
+            )
+            .add_systems(OnEnter(GameState::GameOver), save_achievements);
+    }
+}
+
+fn reset_run(mut achievements: ResMut<Achievements>) {
+    achievements.counted_kills = 0;
+}
+
+fn check_achievements(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    localization: Res<Localization>,
+    stats: Res<GameStats>,
+    elapsed: Res<ElapsedGameplay>,
+    mut cleared: EventReader<WaveCleared>,
+    mut achievements: ResMut<Achievements>,
+    cameras: Query<&Transform, With<Camera2d>>,
+) {
+    let new_kills = stats.enemies_killed - achievements.counted_kills;
+    if new_kills > 0 {
+        achievements.total_kills += new_kills;
+        achievements.counted_kills = stats.enemies_killed;
+    }
+    let mut met = Vec::new();
+    if achievements.total_kills >= KILL_TARGET {
+        met.push(Achievement::Kills);
+    }
+    if elapsed.seconds >= SURVIVE_SECONDS {
+        met.push(Achievement::Survive);
+    }
+    if cleared.read().any(|wave| wave.perfect) {
+        met.push(Achievement::NoDamageWave);
+    }
+
+    let mut unlocked = false;
+    for achievement in met {
+        if !achievements.unlock(achievement) {
+            continue;
+        }
+        info!("achievement `{}` unlocked", achievement.id());
+        unlocked = true;
+        let Ok(camera) = cameras.get_single() else {
+            continue;
+        };
+        let mut style = theme.hud_style(&asset_server);
+        style.color = TOAST_COLOR;
+        spawn_fading_text(
+            &mut commands,
+            (camera.translation.truncate() + TOAST_OFFSET).extend(20.0),
+            format!(
+                "{}: {}",
+                localization.get("achievement_unlocked"),
+                localization.get(achievement.name_key())
+            ),
+            style,
+            Vec2::new(0.0, 20.0),
+            2.5,
+        );
+    }
+    if unlocked {
+        persist::save(ACHIEVEMENTS_FILE, &*achievements);
+    }
+}
+
+// kill progress is only written on unlocks otherwise, so a run that ends
+// short of the next one still counts towards it
+fn save_achievements(achievements: Res<Achievements>) {
+    persist::save(ACHIEVEMENTS_FILE, &*achievements);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        effects::FadingText,
+        testing::{advance, headless_app},
+    };
+
+    fn toasts(app: &mut App) -> usize {
+        app.world
+            .query_filtered::<(), With<FadingText>>()
+            .iter(&app.world)
+            .count()
+    }
+
+    #[test]
+    fn meeting_a_goal_unlocks_it_once_and_saves_it() {
+        let mut app = headless_app();
+        app.add_event::<WaveCleared>()
+            .add_plugins(AchievementPlugin)
+            .insert_resource(Achievements::default());
+        app.world.spawn((Camera2d::default(), Transform::default()));
+        advance(&mut app, 1);
+        assert_eq!(toasts(&mut app), 0);
+
+        app.world.resource_mut::<GameStats>().enemies_killed = KILL_TARGET;
+        advance(&mut app, 1);
+        assert_eq!(toasts(&mut app), 1);
+
+        // still met, and met again further on, but never announced twice
+        advance(&mut app, 5);
+        app.world.resource_mut::<GameStats>().enemies_killed = KILL_TARGET + 10;
+        advance(&mut app, 5);
+        assert_eq!(toasts(&mut app), 1);
+
+        let saved = persist::load::<Achievements>(ACHIEVEMENTS_FILE);
+        assert!(saved.unlocked.contains(Achievement::Kills.id()));
+        assert_eq!(saved.unlocked.len(), 1);
+    }
+}
@@ -0,0 +1,207 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    animation::AnimationComponent,
+    data::state::GameState,
+    entities::{
+        collision::SensorCollisions,
+        enemy::{DamageEnemy, Enemy},
+    },
+};
+
+pub struct AttackPlugin;
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AttackKind {
+    #[default]
+    Piercing,
+    Explosive {
+        radius: f32,
+    },
+    Frost {
+        slow: f32,
+        duration: f32,
+    },
+    Fire {
+        damage: i32,
+        duration: f32,
+    },
+    Chain {
+        jumps: usize,
+        range: f32,
+    },
+    GroundSlam {
+        radius: f32,
+    },
+    // `angle` is the gap in degrees between neighbouring pellets
+    Spread {
+        count: usize,
+        angle: f32,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct AttackDef {
+    pub speed: f32,
+    pub pierce: i32,
+    pub kind: AttackKind,
+}
+
+impl Default for AttackDef {
+    fn default() -> Self {
+        Self {
+            speed: 150.0,
+            pierce: 10,
+            kind: AttackKind::Piercing,
+        }
+    }
+}
+
+impl AttackDef {
+    pub fn validated(mut self, weapon: &str) -> Self {
+        if matches!(self.kind, AttackKind::Unknown) {
+            warn!(
+                "weapon `{}` has an unknown attack kind, using a piercing attack",
+                weapon
+            );
+            self.kind = AttackKind::Piercing;
+        }
+        self
+    }
+}
+
+#[derive(Component)]
+pub struct AttackEffect(pub AttackKind);
+
+#[derive(Component)]
+pub struct Chilled {
+    slow: f32,
+    timer: Timer,
+}
+
+impl Chilled {
+    pub fn speed_factor(&self) -> f32 {
+        (1.0 - self.slow).clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Component)]
+pub struct Burning {
+    damage: i32,
+    timer: Timer,
+    tick: Timer,
+}
+
+impl Plugin for AttackPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (apply_attack_effects, tick_chilled, tick_burning)
+                .run_if(in_state(GameState::GamePlay)),
+        );
+    }
+}
+
+fn apply_attack_effects(
+    mut commands: Commands,
+    mut collisions: SensorCollisions,
+    mut damage: EventWriter<DamageEnemy>,
+    attacks: Query<&AttackEffect>,
+    enemies: Query<(Entity, &Transform, &AnimationComponent), With<Enemy>>,
+) {
+    for (a, b) in collisions.started() {
+        let (effect, target) = if let Ok(effect) = attacks.get(a) {
+            (effect, b)
+        } else if let Ok(effect) = attacks.get(b) {
+            (effect, a)
+        } else {
+            continue;
+        };
+        let Ok((_, target_transform, _)) = enemies.get(target) else {
+            continue;
+        };
+        let origin = target_transform.translation.truncate();
+        let mut nearby: Vec<(Entity, f32)> = enemies
+            .iter()
+            .filter(|(entity, _, anim)| *entity != target && !anim.state.is_dying())
+            .map(|(entity, transform, _)| {
+                (entity, transform.translation.truncate().distance(origin))
+            })
+            .collect();
+        match &effect.0 {
+            AttackKind::Frost { slow, duration } => {
+                commands.entity(target).insert(Chilled {
+                    slow: *slow,
+                    timer: Timer::new(Duration::from_secs_f32(*duration), TimerMode::Once),
+                });
+            }
+            AttackKind::Fire { damage, duration } => {
+                commands.entity(target).insert(Burning {
+                    damage: *damage,
+                    timer: Timer::new(Duration::from_secs_f32(*duration), TimerMode::Once),
+                    tick: Timer::new(Duration::from_secs(1), TimerMode::Repeating),
+                });
+            }
+            AttackKind::Explosive { radius } => {
+                for (entity, _) in nearby.iter().filter(|(_, dist)| dist <= radius) {
+                    damage.send(DamageEnemy {
+                        entity: *entity,
+                        amount: 1,
+                    });
+                }
+            }
+            AttackKind::Chain { jumps, range } => {
+                nearby.retain(|(_, dist)| dist <= range);
+                nearby.sort_by(|a, b| a.1.total_cmp(&b.1));
+                for (entity, _) in nearby.into_iter().take(*jumps) {
+                    damage.send(DamageEnemy { entity, amount: 1 });
+                }
+            }
+            AttackKind::Piercing
+            | AttackKind::GroundSlam { .. }
+            | AttackKind::Spread { .. }
+            | AttackKind::Unknown => {}
+        }
+    }
+}
+
+fn tick_chilled(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut chilled: Query<(Entity, &mut Chilled)>,
+) {
+    for (entity, mut chill) in &mut chilled {
+        chill.timer.tick(time.delta());
+        if chill.timer.finished() {
+            commands.entity(entity).remove::<Chilled>();
+        }
+    }
+}
+
+fn tick_burning(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut damage: EventWriter<DamageEnemy>,
+    mut burning: Query<(Entity, &mut Burning)>,
+) {
+    for (entity, mut burn) in &mut burning {
+        burn.timer.tick(time.delta());
+        burn.tick.tick(time.delta());
+        if burn.tick.just_finished() {
+            damage.send(DamageEnemy {
+                entity,
+                amount: burn.damage,
+            });
+        }
+        if burn.timer.finished() {
+            commands.entity(entity).remove::<Burning>();
+        }
+    }
+}
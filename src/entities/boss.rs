@@ -0,0 +1,319 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    animation::EnemyAnimations,
+    data::{
+        localization::Localization,
+        mode::waves_enabled,
+        playfield::{PlayfieldBounds, PlayfieldOrientation},
+        state::GameState,
+        theme::Theme,
+    },
+    effects::{CameraShake, Cinematic},
+    entities::{
+        enemy::{spawn_named_enemy, Enemy, EnemySet, EnemyTypes},
+        player::{death_in_progress, DeathSequence, GameStats},
+        wave::WaveState,
+    },
+    pause::{PausableTimers, PauseAppExt, RestartSet},
+    GameplayStart,
+};
+
+const BOSS_INTRO_SECONDS: f32 = 2.5;
+const BOSS_INTRO_SHAKE: f32 = 0.5;
+const BOSS_HEALTH_MULT: i32 = 15;
+const BOSS_SCALE: f32 = 3.0;
+const BANNER_FLASH_RATE: f32 = 6.0;
+const BANNER_COLOR: Color = Color::rgb(1.0, 0.2, 0.2);
+const BANNER_Z: i32 = 11;
+
+pub struct BossPlugin;
+
+#[derive(Component)]
+pub struct Boss;
+
+#[derive(Component)]
+struct WarningBanner;
+
+// spawning and input stay off while the timer runs, the boss only shows up
+// once it finishes
+#[derive(Resource)]
+pub struct BossIntro {
+    pub timer: Timer,
+    pub active: bool,
+    pub boss: Option<&'static str>,
+    // the wave whose boss has already been introduced
+    pub done_wave: Option<usize>,
+}
+
+impl Default for BossIntro {
+    fn default() -> Self {
+        Self {
+            timer: Timer::new(Duration::from_secs_f32(BOSS_INTRO_SECONDS), TimerMode::Once),
+            active: false,
+            boss: None,
+            done_wave: None,
+        }
+    }
+}
+
+impl PausableTimers for BossIntro {
+    fn timers_mut(&mut self) -> Vec<&mut Timer> {
+        vec![&mut self.timer]
+    }
+}
+
+pub fn boss_intro_active(intro: Res<BossIntro>) -> bool {
+    intro.active
+}
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BossIntro>()
+            .preserve_timers_on_pause::<BossIntro>()
+            .add_systems(OnEnter(GameState::TransitionToGamePlay), reset_intro)
+            .add_systems(
+                Update,
+                (
+                    start_boss_intro.run_if(
+                        waves_enabled
+                            .and_then(not(death_in_progress))
+                            .and_then(not(boss_intro_active)),
+                    ),
+                    tick_boss_intro.run_if(boss_intro_active),
+                )
+                    .chain()
+                    .before(EnemySet::Spawn)
+                    .run_if(in_state(GameState::GamePlay)),
+            )
+            .add_systems(OnEnter(GameState::GameOver), cancel_boss_intro)
+            .add_systems(
+                OnEnter(GameState::GamePlay),
+                (cancel_boss_intro, reset_intro).chain().in_set(RestartSet),
+            );
+    }
+}
+
+fn reset_intro(mut intro: ResMut<BossIntro>) {
+    *intro = BossIntro::default();
+}
+
+// kicks in once the break before a boss wave is over, before any of the
+// wave's regular enemies come out
+fn start_boss_intro(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    localization: Res<Localization>,
+    waves: Res<WaveState>,
+    mut intro: ResMut<BossIntro>,
+    mut cinematic: ResMut<Cinematic>,
+    mut shake: ResMut<CameraShake>,
+) {
+    if !waves.break_timer.finished() || intro.done_wave == Some(waves.current) {
+        return;
+    }
+    let Some(boss) = waves.wave().and_then(|wave| wave.boss) else {
+        return;
+    };
+    intro.active = true;
+    intro.boss = Some(boss);
+    intro.timer.reset();
+    cinematic.0 = true;
+    shake.add(BOSS_INTRO_SHAKE);
+
+    let mut style = theme.hud_style(&asset_server);
+    style.font_size *= 2.0;
+    style.color = BANNER_COLOR;
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                z_index: ZIndex::Global(BANNER_Z),
+                ..default()
+            },
+            WarningBanner,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                localization.get("boss_warning"),
+                style,
+            ));
+        });
+}
+
+fn tick_boss_intro(
+    mut commands: Commands,
+    time: Res<Time>,
+    death: Res<DeathSequence>,
+    gameplay_start: Res<GameplayStart>,
+    bounds: Res<PlayfieldBounds>,
+    orientation: Res<PlayfieldOrientation>,
+    enemy_anims: Res<EnemyAnimations>,
+    enemy_types: Res<EnemyTypes>,
+    waves: Res<WaveState>,
+    mut stats: ResMut<GameStats>,
+    mut intro: ResMut<BossIntro>,
+    mut cinematic: ResMut<Cinematic>,
+    mut banners: Query<(Entity, &mut Visibility), With<WarningBanner>>,
+) {
+    // a hero who dies mid intro never gets to meet the boss
+    if death.active {
+        end_intro(&mut commands, &mut intro, &mut cinematic, &banners);
+        return;
+    }
+    intro.timer.tick(time.delta());
+    let shown = ((intro.timer.elapsed_secs() * BANNER_FLASH_RATE) as u32).is_multiple_of(2);
+    for (_, mut visibility) in &mut banners {
+        *visibility = if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+    if !intro.timer.finished() {
+        return;
+    }
+
+    let boss = intro.boss;
+    intro.done_wave = Some(waves.current);
+    end_intro(&mut commands, &mut intro, &mut cinematic, &banners);
+    let Some(name) = boss else {
+        return;
+    };
+    let (Some((name, info)), true) = (
+        enemy_types.types.get_key_value(name),
+        enemy_anims.enemies.contains_key(name),
+    ) else {
+        warn!("boss `{}` is not a known enemy type, skipping it", name);
+        return;
+    };
+    let mut enemy = Enemy::new(name, info);
+    enemy.health *= BOSS_HEALTH_MULT;
+    let position = Vec3::new(
+        bounds.spawn_x(gameplay_start.camera_endpos.x, *orientation),
+        (bounds.min_y + bounds.max_y) / 2.0,
+        0.,
+    );
+    let entity = spawn_named_enemy(
+        &mut commands,
+        &enemy_anims,
+        enemy,
+        info,
+        position,
+        *orientation,
+    );
+    commands.entity(entity).insert((
+        Transform::from_translation(position).with_scale(Vec3::splat(BOSS_SCALE)),
+        Boss,
+    ));
+    stats.entites_spawned += 1;
+}
+
+fn cancel_boss_intro(
+    mut commands: Commands,
+    mut intro: ResMut<BossIntro>,
+    mut cinematic: ResMut<Cinematic>,
+    banners: Query<(Entity, &mut Visibility), With<WarningBanner>>,
+) {
+    if intro.active {
+        end_intro(&mut commands, &mut intro, &mut cinematic, &banners);
+    }
+}
+
+fn end_intro(
+    commands: &mut Commands,
+    intro: &mut BossIntro,
+    cinematic: &mut Cinematic,
+    banners: &Query<(Entity, &mut Visibility), With<WarningBanner>>,
+) {
+    intro.active = false;
+    intro.boss = None;
+    cinematic.0 = false;
+    for (entity, _) in banners {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        effects::{CameraShake, Cinematic},
+        entities::wave::WavePlugin,
+        menu::MenuItem,
+        testing::{advance_secs, headless_app, stub_enemy_animations, TEST_ENEMY},
+    };
+
+    // wave runs with the first boss wave's boss standing in for the test enemy
+    fn boss_app() -> (App, usize) {
+        let mut app = headless_app();
+        app.insert_resource(MenuItem::Waves.mode())
+            .init_resource::<Cinematic>()
+            .init_resource::<CameraShake>()
+            .add_plugins((WavePlugin, BossPlugin));
+        let mut waves = app.world.resource_mut::<WaveState>();
+        let index = waves
+            .waves
+            .iter()
+            .position(|wave| wave.boss.is_some())
+            .unwrap();
+        let boss = waves.waves[index].boss.unwrap();
+        waves.current = index;
+        let break_time = waves.break_timer.duration();
+        waves.break_timer.tick(break_time);
+
+        let info = app.world.resource::<EnemyTypes>().types[TEST_ENEMY].clone();
+        app.world
+            .resource_mut::<EnemyTypes>()
+            .types
+            .insert(boss.to_string(), info);
+        let handles = stub_enemy_animations().enemies.remove(TEST_ENEMY).unwrap();
+        app.world
+            .resource_mut::<EnemyAnimations>()
+            .enemies
+            .insert(boss.to_string(), handles);
+        (app, index)
+    }
+
+    fn bosses(app: &mut App) -> usize {
+        app.world
+            .query_filtered::<(), With<Boss>>()
+            .iter(&app.world)
+            .count()
+    }
+
+    #[test]
+    fn boss_wave_holds_spawning_for_the_intro() {
+        let (mut app, _) = boss_app();
+        app.update();
+
+        assert!(app.world.resource::<BossIntro>().active);
+        assert!(app.world.resource::<Cinematic>().0);
+        assert_eq!(app.world.resource::<WaveState>().spawned, 0);
+        assert_eq!(bosses(&mut app), 0);
+    }
+
+    #[test]
+    fn boss_appears_once_the_intro_ends() {
+        let (mut app, index) = boss_app();
+        advance_secs(&mut app, BOSS_INTRO_SECONDS + 0.1);
+
+        let intro = app.world.resource::<BossIntro>();
+        assert!(!intro.active);
+        assert_eq!(intro.done_wave, Some(index));
+        assert!(!app.world.resource::<Cinematic>().0);
+        assert_eq!(bosses(&mut app), 1);
+        // the wave itself carries on behind it
+        assert!(app.world.resource::<WaveState>().spawned > 0);
+    }
+}
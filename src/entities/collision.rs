@@ -0,0 +1,94 @@
+use std::collections::HashSet;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_rapier2d::{prelude::*, rapier::geometry::CollisionEventFlags};
+
+// the player and its attacks share a group, so enemies and pickups only
+// have to list one thing to be hit by
+pub const GROUP_ENEMY: Group = Group::GROUP_1;
+pub const GROUP_PLAYER: Group = Group::GROUP_2;
+pub const GROUP_PICKUP: Group = Group::GROUP_3;
+
+// the pair comes back smallest entity first, so it is the same whichever way
+// round rapier reported it
+pub fn is_sensor_started(event: &CollisionEvent) -> Option<(Entity, Entity)> {
+    match event {
+        CollisionEvent::Started(a, b, flags) if flags.contains(CollisionEventFlags::SENSOR) => {
+            Some(if a < b { (*a, *b) } else { (*b, *a) })
+        }
+        _ => None,
+    }
+}
+
+#[derive(SystemParam)]
+pub struct SensorCollisions<'w, 's> {
+    events: EventReader<'w, 's, CollisionEvent>,
+    seen: Local<'s, HashSet<(Entity, Entity)>>,
+}
+
+impl<'w, 's> SensorCollisions<'w, 's> {
+    // sensors with KINEMATIC_KINEMATIC can report the same overlap more than
+    // once in a frame, so each pair is only yielded once whichever way round
+    pub fn started(&mut self) -> Vec<(Entity, Entity)> {
+        let Self { events, seen } = self;
+        seen.clear();
+        events
+            .read()
+            .filter_map(is_sensor_started)
+            .filter(|pair| seen.insert(*pair))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{advance, collide, headless_app};
+
+    #[derive(Resource, Default)]
+    struct Reported(Vec<(Entity, Entity)>);
+
+    fn record(mut collisions: SensorCollisions, mut reported: ResMut<Reported>) {
+        reported.0.extend(collisions.started());
+    }
+
+    #[test]
+    fn sensor_start_is_reported_smallest_entity_first() {
+        let a = Entity::from_raw(1);
+        let b = Entity::from_raw(2);
+        let started = |x, y| CollisionEvent::Started(x, y, CollisionEventFlags::SENSOR);
+
+        assert_eq!(is_sensor_started(&started(a, b)), Some((a, b)));
+        assert_eq!(is_sensor_started(&started(b, a)), Some((a, b)));
+    }
+
+    #[test]
+    fn only_sensor_starts_are_reported() {
+        let a = Entity::from_raw(1);
+        let b = Entity::from_raw(2);
+
+        let solid = CollisionEvent::Started(a, b, CollisionEventFlags::empty());
+        let stopped = CollisionEvent::Stopped(a, b, CollisionEventFlags::SENSOR);
+        assert_eq!(is_sensor_started(&solid), None);
+        assert_eq!(is_sensor_started(&stopped), None);
+    }
+
+    #[test]
+    fn each_pair_is_reported_once_per_frame() {
+        let mut app = headless_app();
+        app.init_resource::<Reported>().add_systems(Update, record);
+        let a = app.world.spawn_empty().id();
+        let b = app.world.spawn_empty().id();
+
+        collide(&mut app, a, b);
+        collide(&mut app, b, a);
+        collide(&mut app, a, b);
+        advance(&mut app, 1);
+        assert_eq!(app.world.resource::<Reported>().0, vec![(a, b)]);
+
+        // a fresh overlap on a later frame still counts
+        collide(&mut app, b, a);
+        advance(&mut app, 1);
+        assert_eq!(app.world.resource::<Reported>().0, vec![(a, b), (a, b)]);
+    }
+}
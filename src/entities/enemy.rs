@@ -1,4 +1,4 @@
-use std::{collections::HashMap, time::Duration};
+use std::collections::HashMap;
 
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
@@ -6,122 +6,752 @@ use rand::prelude::*;
 
 use crate::{
     animation::{
-        AnimState, AnimationComponent, AnimationHandles, AnimationList, AnimationListAsset,
-        EnemyAnimations, ImagesToLoad,
+        animation_list_valid, placeholder_image, AggroEntry, AnimState, AnimationComponent,
+        AnimationHandles, AnimationList, AnimationListAsset, BlinkerEntry, ContactBehavior,
+        DeathStyle, DrainAuraEntry, DropEntry, EnemyAnimations, ImagesToLoad, RangedEntry,
+        ALERT_ANIM,
     },
-    data::state::GameState,
-    entities::player::GameStats,
+    audio::Sfx,
+    data::{
+        mode::{waves_enabled, GameMode},
+        playfield::{DefenseLine, PlayfieldBounds, PlayfieldOrientation},
+        rng::GameRng,
+        state::GameState,
+        theme::Theme,
+    },
+    effects::{spawn_effect_text, CameraShake, EffectSlots, FlashScreen, HitFlash},
+    entities::{
+        attack::Chilled,
+        boss::boss_intro_active,
+        collision::{SensorCollisions, GROUP_ENEMY, GROUP_PLAYER},
+        hazard::{slow_factor, SlowZone},
+        pickup::{roll_drop, spawn_drop},
+        player::{death_in_progress, DamagePlayer, ElapsedGameplay, GameStats, Invincible, Player},
+        projectile::Ranged,
+        spawner::IntervalSpawner,
+        unlock::Unlocks,
+        wall::Wall,
+    },
+    interpolation::{FixedMovementSet, Interpolated, YSorted},
+    pause::{PausableTimers, PauseAppExt, RestartSet},
     GameplayStart,
 };
 
+const PLACEHOLDER_ENEMY: &str = "placeholder";
+const SEPARATION_DISTANCE: f32 = 24.0;
+const SEPARATION_SPEED: f32 = 30.0;
+const FRENZY_SPEED_CAP: f32 = 2.0;
+const CORPSE_GRAVITY: f32 = 900.0;
+const CORPSE_OFFSCREEN_MARGIN: f32 = 200.0;
+const GRAZE_CONTACT: f32 = 22.0;
+const GRAZE_RADIUS: f32 = 40.0;
+const GRAZE_BONUS: i32 = 1;
+const LINGER_INTERVAL: f32 = 1.0;
+const LINGER_REACH: f32 = 40.0;
+const BLINK_FADE: f32 = 0.15;
+const BLINK_LINE_MARGIN: f32 = 2.0;
+
 pub struct EnemySpawnPlugin;
 
+// movement runs in FixedUpdate ahead of Update, so an enemy spawned this frame
+// is moved and then collided with next frame before anything cleans it up
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum EnemySet {
+    Spawn,
+    React,
+    Cleanup,
+}
+
 #[derive(Component)]
 pub struct Enemy {
     pub name: String,
-    speed: f32,
+    pub speed: f32,
+    pub health: i32,
+    pub reward: i32,
 }
 
 impl Enemy {
-    pub fn new(name: &str) -> Self {
+    pub fn new(name: &str, info: &EnemyInfo) -> Self {
         Self {
             name: name.to_string(),
-            speed: 75.0,
+            speed: info.speed,
+            health: info.health,
+            reward: info.reward,
         }
     }
 }
 
-#[derive(Resource)]
-struct EnemySpawnData {
-    curr_spawned: i32,
+#[derive(Clone)]
+pub struct EnemyInfo {
+    pub health: i32,
+    pub speed: f32,
+    pub reward: i32,
+    pub weight: f32,
+    pub wall_damage: i32,
+    pub unlock_score: Option<i32>,
+    pub max_concurrent: Option<usize>,
+    pub frenzy: Option<f32>,
+    pub drain_aura: Option<DrainAuraEntry>,
+    pub blinker: Option<BlinkerEntry>,
+    pub aggro: Option<AggroEntry>,
+    pub ranged: Option<RangedEntry>,
+    pub drops: Vec<DropEntry>,
+    pub death_style: DeathStyle,
+    pub contact_behavior: ContactBehavior,
+}
+
+#[derive(Component)]
+struct Graze {
+    closest: f32,
+    done: bool,
+}
+
+impl Default for Graze {
+    fn default() -> Self {
+        Self {
+            closest: f32::MAX,
+            done: false,
+        }
+    }
+}
+
+enum LingerTarget {
+    Player,
+    Wall,
+}
+
+// a lingering enemy has stopped at the hero or the line and hits it on a
+// timer until it is killed
+#[derive(Component)]
+struct Lingering {
+    target: LingerTarget,
+    timer: Timer,
+}
+
+impl Lingering {
+    fn new(target: LingerTarget) -> Self {
+        Self {
+            target,
+            timer: Timer::from_seconds(LINGER_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+// slipping past the line: already counted as lost, no longer hittable, and
+// gone once the fade finishes
+#[derive(Component)]
+pub struct Exiting {
     timer: Timer,
 }
 
+#[derive(Component)]
+struct Launched {
+    velocity: Vec2,
+    spin: f32,
+}
+
+#[derive(Component)]
+pub struct DrainAura {
+    pub radius: f32,
+    pub drain_per_sec: f32,
+    drained: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BlinkPhase {
+    Walking,
+    FadingOut,
+    FadingIn,
+}
+
+// jumps `distance` towards the line every `interval`, fading out first and
+// back in after, and can't be hit until it has fully reappeared
+#[derive(Component)]
+pub struct Blinker {
+    pub distance: f32,
+    interval: Timer,
+    fade: Timer,
+    phase: BlinkPhase,
+}
+
+impl Blinker {
+    pub fn new(interval: f32, distance: f32) -> Self {
+        Self {
+            distance,
+            interval: Timer::from_seconds(interval, TimerMode::Repeating),
+            fade: Timer::from_seconds(BLINK_FADE, TimerMode::Once),
+            phase: BlinkPhase::Walking,
+        }
+    }
+
+    pub fn is_blinking(&self) -> bool {
+        self.phase != BlinkPhase::Walking
+    }
+}
+
+// once the hero comes within `range` the enemy switches to its alert walk
+// and speeds up, and stays that way for the rest of its life
+#[derive(Component)]
+struct Aggro {
+    range: f32,
+    speed_mult: f32,
+    alerted: bool,
+}
+
+#[derive(Event)]
+pub struct DamageEnemy {
+    pub entity: Entity,
+    pub amount: i32,
+}
+
+#[derive(Component)]
+pub struct Frenzy {
+    pub speed_gain: f32,
+    base_speed: f32,
+    max_speed: f32,
+}
+
+impl Frenzy {
+    pub fn new(speed_gain: f32, base_speed: f32) -> Self {
+        Self {
+            speed_gain,
+            base_speed,
+            max_speed: base_speed * FRENZY_SPEED_CAP,
+        }
+    }
+
+    pub fn enrage(&self, speed: f32) -> f32 {
+        (speed * (1.0 + self.speed_gain)).min(self.max_speed)
+    }
+
+    pub fn tint(&self, speed: f32) -> Color {
+        let range = (self.max_speed - self.base_speed).max(f32::EPSILON);
+        let rage = ((speed - self.base_speed) / range).clamp(0.0, 1.0);
+        Color::rgb(1.0, 1.0 - 0.7 * rage, 1.0 - 0.7 * rage)
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct EnemyTypes {
+    pub types: HashMap<String, EnemyInfo>,
+}
+
+pub fn live_counts<'a>(enemies: impl Iterator<Item = &'a Enemy>) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for enemy in enemies {
+        *counts.entry(enemy.name.clone()).or_default() += 1;
+    }
+    counts
+}
+
+impl EnemyTypes {
+    // types already at their `max_concurrent` are left out of the draw, and if
+    // that leaves nothing the caller skips the spawn
+    pub fn choose(
+        &self,
+        unlocks: &Unlocks,
+        live: &HashMap<String, usize>,
+        rng: &mut impl Rng,
+    ) -> Option<&String> {
+        let available: Vec<(&String, &EnemyInfo)> = self
+            .types
+            .iter()
+            .filter(|(name, info)| unlocks.is_unlocked(name, info))
+            .filter(|(name, info)| {
+                info.max_concurrent
+                    .is_none_or(|max| live.get(*name).copied().unwrap_or(0) < max)
+            })
+            .collect();
+        available
+            .choose_weighted(rng, |(_, info)| info.weight)
+            .ok()
+            .map(|(name, _)| *name)
+    }
+}
+
+#[derive(Resource)]
+pub struct SpawnConfig {
+    pub interval: f32,
+    pub max_live_enemies: usize,
+    pub grace_period: f32,
+    pub grace_interval: f32,
+    pub exit_fade: f32,
+}
+
+impl Default for SpawnConfig {
+    fn default() -> Self {
+        Self {
+            interval: 0.1,
+            max_live_enemies: 50,
+            grace_period: 10.0,
+            grace_interval: 0.6,
+            exit_fade: 0.2,
+        }
+    }
+}
+
+impl SpawnConfig {
+    // eases from the gentle grace interval to the normal one, anything that
+    // ramps difficulty should build on this rather than `interval`
+    pub fn interval_at(&self, elapsed: f32) -> f32 {
+        if self.grace_period <= 0.0 {
+            return self.interval;
+        }
+        let t = (elapsed / self.grace_period).clamp(0.0, 1.0);
+        self.grace_interval + (self.interval - self.grace_interval) * t
+    }
+}
+
+#[derive(Resource)]
+pub struct EnemySpawnData {
+    pub curr_spawned: i32,
+    spawner: IntervalSpawner,
+}
+
+// debug practice mode: the timers stop and enemies only arrive on request
+#[derive(Resource, Default)]
+pub struct ManualSpawn(pub bool);
+
+pub fn manual_spawn(manual: Res<ManualSpawn>) -> bool {
+    manual.0
+}
+
+// debug sandbox: nothing new arrives but everything already out keeps going
+#[derive(Resource)]
+pub struct SpawningEnabled(pub bool);
+
+impl Default for SpawningEnabled {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+pub fn spawning_enabled(spawning: Res<SpawningEnabled>) -> bool {
+    spawning.0
+}
+
+impl PausableTimers for EnemySpawnData {
+    fn timers_mut(&mut self) -> Vec<&mut Timer> {
+        vec![&mut self.spawner.timer]
+    }
+}
+
+impl EnemySpawnData {
+    pub fn reset_timer(&mut self) {
+        self.spawner.reset();
+    }
+}
+
 impl Default for EnemySpawnData {
     fn default() -> Self {
         Self {
             curr_spawned: 0,
-            timer: Timer::new(Duration::from_secs_f32(0.1), TimerMode::Repeating),
+            spawner: IntervalSpawner::new(0.1),
         }
     }
 }
 
 impl Plugin for EnemySpawnPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(EnemySpawnData::default())
+        app.add_event::<DamageEnemy>()
+            .insert_resource(EnemySpawnData::default())
+            .preserve_timers_on_pause::<EnemySpawnData>()
+            .reset_on_restart::<EnemySpawnData>()
+            .add_systems(
+                OnEnter(GameState::GamePlay),
+                despawn_enemies.in_set(RestartSet),
+            )
+            .init_resource::<SpawnConfig>()
+            .init_resource::<ManualSpawn>()
+            .init_resource::<SpawningEnabled>()
+            .init_resource::<EnemyTypes>()
+            .configure_sets(
+                Update,
+                (EnemySet::Spawn, EnemySet::React, EnemySet::Cleanup).chain(),
+            )
+            .add_systems(
+                Update,
+                apply_deferred
+                    .after(EnemySet::Spawn)
+                    .before(EnemySet::React),
+            )
             .add_systems(
                 Update,
                 (
-                    move_enemies,
-                    spawn_enemy,
-                    remove_enemies,
-                    react_to_collision,
+                    spawn_enemy.in_set(EnemySet::Spawn).run_if(
+                        not(waves_enabled)
+                            .and_then(not(death_in_progress))
+                            .and_then(not(manual_spawn))
+                            .and_then(spawning_enabled)
+                            .and_then(not(boss_intro_active)),
+                    ),
+                    remove_enemies.in_set(EnemySet::Cleanup),
+                    (react_to_collision, apply_enemy_damage)
+                        .chain()
+                        .in_set(EnemySet::React),
+                    (start_lingering, linger_attack).chain(),
+                    fade_exiting,
+                    separate_enemies,
+                    drain_player,
+                    blink_enemies,
+                    notice_player,
+                    award_grazes,
+                    launch_corpses,
                 )
                     .run_if(in_state(GameState::GamePlay)),
             )
+            .add_systems(
+                FixedUpdate,
+                (move_enemies, fly_corpses)
+                    .in_set(FixedMovementSet)
+                    .run_if(in_state(GameState::GamePlay)),
+            )
             .add_systems(
                 Update,
-                load_enemy_animations.run_if(in_state(GameState::Loading)),
+                load_enemy_animations
+                    .run_if(in_state(GameState::Loading).and_then(animation_list_valid)),
             );
     }
 }
 
-fn spawn_enemy(
-    time: Res<Time>,
-    mut commands: Commands,
-    mut spawn_data: ResMut<EnemySpawnData>,
-    mut status: ResMut<GameStats>,
-    gameplay_start: Res<GameplayStart>,
-    enemy_anims: Res<EnemyAnimations>,
-) {
-    spawn_data.timer.tick(time.delta());
-    if spawn_data.timer.just_finished() {
-        let mut rng = thread_rng();
-        let enemy_name = enemy_anims.enemies.keys().choose(&mut rng).unwrap();
-        let anim = enemy_anims.enemies.get(enemy_name).unwrap();
-        commands.spawn((
+pub fn spawn_named_enemy(
+    commands: &mut Commands,
+    enemy_anims: &EnemyAnimations,
+    enemy: Enemy,
+    info: &EnemyInfo,
+    position: Vec3,
+    orientation: PlayfieldOrientation,
+) -> Entity {
+    let anim = enemy_anims.enemies.get(&enemy.name).unwrap();
+    let speed = enemy.speed;
+    let entity = commands
+        .spawn((
             SpriteSheetBundle {
+                sprite: TextureAtlasSprite {
+                    flip_x: orientation.flip_x(),
+                    ..default()
+                },
                 texture_atlas: anim.get_handle(AnimState::Walking).unwrap(),
-                transform: Transform::from_translation(Vec3::new(
-                    gameplay_start.camera_endpos.x + 450.0,
-                    rng.gen_range(-250.0..250.0),
-                    0.,
-                ))
-                .with_scale(Vec3::splat(2.0)),
+                transform: Transform::from_translation(position).with_scale(Vec3::splat(2.0)),
                 ..default()
             },
             AnimationComponent::default(),
-            Enemy::new(enemy_name),
+            anim.frame_times.clone(),
+            Graze::default(),
+            info.death_style,
+            info.contact_behavior,
+            enemy,
+            Interpolated::new(position),
+            YSorted,
             RigidBody::KinematicPositionBased,
             Collider::cuboid(6.0, 7.0),
             Sensor,
             ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_KINEMATIC,
             ActiveEvents::COLLISION_EVENTS,
-            CollisionGroups::new(Group::GROUP_1, Group::GROUP_2),
-        ));
+            CollisionGroups::new(GROUP_ENEMY, GROUP_PLAYER),
+        ))
+        .id();
+    if let Some(speed_gain) = info.frenzy {
+        commands
+            .entity(entity)
+            .insert(Frenzy::new(speed_gain, speed));
+    }
+    if let Some(blinker) = info.blinker {
+        commands
+            .entity(entity)
+            .insert(Blinker::new(blinker.interval, blinker.distance));
+    }
+    if let Some(aggro) = info.aggro {
+        commands.entity(entity).insert(Aggro {
+            range: aggro.range,
+            speed_mult: aggro.speed_mult,
+            alerted: false,
+        });
+    }
+    if let Some(ranged) = info.ranged {
+        commands.entity(entity).insert(Ranged::new(ranged));
+    }
+    if let Some(aura) = info.drain_aura {
+        commands.entity(entity).insert(DrainAura {
+            radius: aura.radius,
+            drain_per_sec: aura.drain_per_sec,
+            drained: 0.0,
+        });
+    }
+    entity
+}
+
+fn spawn_enemy(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut spawn_data: ResMut<EnemySpawnData>,
+    mut status: ResMut<GameStats>,
+    config: Res<SpawnConfig>,
+    elapsed: Res<ElapsedGameplay>,
+    gameplay_start: Res<GameplayStart>,
+    bounds: Res<PlayfieldBounds>,
+    orientation: Res<PlayfieldOrientation>,
+    enemy_anims: Res<EnemyAnimations>,
+    enemy_types: Res<EnemyTypes>,
+    unlocks: Res<Unlocks>,
+    mut game_rng: ResMut<GameRng>,
+    live: Query<&Enemy>,
+) {
+    spawn_data
+        .spawner
+        .set_interval(config.interval_at(elapsed.seconds));
+    if spawn_data.spawner.tick(time.delta()) > 0 && live.iter().count() < config.max_live_enemies {
+        let rng = game_rng.rng();
+        let Some(enemy_name) = enemy_types.choose(&unlocks, &live_counts(live.iter()), rng) else {
+            return;
+        };
+        let info = &enemy_types.types[enemy_name];
+        spawn_named_enemy(
+            &mut commands,
+            &enemy_anims,
+            Enemy::new(enemy_name, info),
+            info,
+            Vec3::new(
+                bounds.spawn_x(gameplay_start.camera_endpos.x, *orientation),
+                rng.gen_range(bounds.min_y..bounds.max_y),
+                0.,
+            ),
+            *orientation,
+        );
         spawn_data.curr_spawned += 1;
         status.entites_spawned += 1;
     }
 }
 
+fn despawn_enemies(mut commands: Commands, enemies: Query<Entity, With<Enemy>>) {
+    for entity in &enemies {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
 fn move_enemies(
     mut commands: Commands,
     time: Res<Time>,
-    camerapos: Res<GameplayStart>,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    defense_line: Res<DefenseLine>,
+    orientation: Res<PlayfieldOrientation>,
+    mode: Res<GameMode>,
+    config: Res<SpawnConfig>,
+    enemy_types: Res<EnemyTypes>,
+    mut wall: ResMut<Wall>,
     mut stats: ResMut<GameStats>,
-    mut enemies: Query<(Entity, &Enemy, &mut Transform, &AnimationComponent)>,
-) {
-    for (entity, enemy, mut transform, anim) in enemies.iter_mut() {
-        if anim.state == AnimState::Walking {
-            transform.translation.x -= enemy.speed * time.delta_seconds();
-            if transform.translation.x <= camerapos.camera_endpos.x - 450.0 {
-                commands.entity(entity).despawn();
-                stats.villagers_lost += 1;
+    (mut flash, mut shake): (ResMut<FlashScreen>, ResMut<CameraShake>),
+    mut effects: EffectSlots,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut enemies: Query<
+        (
+            Entity,
+            &Enemy,
+            &mut Transform,
+            &mut AnimationComponent,
+            &ContactBehavior,
+            Option<&Chilled>,
+        ),
+        Without<Exiting>,
+    >,
+    zones: Query<&SlowZone>,
+) {
+    for (entity, enemy, mut transform, mut anim, behavior, chilled) in enemies.iter_mut() {
+        if anim.state != AnimState::Walking {
+            continue;
+        }
+        let speed = enemy.speed
+            * chilled.map_or(1.0, Chilled::speed_factor)
+            * slow_factor(zones.iter(), transform.translation.truncate());
+        transform.translation.x -= speed * orientation.sign() * time.delta_seconds();
+        if !defense_line.has_crossed(transform.translation.x, *orientation) {
+            continue;
+        }
+        if *behavior == ContactBehavior::Linger {
+            // holds at the line and batters the wall instead of slipping past
+            transform.translation.x = defense_line.x;
+            anim.state = AnimState::Attacking;
+            commands
+                .entity(entity)
+                .insert(Lingering::new(LingerTarget::Wall));
+            continue;
+        }
+        commands
+            .entity(entity)
+            .remove::<Collider>()
+            .remove::<ActiveCollisionTypes>()
+            .remove::<ActiveEvents>()
+            .remove::<CollisionGroups>()
+            .insert(Exiting {
+                timer: Timer::from_seconds(config.exit_fade, TimerMode::Once),
+            });
+        stats.villagers_lost += 1;
+        wall.damage(
+            enemy_types
+                .types
+                .get(&enemy.name)
+                .map_or(1, |info| info.wall_damage),
+        );
+        flash.trigger(Color::RED);
+        shake.add(0.4);
+        let mut style = theme.hud_style(&asset_server);
+        style.color = Color::RED;
+        spawn_effect_text(
+            &mut commands,
+            &mut effects,
+            Vec3::new(defense_line.x, transform.translation.y, 10.0),
+            "-1",
+            style,
+            Vec2::new(0.0, 40.0),
+            1.0,
+        );
+        if wall.is_breached() || mode.villagers_exhausted(stats.villagers_lost) {
+            next_state.set(GameState::GameOver);
+        }
+    }
+}
+
+fn fade_exiting(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut enemies: Query<(Entity, &mut Exiting, &mut TextureAtlasSprite)>,
+) {
+    for (entity, mut exiting, mut sprite) in &mut enemies {
+        if exiting.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        sprite.color.set_a(1.0 - exiting.timer.percent());
+    }
+}
+
+fn start_lingering(
+    mut commands: Commands,
+    mut collisions: SensorCollisions,
+    player: Query<(), With<Player>>,
+    mut enemies: Query<(Entity, &ContactBehavior, &mut AnimationComponent), With<Enemy>>,
+) {
+    for (a, b) in collisions.started() {
+        let other = if player.contains(a) {
+            b
+        } else if player.contains(b) {
+            a
+        } else {
+            continue;
+        };
+        let Ok((entity, behavior, mut anim)) = enemies.get_mut(other) else {
+            continue;
+        };
+        if *behavior == ContactBehavior::Linger && anim.state == AnimState::Walking {
+            anim.state = AnimState::Attacking;
+            commands
+                .entity(entity)
+                .insert(Lingering::new(LingerTarget::Player));
+        }
+    }
+}
+
+fn linger_attack(
+    mut commands: Commands,
+    time: Res<Time>,
+    enemy_types: Res<EnemyTypes>,
+    mut wall: ResMut<Wall>,
+    mut flash: ResMut<FlashScreen>,
+    mut shake: ResMut<CameraShake>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut damage: EventWriter<DamagePlayer>,
+    player: Query<&Transform, With<Player>>,
+    mut enemies: Query<
+        (
+            Entity,
+            &Enemy,
+            &Transform,
+            &mut AnimationComponent,
+            &mut Lingering,
+        ),
+        Without<Player>,
+    >,
+) {
+    for (entity, enemy, transform, mut anim, mut lingering) in &mut enemies {
+        // killed mid-attack, the death animation takes over from here
+        if anim.state != AnimState::Attacking {
+            commands.entity(entity).remove::<Lingering>();
+            continue;
+        }
+        if let LingerTarget::Player = lingering.target {
+            let in_reach = player.get_single().is_ok_and(|player| {
+                player
+                    .translation
+                    .truncate()
+                    .distance(transform.translation.truncate())
+                    <= LINGER_REACH
+            });
+            if !in_reach {
+                anim.state = AnimState::Walking;
+                commands.entity(entity).remove::<Lingering>();
+                continue;
+            }
+        }
+        if !lingering.timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+        match lingering.target {
+            LingerTarget::Player => damage.send(DamagePlayer {
+                amount: 1,
+                from_y: None,
+            }),
+            LingerTarget::Wall => {
+                wall.damage(
+                    enemy_types
+                        .types
+                        .get(&enemy.name)
+                        .map_or(1, |info| info.wall_damage),
+                );
+                flash.trigger(Color::RED);
+                shake.add(0.3);
+                if wall.is_breached() {
+                    next_state.set(GameState::GameOver);
+                }
             }
         }
     }
 }
 
+fn separate_enemies(
+    time: Res<Time>,
+    bounds: Res<PlayfieldBounds>,
+    mut enemies: Query<(Entity, &mut Transform, &AnimationComponent), With<Enemy>>,
+) {
+    let positions: Vec<(Entity, Vec2)> = enemies
+        .iter()
+        .filter(|(_, _, anim)| !anim.state.is_dying())
+        .map(|(entity, transform, _)| (entity, transform.translation.truncate()))
+        .collect();
+    let mut offsets = vec![0.0; positions.len()];
+    for i in 0..positions.len() {
+        for j in (i + 1)..positions.len() {
+            let delta = positions[j].1 - positions[i].1;
+            if delta.x.abs() >= SEPARATION_DISTANCE || delta.y.abs() >= SEPARATION_DISTANCE {
+                continue;
+            }
+            let overlap = 1.0 - delta.y.abs() / SEPARATION_DISTANCE;
+            let direction = if delta.y >= 0.0 { 1.0 } else { -1.0 };
+            offsets[i] -= direction * overlap;
+            offsets[j] += direction * overlap;
+        }
+    }
+    for ((entity, _), offset) in positions.iter().zip(offsets) {
+        if offset == 0.0 {
+            continue;
+        }
+        if let Ok((_, mut transform, _)) = enemies.get_mut(*entity) {
+            let y = transform.translation.y + offset * SEPARATION_SPEED * time.delta_seconds();
+            transform.translation.y = bounds.clamp_y(y);
+        }
+    }
+}
+
 fn remove_enemies(
     mut commands: Commands,
     enemies: Query<(Entity, &AnimationComponent)>,
@@ -129,54 +759,339 @@ fn remove_enemies(
 ) {
     for (entity, data) in enemies.iter() {
         if data.state == AnimState::Dead {
-            commands.entity(entity).despawn();
+            commands.entity(entity).despawn_recursive();
             spawn_data.curr_spawned -= 1;
         }
     }
 }
 
+fn launch_corpses(
+    mut commands: Commands,
+    orientation: Res<PlayfieldOrientation>,
+    mut game_rng: ResMut<GameRng>,
+    corpses: Query<(Entity, &DeathStyle, &AnimationComponent), Without<Launched>>,
+) {
+    let rng = game_rng.rng();
+    for (entity, style, anim) in &corpses {
+        if *style == DeathStyle::Launch && anim.state == AnimState::Dying {
+            // thrown back the way it came
+            commands.entity(entity).insert(Launched {
+                velocity: Vec2::new(
+                    rng.gen_range(100.0..200.0) * orientation.sign(),
+                    rng.gen_range(250.0..350.0),
+                ),
+                spin: rng.gen_range(-10.0..10.0),
+            });
+        }
+    }
+}
+
+fn fly_corpses(
+    time: Res<Time>,
+    bounds: Res<PlayfieldBounds>,
+    mut corpses: Query<(&mut Transform, &mut Launched, &mut AnimationComponent)>,
+) {
+    let delta = time.delta_seconds();
+    for (mut transform, mut launched, mut anim) in &mut corpses {
+        launched.velocity.y -= CORPSE_GRAVITY * delta;
+        transform.translation += launched.velocity.extend(0.0) * delta;
+        transform.rotate_z(launched.spin * delta);
+        if transform.translation.y < bounds.min_y - CORPSE_OFFSCREEN_MARGIN {
+            // remove_enemies despawns it and frees the spawn slot
+            anim.state = AnimState::Dead;
+        }
+    }
+}
+
+pub fn kill_enemy(
+    commands: &mut Commands,
+    entity: Entity,
+    enemy: &Enemy,
+    transform: &Transform,
+    handle: &mut Handle<TextureAtlas>,
+    sprite: &mut TextureAtlasSprite,
+    anim: &mut AnimationComponent,
+    anims: &EnemyAnimations,
+    enemy_types: &EnemyTypes,
+    stats: &mut GameStats,
+    rng: &mut impl Rng,
+) {
+    anim.state = AnimState::Dying;
+    sprite.index = 0;
+    *handle = anims
+        .enemies
+        .get(&enemy.name)
+        .unwrap()
+        .get_handle(AnimState::Dying)
+        .unwrap();
+    commands
+        .entity(entity)
+        .remove::<Collider>()
+        .remove::<ActiveCollisionTypes>()
+        .remove::<ActiveEvents>()
+        .remove::<CollisionGroups>();
+    stats.villagers_saved += enemy.reward;
+    stats.enemies_killed += 1;
+    let drops = enemy_types
+        .types
+        .get(&enemy.name)
+        .map_or(&[][..], |info| &info.drops);
+    if let Some(kind) = roll_drop(drops, rng) {
+        spawn_drop(commands, kind, transform.translation);
+    }
+}
+
 fn react_to_collision(
+    mut collisions: SensorCollisions,
+    mut damage: EventWriter<DamageEnemy>,
+    enemies: Query<(), With<Enemy>>,
+) {
+    for (a, b) in collisions.started() {
+        // player attack enemy
+        let entity = if enemies.contains(a) {
+            a
+        } else if enemies.contains(b) {
+            b
+        } else {
+            continue;
+        };
+        damage.send(DamageEnemy { entity, amount: 1 });
+    }
+}
+
+// the jump happens while fully faded out and stops just short of the line,
+// crossing it is left to the normal walk
+fn blink_enemies(
     mut commands: Commands,
-    mut collision_events: EventReader<CollisionEvent>,
-    anims: Res<EnemyAnimations>,
-    mut query: Query<(
-        Entity,
-        &Enemy,
-        &mut Handle<TextureAtlas>,
-        &mut TextureAtlasSprite,
-        &mut AnimationComponent,
-    )>,
-) {
-    for event in collision_events.read() {
-        if let CollisionEvent::Started(a, b, flags) = event {
-            if flags.bits() & 0b01 == 0b01 {
-                // player attack enemy
-                let enemy = if let Ok(result) = query.get_mut(*a) {
-                    Ok(result)
-                } else if let Ok(result) = query.get_mut(*b) {
-                    Ok(result)
-                } else {
-                    Err(())
-                };
-                if let Ok((entity, enemy, mut handle, mut atlas, mut anim)) = enemy {
-                    if !anim.state.is_dying() {
-                        anim.state = AnimState::Dying;
-                        atlas.index = 0;
-                        *handle = anims
-                            .enemies
-                            .get(&enemy.name)
-                            .unwrap()
-                            .get_handle(AnimState::Dying)
-                            .unwrap();
-                        commands
-                            .entity(entity)
-                            .remove::<Collider>()
-                            .remove::<ActiveCollisionTypes>()
-                            .remove::<ActiveEvents>()
-                            .remove::<CollisionGroups>();
-                    }
+    time: Res<Time>,
+    defense_line: Res<DefenseLine>,
+    orientation: Res<PlayfieldOrientation>,
+    mut blinkers: Query<
+        (
+            Entity,
+            &mut Blinker,
+            &mut Transform,
+            &mut Interpolated,
+            &mut TextureAtlasSprite,
+            &AnimationComponent,
+        ),
+        Without<Exiting>,
+    >,
+) {
+    let sign = orientation.sign();
+    for (entity, mut blinker, mut transform, mut interpolated, mut sprite, anim) in &mut blinkers {
+        if anim.state != AnimState::Walking {
+            continue;
+        }
+        match blinker.phase {
+            BlinkPhase::Walking => {
+                if blinker.interval.tick(time.delta()).just_finished() {
+                    blinker.phase = BlinkPhase::FadingOut;
+                    blinker.fade.reset();
+                    commands.entity(entity).insert(ColliderDisabled);
                 }
             }
+            BlinkPhase::FadingOut => {
+                let alpha = 1.0 - blinker.fade.tick(time.delta()).percent();
+                sprite.color.set_a(alpha);
+                if blinker.fade.finished() {
+                    let target = transform.translation.x - blinker.distance * sign;
+                    let stop = defense_line.x + BLINK_LINE_MARGIN * sign;
+                    transform.translation.x = if (target - stop) * sign < 0.0 {
+                        stop
+                    } else {
+                        target
+                    };
+                    *interpolated = Interpolated::new(transform.translation);
+                    blinker.phase = BlinkPhase::FadingIn;
+                    blinker.fade.reset();
+                }
+            }
+            BlinkPhase::FadingIn => {
+                let alpha = blinker.fade.tick(time.delta()).percent();
+                sprite.color.set_a(alpha);
+                if blinker.fade.finished() {
+                    blinker.phase = BlinkPhase::Walking;
+                    commands.entity(entity).remove::<ColliderDisabled>();
+                }
+            }
+        }
+    }
+}
+
+// an enemy without an alert sheet keeps its walk and only picks up the speed
+fn notice_player(
+    anims: Res<EnemyAnimations>,
+    player: Query<&Transform, With<Player>>,
+    mut enemies: Query<
+        (
+            &mut Enemy,
+            &Transform,
+            &AnimationComponent,
+            &mut Aggro,
+            &mut Handle<TextureAtlas>,
+        ),
+        (Without<Player>, Without<Exiting>),
+    >,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    let player = player.translation.truncate();
+    for (mut enemy, transform, anim, mut aggro, mut handle) in &mut enemies {
+        if aggro.alerted
+            || anim.state.is_dying()
+            || transform.translation.truncate().distance(player) > aggro.range
+        {
+            continue;
+        }
+        aggro.alerted = true;
+        enemy.speed *= aggro.speed_mult;
+        if let Some(alert) = anims
+            .enemies
+            .get(&enemy.name)
+            .and_then(|handles| handles.get_named(ALERT_ANIM))
+        {
+            *handle = alert;
+        }
+    }
+}
+
+fn apply_enemy_damage(
+    mut commands: Commands,
+    mut damage: EventReader<DamageEnemy>,
+    anims: Res<EnemyAnimations>,
+    enemy_types: Res<EnemyTypes>,
+    mut stats: ResMut<GameStats>,
+    mut game_rng: ResMut<GameRng>,
+    mut sfx: EventWriter<Sfx>,
+    mut query: Query<
+        (
+            &mut Enemy,
+            &Transform,
+            &mut Handle<TextureAtlas>,
+            &mut TextureAtlasSprite,
+            &mut AnimationComponent,
+            Option<&Frenzy>,
+            Option<&HitFlash>,
+            Option<&Blinker>,
+        ),
+        Without<Exiting>,
+    >,
+) {
+    for event in damage.read() {
+        let Ok((mut enemy, transform, mut handle, mut atlas, mut anim, frenzy, hit_flash, blinker)) =
+            query.get_mut(event.entity)
+        else {
+            continue;
+        };
+        if anim.state.is_dying() || blinker.is_some_and(Blinker::is_blinking) {
+            continue;
+        }
+        enemy.health -= event.amount;
+        if enemy.health > 0 {
+            // a second hit mid-flash must not keep the flash color for good
+            let mut restore = hit_flash.map_or(atlas.color, HitFlash::restore);
+            if let Some(frenzy) = frenzy {
+                enemy.speed = frenzy.enrage(enemy.speed);
+                restore = frenzy.tint(enemy.speed);
+            }
+            commands.entity(event.entity).insert(HitFlash::new(restore));
+            sfx.send(Sfx::Hit);
+            continue;
+        }
+        sfx.send(Sfx::Death);
+        kill_enemy(
+            &mut commands,
+            event.entity,
+            &enemy,
+            transform,
+            &mut handle,
+            &mut atlas,
+            &mut anim,
+            &anims,
+            &enemy_types,
+            &mut stats,
+            game_rng.rng(),
+        );
+    }
+}
+
+fn drain_player(
+    time: Res<Time>,
+    mut gizmos: Gizmos,
+    mut damage: EventWriter<DamagePlayer>,
+    player: Query<(&Transform, Option<&Invincible>), With<Player>>,
+    mut auras: Query<(&Transform, &AnimationComponent, &mut DrainAura)>,
+) {
+    let Ok((player_transform, invincible)) = player.get_single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    for (transform, anim, mut aura) in &mut auras {
+        let pos = transform.translation.truncate();
+        if anim.state.is_dying() || pos.distance(player_pos) > aura.radius {
+            continue;
+        }
+        gizmos.line_2d(pos, player_pos, Color::PURPLE);
+        if invincible.is_some() {
+            continue;
+        }
+        aura.drained += aura.drain_per_sec * time.delta_seconds();
+        if aura.drained >= 1.0 {
+            aura.drained -= 1.0;
+            damage.send(DamagePlayer {
+                amount: 1,
+                from_y: None,
+            });
+        }
+    }
+}
+
+// settled once the enemy is behind the player, so an enemy that comes close
+// and then hits them never counts
+fn award_grazes(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    orientation: Res<PlayfieldOrientation>,
+    mut stats: ResMut<GameStats>,
+    mut effects: EffectSlots,
+    player: Query<(&Transform, Option<&Invincible>), With<Player>>,
+    mut enemies: Query<(&Transform, &AnimationComponent, &mut Graze), With<Enemy>>,
+) {
+    let Ok((player_transform, invincible)) = player.get_single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    for (transform, anim, mut graze) in &mut enemies {
+        if graze.done || anim.state.is_dying() {
+            continue;
+        }
+        let pos = transform.translation.truncate();
+        let distance = pos.distance(player_pos);
+        if invincible.is_some() && distance < GRAZE_RADIUS {
+            graze.done = true;
+            continue;
+        }
+        graze.closest = graze.closest.min(distance);
+        if (pos.x - player_pos.x) * orientation.sign() >= 0.0 {
+            continue;
+        }
+        graze.done = true;
+        if (GRAZE_CONTACT..GRAZE_RADIUS).contains(&graze.closest) {
+            stats.villagers_saved += GRAZE_BONUS;
+            let mut style = theme.hud_style(&asset_server);
+            style.color = Color::YELLOW;
+            spawn_effect_text(
+                &mut commands,
+                &mut effects,
+                pos.extend(10.0),
+                format!("+{} graze", GRAZE_BONUS),
+                style,
+                Vec2::new(0.0, 30.0),
+                0.8,
+            );
         }
     }
 }
@@ -186,14 +1101,19 @@ fn load_enemy_animations(
     asset_server: Res<AssetServer>,
     anim_assets: ResMut<Assets<AnimationListAsset>>,
     mut images_to_load: ResMut<ImagesToLoad>,
+    mut images: ResMut<Assets<Image>>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
     mut enemy_anims: ResMut<EnemyAnimations>,
+    mut enemy_types: ResMut<EnemyTypes>,
 ) {
-    if !asset_server.is_loaded_with_dependencies(&list.handle) {
+    // the list has no dependencies of its own, so it is ready as soon as it
+    // is in the assets
+    if list.loaded_enemies {
         return;
     }
-    let anim_list = anim_assets.get(&list.handle);
-    let anim_list = anim_list.unwrap();
+    let Some(anim_list) = anim_assets.get(&list.handle) else {
+        return;
+    };
     let mut anim_map: HashMap<String, AnimationHandles> = HashMap::new();
     for enemy in anim_list.enemies.iter() {
         let mut image_handles: HashMap<String, Handle<TextureAtlas>> = HashMap::new();
@@ -214,8 +1134,814 @@ fn load_enemy_animations(
             );
             image_handles.insert(name.clone(), texture_atlases.add(texture_atlas));
         }
-        anim_map.insert(enemy.name.clone(), AnimationHandles::new(image_handles));
+        anim_map.insert(
+            enemy.name.clone(),
+            AnimationHandles::new(image_handles, enemy.frame_times.clone()),
+        );
+        enemy_types.types.insert(
+            enemy.name.clone(),
+            EnemyInfo {
+                health: enemy.health,
+                speed: enemy.speed,
+                reward: enemy.reward,
+                weight: enemy.weight,
+                wall_damage: enemy.wall_damage,
+                unlock_score: enemy.unlock_score,
+                max_concurrent: enemy.max_concurrent,
+                frenzy: enemy.frenzy,
+                drain_aura: enemy.drain_aura,
+                blinker: enemy.blinker,
+                aggro: enemy.aggro,
+                ranged: enemy.ranged,
+                drops: enemy.drops.clone(),
+                death_style: enemy.death_style,
+                contact_behavior: enemy.contact_behavior,
+            },
+        );
+    }
+    if anim_map.is_empty() {
+        warn!("no enemies configured in the animation list, spawning magenta placeholders");
+        anim_map.insert(
+            PLACEHOLDER_ENEMY.to_string(),
+            placeholder_animations(&mut images, &mut texture_atlases),
+        );
+        enemy_types.types.insert(
+            PLACEHOLDER_ENEMY.to_string(),
+            EnemyInfo {
+                health: 1,
+                speed: 75.0,
+                reward: 1,
+                weight: 1.0,
+                wall_damage: 1,
+                unlock_score: None,
+                max_concurrent: None,
+                frenzy: None,
+                drain_aura: None,
+                blinker: None,
+                aggro: None,
+                ranged: None,
+                drops: Vec::new(),
+                death_style: DeathStyle::Flash,
+                contact_behavior: ContactBehavior::PassThrough,
+            },
+        );
     }
     enemy_anims.enemies = anim_map;
     list.loaded_enemies = true;
 }
+
+fn placeholder_animations(
+    images: &mut Assets<Image>,
+    texture_atlases: &mut Assets<TextureAtlas>,
+) -> AnimationHandles {
+    let image = images.add(placeholder_image(64, 16));
+    let atlas = texture_atlases.add(TextureAtlas::from_grid(
+        image,
+        Vec2::new(16.0, 16.0),
+        4,
+        1,
+        None,
+        None,
+    ));
+    let mut handles = AnimationHandles::default();
+    handles.add_handle(AnimState::Walking.to_string(), atlas.clone());
+    handles.add_handle(AnimState::Dying.to_string(), atlas);
+    handles
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+    use crate::{
+        data::mode::VILLAGER_LIMIT,
+        effects::{CameraShake, FlashScreen},
+        entities::{
+            boss::BossIntro,
+            player::{DeathSequence, PlayerData},
+        },
+        menu::MenuItem,
+        testing::{advance, advance_secs, collide, headless_app, stub_enemy_types, TEST_ENEMY},
+    };
+
+    fn damage_sent(app: &App) -> Vec<Entity> {
+        let events = app.world.resource::<Events<DamageEnemy>>();
+        events.get_reader().read(events).map(|e| e.entity).collect()
+    }
+
+    #[test]
+    fn attack_collision_damages_the_enemy_once() {
+        let mut app = headless_app();
+        app.add_event::<DamageEnemy>()
+            .add_systems(Update, react_to_collision);
+        let info = app.world.resource::<EnemyTypes>().types[TEST_ENEMY].clone();
+        let enemy = app.world.spawn(Enemy::new(TEST_ENEMY, &info)).id();
+        let attack = app.world.spawn_empty().id();
+
+        // rapier can report the same overlap both ways round in one frame
+        collide(&mut app, attack, enemy);
+        collide(&mut app, enemy, attack);
+        advance(&mut app, 1);
+
+        assert_eq!(damage_sent(&app), vec![enemy]);
+    }
+
+    // an enemy with everything apply_enemy_damage looks at
+    fn spawn_target(app: &mut App, health: i32) -> Entity {
+        let info = app.world.resource::<EnemyTypes>().types[TEST_ENEMY].clone();
+        let mut enemy = Enemy::new(TEST_ENEMY, &info);
+        enemy.health = health;
+        app.world
+            .spawn((
+                enemy,
+                Transform::default(),
+                Handle::<TextureAtlas>::default(),
+                TextureAtlasSprite::default(),
+                AnimationComponent::new(AnimState::Walking),
+            ))
+            .id()
+    }
+
+    #[test]
+    fn frenzied_enemy_speeds_up_when_wounded() {
+        let mut app = headless_app();
+        app.add_event::<DamageEnemy>()
+            .add_systems(Update, apply_enemy_damage);
+        let enemy = spawn_target(&mut app, 3);
+        let speed = app.world.get::<Enemy>(enemy).unwrap().speed;
+        app.world.entity_mut(enemy).insert(Frenzy::new(0.5, speed));
+
+        app.world.send_event(DamageEnemy {
+            entity: enemy,
+            amount: 1,
+        });
+        advance(&mut app, 1);
+
+        let wounded = app.world.get::<Enemy>(enemy).unwrap();
+        assert_eq!(wounded.health, 2);
+        assert_eq!(wounded.speed, speed * 1.5);
+        let flash = app.world.get::<HitFlash>(enemy).unwrap();
+        assert!(flash.restore().g() < 1.0);
+    }
+
+    fn health(app: &App) -> i32 {
+        app.world.resource::<PlayerData>().health
+    }
+
+    // stands in for the player's own damage handling
+    fn take_damage(mut damage: EventReader<DamagePlayer>, mut player_data: ResMut<PlayerData>) {
+        for event in damage.read() {
+            player_data.health -= event.amount;
+        }
+    }
+
+    #[test]
+    fn drain_aura_stops_once_its_enemy_is_killed() {
+        let mut app = headless_app();
+        app.add_event::<DamageEnemy>().add_systems(
+            Update,
+            (drain_player, apply_enemy_damage, take_damage).chain(),
+        );
+        app.world.spawn((Player, Transform::default()));
+        let enemy = spawn_target(&mut app, 1);
+        app.world.entity_mut(enemy).insert((
+            Transform::from_xyz(50.0, 0.0, 0.0),
+            DrainAura {
+                radius: 100.0,
+                drain_per_sec: 4.0,
+                drained: 0.0,
+            },
+        ));
+
+        let full = health(&app);
+        advance_secs(&mut app, 1.0);
+        let drained = health(&app);
+        assert!(drained < full);
+
+        app.world.send_event(DamageEnemy {
+            entity: enemy,
+            amount: 1,
+        });
+        advance_secs(&mut app, 1.0);
+        assert_eq!(health(&app), drained);
+    }
+
+    #[test]
+    fn lingering_enemy_stops_and_hits_on_an_interval_until_killed() {
+        let mut app = headless_app();
+        app.init_resource::<Wall>()
+            .init_resource::<FlashScreen>()
+            .init_resource::<CameraShake>()
+            .add_event::<DamageEnemy>()
+            .add_systems(
+                Update,
+                (
+                    move_enemies,
+                    (start_lingering, linger_attack).chain(),
+                    apply_enemy_damage,
+                    take_damage,
+                )
+                    .chain(),
+            );
+        let sign = app.world.resource::<PlayfieldOrientation>().sign();
+        let x = app.world.resource::<DefenseLine>().x + 100.0 * sign;
+        let player = app
+            .world
+            .spawn((Player, Transform::from_xyz(x, 0.0, 0.0)))
+            .id();
+        let enemy = spawn_target(&mut app, 1);
+        app.world.entity_mut(enemy).insert((
+            Transform::from_xyz(x + 20.0 * sign, 0.0, 0.0),
+            ContactBehavior::Linger,
+        ));
+        collide(&mut app, player, enemy);
+        advance(&mut app, 1);
+        let x = |app: &App| app.world.get::<Transform>(enemy).unwrap().translation.x;
+        let stopped_at = x(&app);
+
+        let full = health(&app);
+        advance_secs(&mut app, LINGER_INTERVAL * 2.5);
+        assert_eq!(x(&app), stopped_at);
+        assert_eq!(health(&app), full - 2);
+
+        // still fair game for the hero's attacks while it stands there
+        app.world.send_event(DamageEnemy {
+            entity: enemy,
+            amount: 1,
+        });
+        advance_secs(&mut app, LINGER_INTERVAL * 2.0);
+        assert_eq!(health(&app), full - 2);
+        assert!(app.world.get::<Lingering>(enemy).is_none());
+    }
+
+    #[test]
+    fn blinker_jumps_forward_and_cannot_be_hit_mid_blink() {
+        let mut app = headless_app();
+        app.add_event::<DamageEnemy>()
+            .add_systems(Update, (blink_enemies, apply_enemy_damage).chain());
+        let sign = app.world.resource::<PlayfieldOrientation>().sign();
+        let start = Vec3::new(
+            app.world.resource::<DefenseLine>().x + 300.0 * sign,
+            0.0,
+            0.0,
+        );
+        let enemy = spawn_target(&mut app, 2);
+        app.world.entity_mut(enemy).insert((
+            Transform::from_translation(start),
+            Interpolated::new(start),
+            Blinker::new(1.0, 50.0),
+        ));
+        let hit = |app: &mut App| {
+            app.world.send_event(DamageEnemy {
+                entity: enemy,
+                amount: 1,
+            });
+            advance(app, 1);
+            app.world.get::<Enemy>(enemy).unwrap().health
+        };
+
+        advance_secs(&mut app, 1.0);
+        assert!(app.world.get::<ColliderDisabled>(enemy).is_some());
+        assert_eq!(hit(&mut app), 2);
+
+        advance_secs(&mut app, BLINK_FADE * 2.0);
+        let x = app.world.get::<Transform>(enemy).unwrap().translation.x;
+        assert_eq!(x, start.x - 50.0 * sign);
+        assert!(app.world.get::<ColliderDisabled>(enemy).is_none());
+        assert_eq!(hit(&mut app), 1);
+    }
+
+    #[test]
+    fn launched_corpse_flies_off_and_is_despawned() {
+        let mut app = headless_app();
+        app.add_event::<DamageEnemy>().add_systems(
+            Update,
+            (
+                apply_enemy_damage,
+                launch_corpses,
+                fly_corpses,
+                remove_enemies,
+            )
+                .chain(),
+        );
+        app.world.resource_mut::<EnemySpawnData>().curr_spawned = 1;
+        let enemy = spawn_target(&mut app, 1);
+        app.world.entity_mut(enemy).insert(DeathStyle::Launch);
+        app.world.send_event(DamageEnemy {
+            entity: enemy,
+            amount: 1,
+        });
+        advance(&mut app, 2);
+
+        let launched = app.world.get::<Launched>(enemy).unwrap();
+        assert!(launched.velocity.y > 0.0);
+        assert!(launched.spin != 0.0);
+        advance(&mut app, 5);
+        let transform = app.world.get::<Transform>(enemy).unwrap();
+        assert!(transform.translation.y > 0.0);
+        assert!(transform.rotation != Quat::IDENTITY);
+
+        advance_secs(&mut app, 5.0);
+        assert!(app.world.get_entity(enemy).is_none());
+    }
+
+    #[test]
+    fn spawns_come_slower_during_the_grace_period() {
+        let config = SpawnConfig::default();
+        let during = config.interval_at(config.grace_period / 2.0);
+        let after = config.interval_at(config.grace_period + 1.0);
+
+        assert!(config.interval_at(0.0) > during);
+        assert!(during > after);
+        assert!((after - config.interval).abs() < 1e-6);
+    }
+
+    // draws from a capped heavyweight and a light filler with `live` capped
+    // enemies already out
+    fn capped_draws(live: usize) -> Vec<String> {
+        let mut types = stub_enemy_types();
+        let mut capped = types.types[TEST_ENEMY].clone();
+        capped.weight = 100.0;
+        capped.max_concurrent = Some(2);
+        types.types.insert("capped".to_string(), capped);
+        let live = HashMap::from([("capped".to_string(), live)]);
+        let mut rng = GameRng::from_seed(0);
+        (0..50)
+            .filter_map(|_| types.choose(&Unlocks::default(), &live, rng.rng()).cloned())
+            .collect()
+    }
+
+    #[test]
+    fn a_type_at_its_cap_is_left_out_of_the_draw() {
+        assert!(capped_draws(1).iter().any(|name| name == "capped"));
+        let draws = capped_draws(2);
+        assert_eq!(draws.len(), 50);
+        assert!(draws.iter().all(|name| name == TEST_ENEMY));
+    }
+
+    #[test]
+    fn nothing_is_drawn_once_every_type_is_capped() {
+        let mut types = stub_enemy_types();
+        types.types.get_mut(TEST_ENEMY).unwrap().max_concurrent = Some(1);
+        let live = HashMap::from([(TEST_ENEMY.to_string(), 1)]);
+        let mut rng = GameRng::from_seed(0);
+        assert!(types
+            .choose(&Unlocks::default(), &live, rng.rng())
+            .is_none());
+    }
+
+    fn auto_spawned(manual: bool) -> usize {
+        let mut app = headless_app();
+        app.insert_resource(ManualSpawn(manual))
+            .add_systems(Update, spawn_enemy.run_if(not(manual_spawn)));
+        advance_secs(&mut app, 10.0);
+        app.world.query::<&Enemy>().iter(&app.world).count()
+    }
+
+    #[test]
+    fn manual_spawning_holds_back_the_auto_spawner() {
+        assert!(auto_spawned(false) > 0);
+        assert_eq!(auto_spawned(true), 0);
+    }
+
+    #[test]
+    fn with_spawning_off_nothing_arrives_but_enemies_keep_walking() {
+        let mut app = crossing_app(MenuItem::Start, 0);
+        app.insert_resource(SpawningEnabled(false))
+            .add_systems(Update, spawn_enemy.run_if(spawning_enabled));
+        let sign = app.world.resource::<PlayfieldOrientation>().sign();
+        let start = Vec3::new(
+            app.world.resource::<DefenseLine>().x + 300.0 * sign,
+            0.0,
+            0.0,
+        );
+        let walker = spawn_walker(&mut app, start);
+        app.world
+            .entity_mut(walker)
+            .insert(ContactBehavior::PassThrough);
+        let enemies = |app: &mut App| app.world.query::<&Enemy>().iter(&app.world).count();
+        let before = enemies(&mut app);
+
+        advance_secs(&mut app, 1.0);
+        assert_eq!(enemies(&mut app), before);
+        let x = app.world.get::<Transform>(walker).unwrap().translation.x;
+        assert!((start.x - x) * sign > 0.0);
+    }
+
+    #[derive(Resource, Default)]
+    struct RunOrder(Vec<&'static str>);
+
+    fn track(step: &'static str) -> impl FnMut(ResMut<RunOrder>) {
+        move |mut order: ResMut<RunOrder>| order.0.push(step)
+    }
+
+    #[test]
+    fn enemies_move_spawn_react_then_clean_up() {
+        // built from the real plugins rather than the headless harness, which
+        // orders the sets itself
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, EnemySpawnPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+                1.0 / 60.0,
+            )))
+            .add_state::<GameState>()
+            .init_resource::<AnimationList>()
+            .init_resource::<GameMode>()
+            .init_resource::<DeathSequence>()
+            .init_resource::<BossIntro>()
+            .init_resource::<RunOrder>()
+            .add_systems(First, |mut order: ResMut<RunOrder>| order.0.clear())
+            .add_systems(FixedUpdate, track("move").in_set(FixedMovementSet))
+            .add_systems(
+                Update,
+                (
+                    track("spawn").in_set(EnemySet::Spawn),
+                    track("react").in_set(EnemySet::React),
+                    track("cleanup").in_set(EnemySet::Cleanup),
+                ),
+            );
+
+        // render frames only run a fixed step once enough time has built up
+        for _ in 0..10 {
+            app.update();
+            let order = &app.world.resource::<RunOrder>().0;
+            if order.len() == 4 {
+                assert_eq!(*order, ["move", "spawn", "react", "cleanup"]);
+                return;
+            }
+        }
+        panic!("no frame ran a fixed step");
+    }
+
+    #[test]
+    fn grazing_an_enemy_pays_out_once() {
+        let mut app = headless_app();
+        app.add_systems(Update, award_grazes);
+        app.world.spawn((Player, Transform::default()));
+        let y = (GRAZE_CONTACT + GRAZE_RADIUS) / 2.0;
+        let enemy = spawn_walker(&mut app, Vec3::new(30.0, y, 0.0));
+        app.world.entity_mut(enemy).insert(Graze::default());
+
+        // walks past the hero just out of reach, then on down the lane
+        for step in 0..10 {
+            app.world.get_mut::<Transform>(enemy).unwrap().translation.x =
+                30.0 - 10.0 * step as f32;
+            advance(&mut app, 1);
+        }
+        assert_eq!(
+            app.world.resource::<GameStats>().villagers_saved,
+            GRAZE_BONUS
+        );
+    }
+
+    #[test]
+    fn facing_left_spawns_enemies_on_the_left() {
+        let mut app = headless_app();
+        app.insert_resource(PlayfieldOrientation::FacingLeft)
+            .add_systems(Update, spawn_enemy);
+        advance_secs(&mut app, 1.0);
+
+        let center = app.world.resource::<GameplayStart>().camera_endpos.x;
+        let xs: Vec<f32> = app
+            .world
+            .query_filtered::<&Transform, With<Enemy>>()
+            .iter(&app.world)
+            .map(|transform| transform.translation.x)
+            .collect();
+        assert!(!xs.is_empty());
+        assert!(xs.iter().all(|x| *x < center));
+    }
+
+    #[test]
+    fn empty_enemy_list_spawns_placeholders() {
+        let mut app = headless_app();
+        app.init_asset::<Image>()
+            .init_asset::<TextureAtlas>()
+            .init_asset::<AnimationListAsset>()
+            .init_resource::<ImagesToLoad>()
+            .insert_resource(EnemyTypes::default())
+            .insert_resource(EnemyAnimations::default())
+            .add_systems(Update, (load_enemy_animations, spawn_enemy).chain());
+        let handle = app
+            .world
+            .resource_mut::<Assets<AnimationListAsset>>()
+            .add(AnimationListAsset::default());
+        app.insert_resource(AnimationList {
+            handle,
+            ..default()
+        });
+        advance_secs(&mut app, 1.0);
+
+        let names: Vec<String> = app
+            .world
+            .query::<&Enemy>()
+            .iter(&app.world)
+            .map(|enemy| enemy.name.clone())
+            .collect();
+        assert!(!names.is_empty());
+        assert!(names.iter().all(|name| name == PLACEHOLDER_ENEMY));
+    }
+
+    #[test]
+    fn exiting_enemies_are_despawned_once_faded() {
+        let mut app = headless_app();
+        app.add_systems(Update, fade_exiting);
+        let entity = app
+            .world
+            .spawn((
+                Exiting {
+                    timer: Timer::from_seconds(0.5, TimerMode::Once),
+                },
+                TextureAtlasSprite::default(),
+            ))
+            .id();
+
+        advance_secs(&mut app, 0.25);
+        assert!(app.world.get_entity(entity).is_some());
+        advance_secs(&mut app, 0.5);
+        assert!(app.world.get_entity(entity).is_none());
+    }
+
+    // a run that has already let `lost` villagers go, with one more enemy
+    // about to cross the line
+    fn crossing_app(item: MenuItem, lost: i32) -> App {
+        let mut app = headless_app();
+        app.insert_resource(item.mode())
+            .init_resource::<Wall>()
+            .init_resource::<FlashScreen>()
+            .init_resource::<CameraShake>()
+            .add_systems(Update, move_enemies);
+        app.world.resource_mut::<GameStats>().villagers_lost = lost;
+        let info = app.world.resource::<EnemyTypes>().types[TEST_ENEMY].clone();
+        let x = app.world.resource::<DefenseLine>().x;
+        app.world.spawn((
+            Enemy::new(TEST_ENEMY, &info),
+            Transform::from_xyz(x, 0.0, 0.0),
+            AnimationComponent::new(AnimState::Walking),
+            ContactBehavior::PassThrough,
+        ));
+        app
+    }
+
+    fn state(app: &App) -> GameState {
+        *app.world.resource::<State<GameState>>().get()
+    }
+
+    #[test]
+    fn crossing_the_line_loses_a_villager_and_despawns() {
+        let mut app = crossing_app(MenuItem::Start, 0);
+        app.add_systems(Update, fade_exiting);
+        let enemy = app
+            .world
+            .query_filtered::<Entity, With<Enemy>>()
+            .single(&app.world);
+        app.world
+            .entity_mut(enemy)
+            .insert(TextureAtlasSprite::default());
+        advance(&mut app, 2);
+        assert_eq!(app.world.resource::<GameStats>().villagers_lost, 1);
+
+        let fade = app.world.resource::<SpawnConfig>().exit_fade;
+        advance_secs(&mut app, fade + 0.1);
+        assert!(app.world.get_entity(enemy).is_none());
+        assert_eq!(app.world.resource::<GameStats>().villagers_lost, 1);
+    }
+
+    #[test]
+    fn crossing_enemy_fades_out_untouchable_then_despawns() {
+        let mut app = crossing_app(MenuItem::Start, 0);
+        app.add_systems(Update, fade_exiting);
+        let enemy = app
+            .world
+            .query_filtered::<Entity, With<Enemy>>()
+            .single(&app.world);
+        app.world
+            .entity_mut(enemy)
+            .insert((TextureAtlasSprite::default(), Collider::ball(8.0)));
+        advance(&mut app, 2);
+        assert!(app.world.get::<Exiting>(enemy).is_some());
+        assert!(app.world.get::<Collider>(enemy).is_none());
+
+        let fade = app.world.resource::<SpawnConfig>().exit_fade;
+        advance_secs(&mut app, fade / 2.0);
+        let alpha = app
+            .world
+            .get::<TextureAtlasSprite>(enemy)
+            .unwrap()
+            .color
+            .a();
+        assert!(alpha > 0.0 && alpha < 1.0);
+
+        advance_secs(&mut app, fade / 2.0 + 0.1);
+        assert!(app.world.get_entity(enemy).is_none());
+        assert_eq!(app.world.resource::<GameStats>().villagers_lost, 1);
+    }
+
+    #[test]
+    fn enemies_in_a_slow_zone_walk_slower() {
+        let mut app = crossing_app(MenuItem::Start, 0);
+        let sign = app.world.resource::<PlayfieldOrientation>().sign();
+        let x = app.world.resource::<DefenseLine>().x + 200.0 * sign;
+        let slowed = spawn_walker(&mut app, Vec3::new(x, 50.0, 0.0));
+        let free = spawn_walker(&mut app, Vec3::new(x, -50.0, 0.0));
+        for enemy in [slowed, free] {
+            app.world
+                .entity_mut(enemy)
+                .insert(ContactBehavior::PassThrough);
+        }
+        app.world.spawn(SlowZone {
+            rect: Rect::from_center_size(Vec2::new(x, 50.0), Vec2::splat(100.0)),
+            factor: 0.5,
+        });
+        advance(&mut app, 1);
+
+        let moved = |enemy| (app.world.get::<Transform>(enemy).unwrap().translation.x - x).abs();
+        assert!(moved(free) > 0.0);
+        assert!((moved(slowed) - moved(free) * 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn losing_a_villager_flashes_the_screen() {
+        let mut app = crossing_app(MenuItem::Start, 0);
+        assert_eq!(app.world.resource::<FlashScreen>().alpha(), 0.0);
+        advance(&mut app, 2);
+
+        let flash = app.world.resource::<FlashScreen>();
+        assert!(flash.alpha() > 0.0);
+        assert_eq!(flash.color, Color::RED);
+    }
+
+    #[test]
+    fn crossing_batters_the_wall_until_it_falls() {
+        let mut app = crossing_app(MenuItem::Start, 0);
+        let mut types = app.world.resource_mut::<EnemyTypes>();
+        types.types.get_mut(TEST_ENEMY).unwrap().wall_damage = 3;
+        let max_hp = app.world.resource::<Wall>().max_hp;
+        advance(&mut app, 2);
+        assert_eq!(app.world.resource::<Wall>().hp, max_hp - 3);
+        assert_eq!(state(&app), GameState::GamePlay);
+
+        app.world.resource_mut::<Wall>().hp = 3;
+        let info = app.world.resource::<EnemyTypes>().types[TEST_ENEMY].clone();
+        let x = app.world.resource::<DefenseLine>().x;
+        app.world.spawn((
+            Enemy::new(TEST_ENEMY, &info),
+            Transform::from_xyz(x, 0.0, 0.0),
+            AnimationComponent::new(AnimState::Walking),
+            ContactBehavior::PassThrough,
+        ));
+        advance(&mut app, 2);
+        assert!(app.world.resource::<Wall>().is_breached());
+        assert_eq!(state(&app), GameState::GameOver);
+    }
+
+    #[test]
+    fn reaching_the_villager_limit_ends_the_run() {
+        let mut app = crossing_app(MenuItem::Villagers, VILLAGER_LIMIT - 1);
+        advance(&mut app, 2);
+
+        assert_eq!(
+            app.world.resource::<GameStats>().villagers_lost,
+            VILLAGER_LIMIT
+        );
+        assert_eq!(state(&app), GameState::GameOver);
+    }
+
+    #[test]
+    fn without_a_limit_losing_villagers_plays_on() {
+        let mut app = crossing_app(MenuItem::Start, VILLAGER_LIMIT - 1);
+        advance(&mut app, 2);
+
+        assert_eq!(
+            app.world.resource::<GameStats>().villagers_lost,
+            VILLAGER_LIMIT
+        );
+        assert_eq!(state(&app), GameState::GamePlay);
+    }
+
+    fn spawn_walker(app: &mut App, position: Vec3) -> Entity {
+        let info = app.world.resource::<EnemyTypes>().types[TEST_ENEMY].clone();
+        app.world
+            .spawn((
+                Enemy::new(TEST_ENEMY, &info),
+                Transform::from_translation(position),
+                AnimationComponent::new(AnimState::Walking),
+            ))
+            .id()
+    }
+
+    fn spawn_aggro(app: &mut App, x: f32, state: AnimState) -> Entity {
+        let enemy = spawn_walker(app, Vec3::new(x, 0.0, 0.0));
+        app.world.entity_mut(enemy).insert((
+            Aggro {
+                range: 100.0,
+                speed_mult: 2.0,
+                alerted: false,
+            },
+            Handle::<TextureAtlas>::default(),
+        ));
+        app.world
+            .get_mut::<AnimationComponent>(enemy)
+            .unwrap()
+            .state = state;
+        enemy
+    }
+
+    #[test]
+    fn an_enemy_in_range_switches_to_its_alert_walk() {
+        let mut app = headless_app();
+        let alert = Handle::weak_from_u128(7);
+        app.world
+            .resource_mut::<EnemyAnimations>()
+            .enemies
+            .get_mut(TEST_ENEMY)
+            .unwrap()
+            .add_handle(ALERT_ANIM.to_string(), alert.clone());
+        app.add_systems(Update, notice_player);
+        app.world.spawn((Player, Transform::default()));
+        let speed = app.world.resource::<EnemyTypes>().types[TEST_ENEMY].speed;
+        let near = spawn_aggro(&mut app, 50.0, AnimState::Walking);
+        let far = spawn_aggro(&mut app, 300.0, AnimState::Walking);
+        let dying = spawn_aggro(&mut app, 50.0, AnimState::Dying);
+        advance(&mut app, 1);
+
+        let handle = |entity| {
+            app.world
+                .get::<Handle<TextureAtlas>>(entity)
+                .unwrap()
+                .clone()
+        };
+        let enemy_speed = |entity| app.world.get::<Enemy>(entity).unwrap().speed;
+        assert_eq!(handle(near), alert);
+        assert_eq!(enemy_speed(near), speed * 2.0);
+        assert_eq!(handle(far), Handle::default());
+        assert_eq!(enemy_speed(far), speed);
+        assert_eq!(handle(dying), Handle::default());
+        assert_eq!(enemy_speed(dying), speed);
+    }
+
+    #[test]
+    fn without_an_alert_sheet_the_walk_is_kept() {
+        let mut app = headless_app();
+        app.add_systems(Update, notice_player);
+        app.world.spawn((Player, Transform::default()));
+        let speed = app.world.resource::<EnemyTypes>().types[TEST_ENEMY].speed;
+        let near = spawn_aggro(&mut app, 50.0, AnimState::Walking);
+        advance(&mut app, 1);
+
+        assert_eq!(
+            *app.world.get::<Handle<TextureAtlas>>(near).unwrap(),
+            Handle::default()
+        );
+        assert_eq!(app.world.get::<Enemy>(near).unwrap().speed, speed * 2.0);
+    }
+
+    #[test]
+    fn enemies_on_the_same_row_drift_apart() {
+        let mut app = headless_app();
+        app.add_systems(Update, separate_enemies);
+        let a = spawn_walker(&mut app, Vec3::new(100.0, 0.0, 0.0));
+        let b = spawn_walker(&mut app, Vec3::new(105.0, 0.0, 0.0));
+        let gap = |app: &App| {
+            let y = |entity| app.world.get::<Transform>(entity).unwrap().translation.y;
+            (y(a) - y(b)).abs()
+        };
+
+        advance(&mut app, 5);
+        let early = gap(&app);
+        advance(&mut app, 30);
+        assert!(early > 0.0);
+        assert!(gap(&app) > early);
+    }
+
+    // a second of walking cut into render frames of the given length
+    fn walked_in_a_second(frame: f32) -> f32 {
+        let mut app = headless_app();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            frame,
+        )))
+        .init_resource::<Wall>()
+        .init_resource::<FlashScreen>()
+        .init_resource::<CameraShake>()
+        .add_systems(FixedUpdate, move_enemies);
+        let start = Vec3::new(400.0, 0.0, 0.0);
+        let enemy = spawn_walker(&mut app, start);
+        app.world
+            .entity_mut(enemy)
+            .insert(ContactBehavior::PassThrough);
+        advance(&mut app, (1.0 / frame).round() as usize);
+        start.x - app.world.get::<Transform>(enemy).unwrap().translation.x
+    }
+
+    #[test]
+    fn walking_distance_does_not_depend_on_the_frame_rate() {
+        // both divide the fixed step exactly, so each runs the same ticks
+        let slow = walked_in_a_second(1.0 / 32.0);
+        let fast = walked_in_a_second(1.0 / 128.0);
+        assert!(slow > 0.0);
+        assert!((slow - fast).abs() < 0.001);
+    }
+}
@@ -0,0 +1,96 @@
+use bevy::{asset::LoadState, prelude::*};
+use serde::Deserialize;
+
+use crate::data::json::*;
+
+// zones stacked on top of each other never slow things down more than this
+const MIN_SLOW_FACTOR: f32 = 0.3;
+const SLOW_ZONE_COLOR: Color = Color::rgba(0.3, 0.5, 0.2, 0.25);
+const SLOW_ZONE_Z: f32 = -0.6;
+
+pub struct HazardPlugin;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SlowZoneDef {
+    pub min: [f32; 2],
+    pub max: [f32; 2],
+    pub factor: f32,
+}
+
+#[derive(Asset, TypePath, Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct MapAsset {
+    pub slow_zones: Vec<SlowZoneDef>,
+}
+
+#[derive(Resource, Default)]
+struct MapHandle {
+    handle: Handle<MapAsset>,
+    resolved: bool,
+}
+
+#[derive(Component)]
+pub struct SlowZone {
+    pub rect: Rect,
+    pub factor: f32,
+}
+
+// the combined slowdown of every zone covering `position`
+pub fn slow_factor<'a>(zones: impl Iterator<Item = &'a SlowZone>, position: Vec2) -> f32 {
+    zones
+        .filter(|zone| zone.rect.contains(position))
+        .map(|zone| zone.factor.clamp(0.0, 1.0))
+        .product::<f32>()
+        .max(MIN_SLOW_FACTOR)
+}
+
+impl Plugin for HazardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(JsonPlugin::<MapAsset> {
+            extensions: vec!["map.json"],
+            ..default()
+        })
+        .init_resource::<MapHandle>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, spawn_hazards);
+    }
+}
+
+fn setup(mut map_handle: ResMut<MapHandle>, asset_server: Res<AssetServer>) {
+    map_handle.handle = asset_server.load("default.map.json");
+}
+
+fn spawn_hazards(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    maps: Res<Assets<MapAsset>>,
+    mut map_handle: ResMut<MapHandle>,
+) {
+    if map_handle.resolved {
+        return;
+    }
+    if let Some(map) = maps.get(&map_handle.handle) {
+        for zone in map.slow_zones.iter() {
+            let rect = Rect::from_corners(Vec2::from(zone.min), Vec2::from(zone.max));
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: SLOW_ZONE_COLOR,
+                        custom_size: Some(rect.size()),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(rect.center().extend(SLOW_ZONE_Z)),
+                    ..default()
+                },
+                SlowZone {
+                    rect,
+                    factor: zone.factor,
+                },
+            ));
+        }
+        map_handle.resolved = true;
+    } else if asset_server.get_load_state(&map_handle.handle) == Some(LoadState::Failed) {
+        warn!("failed to load the map, playing without hazards");
+        map_handle.resolved = true;
+    }
+}
@@ -0,0 +1,221 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    data::state::GameState,
+    entities::player::{DeathSequence, Player, PlayerData, PlayerHitFlash},
+    pause::{PausableTimers, PauseAppExt, RestartSet},
+};
+
+const LAST_STAND_HEALTH: i32 = 1;
+const LAST_STAND_TIME_SCALE: f32 = 0.3;
+
+pub struct LastStandPlugin;
+
+#[derive(Resource)]
+pub struct LastStand {
+    pub timer: Timer,
+    pub armed: bool,
+    pub active: bool,
+}
+
+impl Default for LastStand {
+    fn default() -> Self {
+        Self {
+            timer: Timer::new(Duration::from_secs_f32(1.5), TimerMode::Once),
+            armed: true,
+            active: false,
+        }
+    }
+}
+
+impl PausableTimers for LastStand {
+    fn timers_mut(&mut self) -> Vec<&mut Timer> {
+        vec![&mut self.timer]
+    }
+}
+
+impl LastStand {
+    pub fn update(&mut self, health: i32) -> bool {
+        if health > LAST_STAND_HEALTH {
+            self.armed = true;
+        } else if health == LAST_STAND_HEALTH && self.armed && !self.active {
+            self.armed = false;
+            self.active = true;
+            self.timer.reset();
+            return true;
+        }
+        false
+    }
+}
+
+#[derive(Component)]
+struct LastStandPulse;
+
+impl Plugin for LastStandPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LastStand>()
+            .preserve_timers_on_pause::<LastStand>()
+            .add_systems(
+                Update,
+                (trigger_last_stand, tick_last_stand)
+                    .chain()
+                    .run_if(in_state(GameState::GamePlay)),
+            )
+            .add_systems(OnEnter(GameState::GameOver), end_last_stand)
+            .add_systems(
+                OnEnter(GameState::GamePlay),
+                (end_last_stand, reset_last_stand)
+                    .chain()
+                    .in_set(RestartSet),
+            );
+    }
+}
+
+fn trigger_last_stand(
+    mut commands: Commands,
+    player_data: Res<PlayerData>,
+    mut last_stand: ResMut<LastStand>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if last_stand.update(player_data.health) {
+        time.set_relative_speed(LAST_STAND_TIME_SCALE);
+        commands.spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                background_color: Color::rgba(1.0, 0.0, 0.0, 0.0).into(),
+                ..default()
+            },
+            LastStandPulse,
+        ));
+    }
+}
+
+fn tick_last_stand(
+    mut commands: Commands,
+    real_time: Res<Time<Real>>,
+    mut last_stand: ResMut<LastStand>,
+    death: Res<DeathSequence>,
+    mut time: ResMut<Time<Virtual>>,
+    mut pulses: Query<(Entity, &mut BackgroundColor), With<LastStandPulse>>,
+    mut player: Query<&mut TextureAtlasSprite, (With<Player>, Without<PlayerHitFlash>)>,
+) {
+    if !last_stand.active || death.active {
+        return;
+    }
+    last_stand.timer.tick(real_time.delta());
+    if last_stand.timer.finished() {
+        last_stand.active = false;
+        restore(&mut commands, &mut time, &mut pulses, &mut player);
+        return;
+    }
+    let wave = (last_stand.timer.elapsed_secs() * 12.0).sin();
+    for (_, mut color) in &mut pulses {
+        color.0.set_a(0.15 + 0.15 * wave);
+    }
+    for mut sprite in &mut player {
+        sprite.color = if wave > 0.0 { Color::RED } else { Color::WHITE };
+    }
+}
+
+fn reset_last_stand(mut last_stand: ResMut<LastStand>) {
+    *last_stand = LastStand::default();
+}
+
+fn end_last_stand(
+    mut commands: Commands,
+    mut last_stand: ResMut<LastStand>,
+    mut time: ResMut<Time<Virtual>>,
+    mut pulses: Query<(Entity, &mut BackgroundColor), With<LastStandPulse>>,
+    mut player: Query<&mut TextureAtlasSprite, (With<Player>, Without<PlayerHitFlash>)>,
+) {
+    if last_stand.active {
+        last_stand.active = false;
+        restore(&mut commands, &mut time, &mut pulses, &mut player);
+    }
+}
+
+fn restore(
+    commands: &mut Commands,
+    time: &mut Time<Virtual>,
+    pulses: &mut Query<(Entity, &mut BackgroundColor), With<LastStandPulse>>,
+    player: &mut Query<&mut TextureAtlasSprite, (With<Player>, Without<PlayerHitFlash>)>,
+) {
+    time.set_relative_speed(1.0);
+    for mut sprite in player.iter_mut() {
+        sprite.color = Color::WHITE;
+    }
+    for (entity, _) in pulses.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{advance, headless_app};
+
+    fn last_stand_app() -> App {
+        let mut app = headless_app();
+        app.add_plugins(LastStandPlugin);
+        app
+    }
+
+    fn set_health(app: &mut App, health: i32) {
+        app.world.resource_mut::<PlayerData>().health = health;
+        advance(app, 1);
+    }
+
+    fn pulses(app: &mut App) -> usize {
+        app.world
+            .query_filtered::<(), With<LastStandPulse>>()
+            .iter(&app.world)
+            .count()
+    }
+
+    #[test]
+    fn last_stand_triggers_once_and_healing_rearms_it() {
+        let mut app = last_stand_app();
+        set_health(&mut app, LAST_STAND_HEALTH);
+        assert!(app.world.resource::<LastStand>().active);
+        assert_eq!(pulses(&mut app), 1);
+
+        app.world.resource_mut::<LastStand>().active = false;
+        set_health(&mut app, LAST_STAND_HEALTH);
+        assert!(!app.world.resource::<LastStand>().active);
+        assert_eq!(pulses(&mut app), 1);
+
+        set_health(&mut app, LAST_STAND_HEALTH + 1);
+        assert!(app.world.resource::<LastStand>().armed);
+        set_health(&mut app, LAST_STAND_HEALTH);
+        assert!(app.world.resource::<LastStand>().active);
+        assert_eq!(pulses(&mut app), 2);
+    }
+
+    #[test]
+    fn last_stand_tints_the_hero_unless_a_hit_flash_is_showing() {
+        let mut app = last_stand_app();
+        let flashed = Color::rgb(1.0, 0.5, 0.5);
+        let sprite = TextureAtlasSprite {
+            color: flashed,
+            ..default()
+        };
+        let hit = app
+            .world
+            .spawn((Player, sprite.clone(), PlayerHitFlash::new()))
+            .id();
+        let unhit = app.world.spawn((Player, sprite)).id();
+        set_health(&mut app, LAST_STAND_HEALTH);
+        advance(&mut app, 10);
+
+        let color = |app: &App, entity| app.world.get::<TextureAtlasSprite>(entity).unwrap().color;
+        assert_eq!(color(&app, hit), flashed);
+        assert_ne!(color(&app, unhit), flashed);
+    }
+}
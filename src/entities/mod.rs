@@ -1,2 +1,16 @@
+pub mod achievement;
+pub mod attack;
+pub mod boss;
+pub mod collision;
 pub mod enemy;
+pub mod hazard;
+pub mod last_stand;
+pub mod pickup;
 pub mod player;
+pub mod projectile;
+pub mod spawner;
+pub mod time_attack;
+pub mod unlock;
+pub mod wall;
+pub mod wave;
+pub mod weapon;
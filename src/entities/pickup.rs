@@ -0,0 +1,469 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::prelude::*;
+
+use crate::{
+    animation::{AnimationComponent, DropEntry, DropKind, EnemyAnimations},
+    audio::Sfx,
+    data::{
+        playfield::{PlayfieldBounds, PlayfieldOrientation},
+        rng::GameRng,
+        state::GameState,
+    },
+    entities::{
+        collision::{SensorCollisions, GROUP_PICKUP, GROUP_PLAYER},
+        enemy::{kill_enemy, Blinker, Enemy, EnemyTypes, Exiting},
+        player::{GameStats, Player, PlayerData, Shield},
+    },
+    pause::RestartSet,
+    GameplayStart,
+};
+
+const HEALTH_DROP_AMOUNT: i32 = 2;
+const SHIELD_CHARGES: i32 = 3;
+const MAX_HEALTH_BONUS: i32 = 2;
+const MAX_HEALTH_CAP: i32 = 20;
+const SHOCKWAVE_RADIUS: f32 = 900.0;
+
+pub struct PickupPlugin;
+
+#[derive(Component)]
+pub struct Pickup {
+    speed: f32,
+}
+
+impl Default for Pickup {
+    fn default() -> Self {
+        Self { speed: 50.0 }
+    }
+}
+
+#[derive(Component)]
+pub struct HealthPickup {
+    pub amount: i32,
+}
+
+#[derive(Component)]
+pub struct Bomb;
+
+#[derive(Component)]
+pub struct ShieldPickup {
+    pub charges: i32,
+}
+
+#[derive(Component)]
+pub struct MaxHealthPickup {
+    pub amount: i32,
+}
+
+#[derive(Component)]
+struct Shockwave {
+    timer: Timer,
+    max_radius: f32,
+}
+
+impl Plugin for PickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                move_pickups,
+                collect_health_pickups,
+                collect_bombs,
+                collect_shield_pickups,
+                collect_max_health_pickups,
+                draw_shockwaves,
+            )
+                .run_if(in_state(GameState::GamePlay)),
+        )
+        .add_systems(
+            OnEnter(GameState::GamePlay),
+            despawn_pickups.in_set(RestartSet),
+        );
+    }
+}
+
+pub fn spawn_health_pickup(commands: &mut Commands, position: Vec3, amount: i32) {
+    spawn_pickup(
+        commands,
+        position,
+        Color::rgb(0.9, 0.1, 0.2),
+        HealthPickup { amount },
+    );
+}
+
+pub fn spawn_bomb_pickup(commands: &mut Commands, position: Vec3) {
+    spawn_pickup(commands, position, Color::rgb(1.0, 0.8, 0.2), Bomb);
+}
+
+pub fn spawn_shield_pickup(commands: &mut Commands, position: Vec3) {
+    spawn_pickup(
+        commands,
+        position,
+        Color::rgb(0.3, 0.7, 1.0),
+        ShieldPickup {
+            charges: SHIELD_CHARGES,
+        },
+    );
+}
+
+pub fn spawn_max_health_pickup(commands: &mut Commands, position: Vec3) {
+    spawn_pickup(
+        commands,
+        position,
+        Color::rgb(1.0, 0.5, 0.8),
+        MaxHealthPickup {
+            amount: MAX_HEALTH_BONUS,
+        },
+    );
+}
+
+// see DropEntry for how the chances combine
+pub fn roll_drop(drops: &[DropEntry], rng: &mut impl Rng) -> Option<DropKind> {
+    let roll = rng.gen::<f64>();
+    let mut total = 0.0;
+    drops.iter().find_map(|drop| {
+        total += drop.chance.max(0.0);
+        (roll < total).then_some(drop.kind)
+    })
+}
+
+pub fn spawn_drop(commands: &mut Commands, kind: DropKind, position: Vec3) {
+    match kind {
+        DropKind::Health => spawn_health_pickup(commands, position, HEALTH_DROP_AMOUNT),
+        DropKind::Bomb => spawn_bomb_pickup(commands, position),
+        DropKind::Shield => spawn_shield_pickup(commands, position),
+        DropKind::MaxHealth => spawn_max_health_pickup(commands, position),
+    }
+}
+
+fn spawn_pickup(commands: &mut Commands, position: Vec3, color: Color, kind: impl Bundle) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::splat(10.0)),
+                ..default()
+            },
+            transform: Transform::from_translation(position),
+            ..default()
+        },
+        Pickup::default(),
+        kind,
+        RigidBody::KinematicPositionBased,
+        Collider::cuboid(5.0, 5.0),
+        Sensor,
+        ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_KINEMATIC,
+        ActiveEvents::COLLISION_EVENTS,
+        CollisionGroups::new(GROUP_PICKUP, GROUP_PLAYER),
+    ));
+}
+
+fn despawn_pickups(
+    mut commands: Commands,
+    pickups: Query<Entity, Or<(With<Pickup>, With<Shockwave>)>>,
+) {
+    for entity in &pickups {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn move_pickups(
+    mut commands: Commands,
+    time: Res<Time>,
+    camerapos: Res<GameplayStart>,
+    bounds: Res<PlayfieldBounds>,
+    orientation: Res<PlayfieldOrientation>,
+    mut pickups: Query<(Entity, &Pickup, &mut Transform)>,
+) {
+    for (entity, pickup, mut transform) in pickups.iter_mut() {
+        transform.translation.x -= pickup.speed * orientation.sign() * time.delta_seconds();
+        if bounds.has_crossed(
+            transform.translation.x,
+            camerapos.camera_endpos.x,
+            *orientation,
+        ) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn collect_health_pickups(
+    mut commands: Commands,
+    mut collisions: SensorCollisions,
+    mut player_data: ResMut<PlayerData>,
+    player: Query<(), With<Player>>,
+    pickups: Query<&HealthPickup>,
+) {
+    for (a, b) in collisions.started() {
+        let pickup = if player.contains(a) {
+            b
+        } else if player.contains(b) {
+            a
+        } else {
+            continue;
+        };
+        if let Ok(health) = pickups.get(pickup) {
+            player_data.heal(health.amount);
+            commands.entity(pickup).despawn();
+        }
+    }
+}
+
+fn collect_shield_pickups(
+    mut commands: Commands,
+    mut collisions: SensorCollisions,
+    mut shield: ResMut<Shield>,
+    player: Query<(), With<Player>>,
+    pickups: Query<&ShieldPickup>,
+) {
+    for (a, b) in collisions.started() {
+        let pickup = if player.contains(a) {
+            b
+        } else if player.contains(b) {
+            a
+        } else {
+            continue;
+        };
+        if let Ok(pickup_shield) = pickups.get(pickup) {
+            shield.charges += pickup_shield.charges;
+            commands.entity(pickup).despawn();
+        }
+    }
+}
+
+fn collect_max_health_pickups(
+    mut commands: Commands,
+    mut collisions: SensorCollisions,
+    mut player_data: ResMut<PlayerData>,
+    player: Query<(), With<Player>>,
+    pickups: Query<&MaxHealthPickup>,
+) {
+    for (a, b) in collisions.started() {
+        let pickup = if player.contains(a) {
+            b
+        } else if player.contains(b) {
+            a
+        } else {
+            continue;
+        };
+        if let Ok(max_health) = pickups.get(pickup) {
+            player_data.raise_max_health(max_health.amount, MAX_HEALTH_CAP);
+            commands.entity(pickup).despawn();
+        }
+    }
+}
+
+fn collect_bombs(
+    mut commands: Commands,
+    mut collisions: SensorCollisions,
+    anims: Res<EnemyAnimations>,
+    enemy_types: Res<EnemyTypes>,
+    mut stats: ResMut<GameStats>,
+    mut game_rng: ResMut<GameRng>,
+    mut sfx: EventWriter<Sfx>,
+    player: Query<&Transform, With<Player>>,
+    bombs: Query<(), With<Bomb>>,
+    mut enemies: Query<
+        (
+            Entity,
+            &Enemy,
+            &Transform,
+            &mut Handle<TextureAtlas>,
+            &mut TextureAtlasSprite,
+            &mut AnimationComponent,
+            Option<&Blinker>,
+        ),
+        (Without<Player>, Without<Exiting>),
+    >,
+) {
+    for (a, b) in collisions.started() {
+        let (bomb, player_transform) = if let Ok(transform) = player.get(a) {
+            (b, transform)
+        } else if let Ok(transform) = player.get(b) {
+            (a, transform)
+        } else {
+            continue;
+        };
+        if !bombs.contains(bomb) {
+            continue;
+        }
+        commands.entity(bomb).despawn();
+        sfx.send(Sfx::Death);
+        for (entity, enemy, transform, mut handle, mut sprite, mut anim, blinker) in &mut enemies {
+            if anim.state.is_dying() || blinker.is_some_and(Blinker::is_blinking) {
+                continue;
+            }
+            kill_enemy(
+                &mut commands,
+                entity,
+                enemy,
+                transform,
+                &mut handle,
+                &mut sprite,
+                &mut anim,
+                &anims,
+                &enemy_types,
+                &mut stats,
+                game_rng.rng(),
+            );
+        }
+        commands.spawn((
+            Transform::from_translation(player_transform.translation),
+            Shockwave {
+                timer: Timer::new(Duration::from_secs_f32(0.6), TimerMode::Once),
+                max_radius: SHOCKWAVE_RADIUS,
+            },
+        ));
+    }
+}
+
+fn draw_shockwaves(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut gizmos: Gizmos,
+    mut shockwaves: Query<(Entity, &Transform, &mut Shockwave)>,
+) {
+    for (entity, transform, mut shockwave) in &mut shockwaves {
+        shockwave.timer.tick(time.delta());
+        let progress = shockwave.timer.percent();
+        gizmos.circle_2d(
+            transform.translation.truncate(),
+            shockwave.max_radius * progress,
+            Color::rgba(1.0, 0.8, 0.2, 1.0 - progress),
+        );
+        if shockwave.timer.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+
+    use super::*;
+    use crate::{
+        animation::AnimState,
+        testing::{advance, collide, headless_app, TEST_ENEMY},
+    };
+
+    fn pickup_app() -> (App, Entity) {
+        let mut app = headless_app();
+        app.init_resource::<Shield>().add_plugins(PickupPlugin);
+        let player = app.world.spawn(Player).id();
+        (app, player)
+    }
+
+    fn collect(app: &mut App, player: Entity, pickup: impl Bundle) -> Entity {
+        let pickup = app.world.spawn(pickup).id();
+        collide(app, player, pickup);
+        advance(app, 1);
+        pickup
+    }
+
+    #[test]
+    fn health_pickup_heals_up_to_the_cap() {
+        let (mut app, player) = pickup_app();
+        app.world.resource_mut::<PlayerData>().health = 7;
+
+        let pickup = collect(&mut app, player, HealthPickup { amount: 2 });
+        assert_eq!(app.world.resource::<PlayerData>().health, 9);
+        assert!(app.world.get_entity(pickup).is_none());
+
+        collect(&mut app, player, HealthPickup { amount: 2 });
+        let data = app.world.resource::<PlayerData>();
+        assert_eq!(data.health, data.max_health);
+    }
+
+    fn spawn_enemy(app: &mut App, reward: i32, state: AnimState) -> Entity {
+        let info = app.world.resource::<EnemyTypes>().types[TEST_ENEMY].clone();
+        let mut enemy = Enemy::new(TEST_ENEMY, &info);
+        enemy.reward = reward;
+        app.world
+            .spawn((
+                enemy,
+                Transform::default(),
+                Handle::<TextureAtlas>::default(),
+                TextureAtlasSprite::default(),
+                AnimationComponent::new(state),
+            ))
+            .id()
+    }
+
+    #[test]
+    fn bomb_kills_every_living_enemy_for_its_reward() {
+        let (mut app, player) = pickup_app();
+        app.world.entity_mut(player).insert(Transform::default());
+        let living = [
+            spawn_enemy(&mut app, 2, AnimState::Walking),
+            spawn_enemy(&mut app, 3, AnimState::Walking),
+        ];
+        spawn_enemy(&mut app, 5, AnimState::Dying);
+
+        collect(&mut app, player, Bomb);
+        for enemy in living {
+            let anim = app.world.get::<AnimationComponent>(enemy).unwrap();
+            assert!(anim.state.is_dying());
+        }
+        let stats = app.world.resource::<GameStats>();
+        assert_eq!(stats.villagers_saved, 5);
+        assert_eq!(stats.enemies_killed, 2);
+    }
+
+    #[test]
+    fn a_half_chance_drop_falls_from_about_half_the_kills() {
+        let (mut app, player) = pickup_app();
+        app.world.entity_mut(player).insert(Transform::default());
+        app.world
+            .resource_mut::<EnemyTypes>()
+            .types
+            .get_mut(TEST_ENEMY)
+            .unwrap()
+            .drops = vec![DropEntry {
+            kind: DropKind::Health,
+            chance: 0.5,
+        }];
+        const KILLS: usize = 400;
+        for _ in 0..KILLS {
+            spawn_enemy(&mut app, 1, AnimState::Walking);
+        }
+
+        collect(&mut app, player, Bomb);
+        assert_eq!(
+            app.world.resource::<GameStats>().enemies_killed,
+            KILLS as i32
+        );
+        let drops = app.world.query::<&HealthPickup>().iter(&app.world).count();
+        assert!(
+            (KILLS * 2 / 5..KILLS * 3 / 5).contains(&drops),
+            "{drops} drops"
+        );
+    }
+
+    #[test]
+    fn chances_past_a_total_of_one_are_cut_short() {
+        let drops = [
+            DropEntry {
+                kind: DropKind::Health,
+                chance: 0.7,
+            },
+            DropEntry {
+                kind: DropKind::Shield,
+                chance: 0.7,
+            },
+        ];
+        let mut rng = StdRng::seed_from_u64(0);
+        let rolls: Vec<_> = (0..1000).map(|_| roll_drop(&drops, &mut rng)).collect();
+        let health = rolls
+            .iter()
+            .filter(|kind| matches!(kind, Some(DropKind::Health)))
+            .count();
+
+        // one roll per kill, so the shield only gets what is left above 0.7
+        assert!(rolls.iter().all(Option::is_some));
+        assert!((650..750).contains(&health), "{health} health drops");
+    }
+}
@@ -2,21 +2,89 @@ use std::time::Duration;
 
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     animation::{
-        AnimState, AnimationComponent, AnimationList, AnimationListAsset, ImagesToLoad,
-        PlayerAnimation,
+        animation_list_valid, AnimState, AnimationComponent, AnimationList, AnimationListAsset,
+        ImagesToLoad, PlayerAnimation,
     },
-    data::state::GameState,
-    GameplayStart,
+    audio::Sfx,
+    data::{
+        layout::ScreenLayout,
+        localization::Localization,
+        mode::RunModifiers,
+        playfield::{DefenseLine, PlayfieldBounds, PlayfieldOrientation},
+        settings::{AimAssist, Difficulty, InvertVertical},
+        state::GameState,
+        theme::Theme,
+    },
+    entities::{
+        attack::{AttackEffect, AttackKind},
+        boss::boss_intro_active,
+        collision::{SensorCollisions, GROUP_ENEMY, GROUP_PICKUP, GROUP_PLAYER},
+        enemy::{DamageEnemy, Enemy},
+        hazard::{slow_factor, SlowZone},
+        projectile::EnemyProjectile,
+        wave::WaveState,
+        weapon::Weapons,
+    },
+    hud::{HudLayout, HudRoot},
+    input::{Action, Actions},
+    interpolation::{FixedMovementSet, Interpolated, YSorted},
+    pause::{PausableTimers, PauseAppExt, RestartSet},
+    CameraConfig, GameplayStart,
 };
 
+const DEATH_TIME_SCALE: f32 = 0.25;
+const ATTACK_BUFFER: Duration = Duration::from_millis(150);
+const SHIELD_RADIUS: f32 = 24.0;
+const SHIELD_COLOR: Color = Color::rgb(0.3, 0.7, 1.0);
+const RESPAWN_CLEAR_RADIUS: f32 = 150.0;
+const PLAYER_SPEED: f32 = 250.0;
+const PLAYER_HITBOX: Vec2 = Vec2::new(6.0, 7.0);
+const FOCUS_SPEED_MULT: f32 = 0.4;
+const FOCUS_HITBOX_COLOR: Color = Color::rgb(1.0, 0.2, 0.8);
+const KNOCKBACK_SPEED: f32 = 300.0;
+const KNOCKBACK_SECONDS: f32 = 0.2;
+const SLIDE_IN_SPEED: f32 = 200.0;
+const HIT_FLASH_SECONDS: f32 = 0.3;
+
 #[derive(Resource)]
-struct PlayerData {
-    max_health: i32,
-    health: i32,
-    timer: Timer,
+pub struct PlayerData {
+    pub max_health: i32,
+    pub health: i32,
+    pub lives: i32,
+    pub timer: Timer,
+}
+
+impl PlayerData {
+    pub fn heal(&mut self, amount: i32) {
+        self.health = (self.health + amount).min(self.max_health);
+    }
+
+    // the new room comes filled in, and the raise lasts until the run ends
+    pub fn raise_max_health(&mut self, amount: i32, cap: i32) {
+        let raised = (self.max_health + amount).min(cap).max(self.max_health);
+        let gained = raised - self.max_health;
+        self.max_health = raised;
+        self.heal(gained);
+    }
+}
+
+impl PlayerData {
+    pub fn for_run(modifiers: &RunModifiers) -> Self {
+        let mut data = Self::default();
+        data.max_health = modifiers.max_health(data.max_health);
+        data.health = data.max_health;
+        data
+    }
+}
+
+impl PausableTimers for PlayerData {
+    fn timers_mut(&mut self) -> Vec<&mut Timer> {
+        vec![&mut self.timer]
+    }
 }
 
 impl Default for PlayerData {
@@ -24,11 +92,15 @@ impl Default for PlayerData {
         Self {
             max_health: 10,
             health: 10,
+            lives: 3,
             timer: Timer::new(Duration::from_secs_f32(2.0), TimerMode::Repeating),
         }
     }
 }
 
+#[derive(Component)]
+pub struct Player;
+
 #[derive(Component)]
 enum PlayerDirection {
     Up,
@@ -45,6 +117,33 @@ struct PlayerLoaded {
 struct PlayerAttackTimer {
     pub timer: Timer,
     pub attacked: bool,
+    pub buffered_attack_at: Option<Duration>,
+}
+
+impl PlayerAttackTimer {
+    // a press shortly before the cooldown ends still counts once it does
+    fn wants_attack(&mut self, pressed: bool, just_pressed: bool, now: Duration) -> bool {
+        if just_pressed && self.attacked {
+            self.buffered_attack_at = Some(now);
+        }
+        if self
+            .buffered_attack_at
+            .is_some_and(|at| now.saturating_sub(at) > ATTACK_BUFFER)
+        {
+            self.buffered_attack_at = None;
+        }
+        if self.attacked {
+            return false;
+        }
+        let buffered = self.buffered_attack_at.take().is_some();
+        pressed || buffered
+    }
+}
+
+impl PausableTimers for PlayerAttackTimer {
+    fn timers_mut(&mut self) -> Vec<&mut Timer> {
+        vec![&mut self.timer]
+    }
 }
 
 impl Default for PlayerAttackTimer {
@@ -52,24 +151,120 @@ impl Default for PlayerAttackTimer {
         Self {
             timer: Timer::new(Duration::from_secs_f32(0.8), TimerMode::Once),
             attacked: false,
+            buffered_attack_at: None,
         }
     }
 }
 
+#[derive(Resource)]
+pub struct Heat {
+    pub value: f32,
+    pub per_shot: f32,
+    pub cool_rate: f32,
+    pub max: f32,
+    pub unlock_below: f32,
+    pub overheated: bool,
+}
+
+impl Default for Heat {
+    fn default() -> Self {
+        Self {
+            value: 0.0,
+            per_shot: 30.0,
+            cool_rate: 15.0,
+            max: 100.0,
+            unlock_below: 50.0,
+            overheated: false,
+        }
+    }
+}
+
+impl Heat {
+    pub fn add_shot(&mut self) {
+        self.value = (self.value + self.per_shot).min(self.max);
+        if self.value >= self.max {
+            self.overheated = true;
+        }
+    }
+
+    pub fn cool(&mut self, delta: f32) {
+        self.value = (self.value - self.cool_rate * delta).max(0.0);
+        if self.overheated && self.value < self.unlock_below {
+            self.overheated = false;
+        }
+    }
+
+    pub fn ratio(&self) -> f32 {
+        (self.value / self.max).clamp(0.0, 1.0)
+    }
+}
+
 #[derive(Component)]
 struct PlayerAttack {
     pub health: i32,
+    pub speed: f32,
+    pub vertical: f32,
+    pub lifetime: Option<Timer>,
 }
 
-impl Default for PlayerAttack {
-    fn default() -> Self {
-        Self { health: 10 }
+const ATTACK_INITIAL_SCALE: f32 = 0.75;
+// aim assist only bends towards enemies roughly in front of the hero, and
+// only by a fraction of what it would take to line up with them
+const AIM_ASSIST_CONE: f32 = 0.26;
+const AIM_ASSIST_STRENGTH: f32 = 0.4;
+const AIM_ASSIST_MAX_VERTICAL: f32 = 30.0;
+
+// offsets in radians from straight down the lane, one per attack in the volley
+fn volley_angles(kind: &AttackKind) -> Vec<f32> {
+    match *kind {
+        AttackKind::Spread { count, angle } => {
+            let middle = count.saturating_sub(1) as f32 / 2.0;
+            (0..count)
+                .map(|i| ((i as f32 - middle) * angle).to_radians())
+                .collect()
+        }
+        _ => vec![0.0],
     }
 }
 
+fn aim_assist_vertical(
+    origin: Vec2,
+    speed: f32,
+    orientation: PlayfieldOrientation,
+    targets: impl Iterator<Item = Vec2>,
+) -> f32 {
+    let nearest = targets
+        .map(|target| target - origin)
+        .filter(|offset| {
+            let ahead = offset.x * orientation.sign();
+            ahead > 0.0 && offset.y.abs().atan2(ahead) <= AIM_ASSIST_CONE
+        })
+        .min_by(|a, b| a.length_squared().total_cmp(&b.length_squared()));
+    let Some(offset) = nearest else {
+        return 0.0;
+    };
+    // the vertical speed that would meet the target, toned down
+    let time_to_reach = offset.x.abs() / speed.max(1.0);
+    (offset.y / time_to_reach * AIM_ASSIST_STRENGTH)
+        .clamp(-AIM_ASSIST_MAX_VERTICAL, AIM_ASSIST_MAX_VERTICAL)
+}
+
 #[derive(Resource, Default)]
-pub struct PlayerAttackSprite {
-    pub sprite: Handle<Image>,
+pub struct AttackPool {
+    free: Vec<Entity>,
+}
+
+impl AttackPool {
+    fn release(&mut self, commands: &mut Commands, entity: Entity) {
+        if self.free.contains(&entity) {
+            return;
+        }
+        commands
+            .entity(entity)
+            .remove::<(PlayerAttack, AttackEffect, Collider)>()
+            .insert(Visibility::Hidden);
+        self.free.push(entity);
+    }
 }
 
 pub struct PlayerPlugin;
@@ -77,44 +272,103 @@ pub struct PlayerPlugin;
 #[derive(Resource)]
 pub struct PlayerPhysicsAttached(bool);
 
-#[derive(Resource, Default)]
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
 pub struct GameStats {
     pub villagers_saved: i32,
     pub villagers_lost: i32,
     pub entites_spawned: i32,
+    #[serde(default)]
+    pub enemies_killed: i32,
+}
+
+#[derive(Resource, Default)]
+pub struct ElapsedGameplay {
+    pub seconds: f32,
 }
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<PlayerLoaded>()
             .insert_resource(PlayerAttackTimer::default())
-            .insert_resource(PlayerAttackSprite::default())
             .insert_resource(PlayerPhysicsAttached(false))
             .insert_resource(GameStats::default())
+            .init_resource::<ElapsedGameplay>()
             .insert_resource(PlayerData::default())
-            .add_systems(Startup, load_assets)
+            .add_event::<DamagePlayer>()
+            .init_resource::<DeathSequence>()
+            .init_resource::<Dash>()
+            .init_resource::<AttackPool>()
+            .init_resource::<Heat>()
+            .init_resource::<Shield>()
+            .init_resource::<PlayerKnockback>()
+            .preserve_timers_on_pause::<Dash>()
+            .preserve_timers_on_pause::<PlayerKnockback>()
+            .preserve_timers_on_pause::<PlayerData>()
+            .preserve_timers_on_pause::<PlayerAttackTimer>()
+            .reset_on_restart::<GameStats>()
+            .reset_on_restart::<ElapsedGameplay>()
+            .reset_on_restart::<DeathSequence>()
+            .reset_on_restart::<Dash>()
+            .reset_on_restart::<Heat>()
+            .reset_on_restart::<Shield>()
+            .reset_on_restart::<PlayerKnockback>()
+            .reset_on_restart::<PlayerAttackTimer>()
+            .add_systems(
+                OnEnter(GameState::GamePlay),
+                (restart_player, reset_player_data).in_set(RestartSet),
+            )
             .add_systems(Update, setup)
-            .add_systems(OnEnter(GameState::GamePlay), spawn_text)
             .add_systems(
-                Update,
-                slide_in_player.run_if(in_state(GameState::TransitionToGamePlay)),
+                OnEnter(GameState::TransitionToGamePlay),
+                (reset_shield, reset_player_data),
+            )
+            .add_systems(
+                OnExit(GameState::TransitionToGamePlay),
+                (spawn_text, spawn_shield_count),
+            )
+            .add_systems(
+                FixedUpdate,
+                slide_in_player
+                    .in_set(FixedMovementSet)
+                    .run_if(in_state(GameState::TransitionToGamePlay)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (move_player, dash_player)
+                    .chain()
+                    .in_set(FixedMovementSet)
+                    .run_if(in_state(GameState::GamePlay)),
             )
             .add_systems(
                 Update,
-                load_player_animations.run_if(in_state(GameState::Loading)),
+                load_player_animations
+                    .run_if(in_state(GameState::Loading).and_then(animation_list_valid)),
             )
             .add_systems(Update, add_collisions.run_if(in_state(GameState::GamePlay)))
             .add_systems(
                 Update,
                 (
-                    move_player,
-                    handle_input,
+                    // attacks go back to the pool before a new one can take
+                    // one out, so a reused entity never loses its new bundle
+                    // to a release queued the same frame
+                    (
+                        update_attack,
+                        react_to_player_attack_collision,
+                        handle_input.run_if(not(boss_intro_active)),
+                    )
+                        .chain(),
+                    hold_still.run_if(boss_intro_active),
                     change_player_anim,
-                    update_attack,
                     tick_attack_timer,
-                    react_to_player_attack_collision,
-                    react_to_player_collision,
+                    cool_heat,
+                    draw_shield,
+                    draw_focus_hitbox,
+                    (react_to_player_collision, apply_player_damage).chain(),
                     update_text,
+                    tick_death_sequence,
+                    tick_respawn_grace,
+                    fade_hit_flash,
+                    tick_elapsed,
                 )
                     .run_if(in_state(GameState::GamePlay)),
             )
@@ -122,62 +376,243 @@ impl Plugin for PlayerPlugin {
     }
 }
 
+#[derive(Resource)]
+pub struct DeathSequence {
+    pub timer: Timer,
+    pub active: bool,
+}
+
+impl Default for DeathSequence {
+    fn default() -> Self {
+        Self {
+            timer: Timer::new(Duration::from_secs_f32(0.5), TimerMode::Once),
+            active: false,
+        }
+    }
+}
+
+pub fn death_in_progress(death: Res<DeathSequence>) -> bool {
+    death.active
+}
+
+#[derive(Resource)]
+pub struct Dash {
+    pub timer: Timer,
+    pub cooldown: Timer,
+    pub active: bool,
+    pub distance: f32,
+    direction: f32,
+}
+
+impl Default for Dash {
+    fn default() -> Self {
+        let cooldown = Duration::from_secs_f32(1.0);
+        let mut dash = Self {
+            timer: Timer::new(Duration::from_secs_f32(0.15), TimerMode::Once),
+            cooldown: Timer::new(cooldown, TimerMode::Once),
+            active: false,
+            distance: 120.0,
+            direction: 0.0,
+        };
+        dash.cooldown.set_elapsed(cooldown);
+        dash
+    }
+}
+
+// a vertical shove away from whatever hit the hero, fading out over the timer
+#[derive(Resource)]
+pub struct PlayerKnockback {
+    pub velocity: f32,
+    pub timer: Timer,
+}
+
+impl Default for PlayerKnockback {
+    fn default() -> Self {
+        let mut timer = Timer::from_seconds(KNOCKBACK_SECONDS, TimerMode::Once);
+        timer.tick(timer.duration());
+        Self {
+            velocity: 0.0,
+            timer,
+        }
+    }
+}
+
+impl PlayerKnockback {
+    // hits from dead level push downwards
+    pub fn push_away(&mut self, player_y: f32, from_y: f32) {
+        let direction = if player_y > from_y { 1.0 } else { -1.0 };
+        self.velocity = KNOCKBACK_SPEED * direction;
+        self.timer.reset();
+    }
+
+    pub fn current(&self) -> f32 {
+        if self.timer.finished() {
+            0.0
+        } else {
+            self.velocity * (1.0 - self.timer.percent())
+        }
+    }
+
+    pub fn stop(&mut self) {
+        let duration = self.timer.duration();
+        self.timer.set_elapsed(duration);
+    }
+}
+
+impl PausableTimers for PlayerKnockback {
+    fn timers_mut(&mut self) -> Vec<&mut Timer> {
+        vec![&mut self.timer]
+    }
+}
+
+impl PausableTimers for Dash {
+    fn timers_mut(&mut self) -> Vec<&mut Timer> {
+        vec![&mut self.timer, &mut self.cooldown]
+    }
+}
+
+impl Dash {
+    pub fn start(&mut self, direction: f32) -> bool {
+        if self.active || !self.cooldown.finished() || direction == 0.0 {
+            return false;
+        }
+        self.active = true;
+        self.direction = direction;
+        self.timer.reset();
+        self.cooldown.reset();
+        true
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.distance / self.timer.duration().as_secs_f32()
+    }
+}
+
+#[derive(Component)]
+pub struct Invincible;
+
+#[derive(Component)]
+struct RespawnGrace {
+    timer: Timer,
+}
+
+// only ever touches the sprite color, so it shows on top of whatever else is
+// going on with the hero's visibility. other tints leave the hero alone while
+// it runs
+#[derive(Component)]
+pub struct PlayerHitFlash {
+    timer: Timer,
+}
+
+impl PlayerHitFlash {
+    pub fn new() -> Self {
+        Self {
+            timer: Timer::from_seconds(HIT_FLASH_SECONDS, TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct Shield {
+    pub charges: i32,
+    flash: Timer,
+}
+
+impl Default for Shield {
+    fn default() -> Self {
+        let mut flash = Timer::new(Duration::from_secs_f32(0.2), TimerMode::Once);
+        flash.tick(flash.duration());
+        Self { charges: 0, flash }
+    }
+}
+
+impl Shield {
+    pub fn absorb(&mut self) -> bool {
+        if self.charges <= 0 {
+            return false;
+        }
+        self.charges -= 1;
+        self.flash.reset();
+        true
+    }
+}
+
+#[derive(Component)]
+struct ShieldCount;
+
+#[derive(Event)]
+pub struct DamagePlayer {
+    pub amount: i32,
+    // the height of whatever touched the hero, contact hits shove it away
+    pub from_y: Option<f32>,
+}
+
 #[derive(Component, Default)]
 struct EntitiesText;
 
 fn spawn_text(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    localization: Res<Localization>,
+    layout: Res<HudLayout>,
     player_data: Res<PlayerData>,
 ) {
+    let style = layout.score.text_style(theme.hud_style(&asset_server));
     commands.spawn((
         TextBundle {
             text: Text::from_sections([
                 TextSection {
-                    value: format!("Entities Spawned: {}", 0),
-                    style: TextStyle {
-                        font: asset_server.load("fonts/plop.ttf"),
-                        font_size: 25.0,
-                        color: Color::WHITE,
-                    },
+                    value: format!("{}: {}", localization.get("hud_entities_spawned"), 0),
+                    style: style.clone(),
                 },
                 TextSection {
-                    value: format!("Player Life: {}", player_data.health),
-                    style: TextStyle {
-                        font: asset_server.load("fonts/plop.ttf"),
-                        font_size: 25.0,
-                        color: Color::WHITE,
-                    },
+                    value: format!(
+                        "{}: {}",
+                        localization.get("hud_player_life"),
+                        player_data.health
+                    ),
+                    style: style.clone(),
+                },
+                TextSection {
+                    value: format!(" {}: {}", localization.get("hud_lives"), player_data.lives),
+                    style: style.clone(),
                 },
             ]),
+            style: layout.score.style(),
             ..default()
         },
         EntitiesText,
+        HudRoot,
     ));
 }
 
 fn update_text(
     asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    localization: Res<Localization>,
+    layout: Res<HudLayout>,
     player_data: Res<PlayerData>,
     mut query: Query<(&mut Text, &EntitiesText)>,
 ) {
+    let style = layout.score.text_style(theme.hud_style(&asset_server));
     for (mut text, _) in &mut query {
         *text = Text::from_sections([
             TextSection {
-                value: format!("Entities Spawned: {}", 0),
-                style: TextStyle {
-                    font: asset_server.load("fonts/plop.ttf"),
-                    font_size: 25.0,
-                    color: Color::WHITE,
-                },
+                value: format!("{}: {}", localization.get("hud_entities_spawned"), 0),
+                style: style.clone(),
             },
             TextSection {
-                value: format!("Player Life: {}", player_data.health),
-                style: TextStyle {
-                    font: asset_server.load("fonts/plop.ttf"),
-                    font_size: 25.0,
-                    color: Color::WHITE,
-                },
+                value: format!(
+                    "{}: {}",
+                    localization.get("hud_player_life"),
+                    player_data.health
+                ),
+                style: style.clone(),
+            },
+            TextSection {
+                value: format!(" {}: {}", localization.get("hud_lives"), player_data.lives),
+                style: style.clone(),
             },
         ]);
     }
@@ -205,33 +640,30 @@ fn player_dies(
     }
 }
 
-fn load_assets(
-    asset_server: Res<AssetServer>,
-    mut attack_sprite: ResMut<PlayerAttackSprite>,
-    mut images_to_load: ResMut<ImagesToLoad>,
-) {
-    let handle = asset_server.load("sprites/other/player_attack.png");
-    images_to_load.images.push(handle.id());
-    attack_sprite.sprite = handle;
-}
-
 fn setup(
     mut commands: Commands,
     mut player_loaded: ResMut<PlayerLoaded>,
     player_anim: Res<PlayerAnimation>,
+    layout: Res<ScreenLayout>,
+    camera: Res<CameraConfig>,
+    defense_line: Res<DefenseLine>,
 ) {
     if player_loaded.loaded || !player_anim.loaded {
         return;
     }
+    let translation = Vec3::new(layout.menu_pos(&camera, &defense_line).x, 40.0, 0.0);
     commands.spawn((
         SpriteSheetBundle {
             texture_atlas: player_anim.anims.get_handle(AnimState::Idle).unwrap(),
-            transform: Transform::from_translation(Vec3::new(-500.0, 40.0, 0.0))
-                .with_scale(Vec3::splat(2.0)),
+            transform: Transform::from_translation(translation).with_scale(Vec3::splat(2.0)),
             ..default()
         },
         AnimationComponent::new(AnimState::Idle),
+        player_anim.anims.frame_times.clone(),
         PlayerDirection::None,
+        Player,
+        Interpolated::new(translation),
+        YSorted,
     ));
     player_loaded.loaded = true;
 }
@@ -245,37 +677,49 @@ fn add_collisions(
         if let Ok(entity) = player.get_single() {
             commands.entity(entity).insert((
                 RigidBody::KinematicPositionBased,
-                Collider::cuboid(6.0, 7.0),
+                Collider::cuboid(PLAYER_HITBOX.x, PLAYER_HITBOX.y),
                 Sensor,
                 ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_KINEMATIC,
                 ActiveEvents::COLLISION_EVENTS,
-                CollisionGroups::new(Group::GROUP_2, Group::GROUP_1),
+                CollisionGroups::new(GROUP_PLAYER, GROUP_ENEMY | GROUP_PICKUP),
             ));
         }
         attached.0 = true;
     }
 }
 
+// snaps onto the line once the last step would reach it, however fast the
+// difficulty makes the walk
 fn slide_in_player(
     time: Res<Time>,
+    difficulty: Res<Difficulty>,
     mut gameplay_start: ResMut<GameplayStart>,
+    orientation: Res<PlayfieldOrientation>,
+    defense_line: Res<DefenseLine>,
     player_anim: Res<PlayerAnimation>,
     mut player: Query<(
         &PlayerDirection,
         &mut Transform,
         &mut Handle<TextureAtlas>,
+        &mut TextureAtlasSprite,
         &mut AnimationComponent,
     )>,
 ) {
     if !gameplay_start.play_inplace || !player_anim.loaded {
-        for (_, mut player_transform, mut handle, mut anim) in player.iter_mut() {
+        let target_x = defense_line.player_x(*orientation);
+        for (_, mut player_transform, mut handle, mut sprite, mut anim) in player.iter_mut() {
             if anim.state == AnimState::Idle {
                 anim.state = AnimState::Walking;
                 *handle = player_anim.anims.get_handle(anim.state).unwrap();
             }
-            player_transform.translation.x += 200.0 * time.delta_seconds();
-            if player_transform.translation.x >= gameplay_start.player_endpos.x {
+            let step = SLIDE_IN_SPEED * difficulty.transition_speed() * time.delta_seconds();
+            let remaining = target_x - player_transform.translation.x;
+            if remaining.abs() > step {
+                player_transform.translation.x += step * remaining.signum();
+            } else {
+                player_transform.translation.x = target_x;
                 gameplay_start.play_inplace = true;
+                sprite.flip_x = orientation.flip_x();
                 anim.state = AnimState::Idle;
                 *handle = player_anim.anims.get_handle(anim.state).unwrap();
             }
@@ -283,26 +727,74 @@ fn slide_in_player(
     }
 }
 
+// focus trades speed for precision, the clamp below still applies to the
+// slower step and the dash moves the player on its own. a dash also cuts any
+// knockback short rather than the two pulling against each other
 fn move_player(
     time: Res<Time>,
+    actions: Actions,
     player_anim: Res<PlayerAnimation>,
+    bounds: Res<PlayfieldBounds>,
+    dash: Res<Dash>,
+    mut knockback: ResMut<PlayerKnockback>,
     mut player_data: ResMut<PlayerData>,
     mut player_pos: Query<(&PlayerDirection, &mut Transform)>,
+    zones: Query<&SlowZone>,
 ) {
     if !player_anim.loaded {
         return;
     }
+    let speed = if actions.pressed(Action::Focus) {
+        PLAYER_SPEED * FOCUS_SPEED_MULT
+    } else {
+        PLAYER_SPEED
+    };
+    if dash.active {
+        knockback.stop();
+    }
+    knockback.timer.tick(time.delta());
+    let shove = knockback.current() * time.delta_seconds();
     for (dir, mut transform) in &mut player_pos {
+        let speed = speed * slow_factor(zones.iter(), transform.translation.truncate());
         match *dir {
-            PlayerDirection::Up => transform.translation.y += 250.0 * time.delta_seconds(),
-            PlayerDirection::Down => transform.translation.y -= 250.0 * time.delta_seconds(),
+            PlayerDirection::Up => transform.translation.y += speed * time.delta_seconds(),
+            PlayerDirection::Down => transform.translation.y -= speed * time.delta_seconds(),
             _ => {}
         }
+        transform.translation.y = bounds.clamp_y(transform.translation.y + shove);
         player_data.timer.tick(time.delta());
         if player_data.timer.just_finished() {
-            player_data.health = (player_data.health + 1).min(player_data.max_health);
+            player_data.heal(1);
+        }
+    }
+}
+
+fn dash_player(
+    mut commands: Commands,
+    time: Res<Time>,
+    bounds: Res<PlayfieldBounds>,
+    mut dash: ResMut<Dash>,
+    mut player: Query<(Entity, &mut Transform, Option<&RespawnGrace>), With<Player>>,
+) {
+    dash.cooldown.tick(time.delta());
+    if !dash.active {
+        return;
+    }
+    // only the part of the frame the dash was still running for counts, so it
+    // covers its distance exactly whatever the frame length
+    let before = dash.timer.elapsed();
+    dash.timer.tick(time.delta());
+    let dashed = (dash.timer.elapsed() - before).as_secs_f32();
+    let step = dash.speed() * dash.direction * dashed;
+    for (entity, mut transform, grace) in &mut player {
+        transform.translation.y = bounds.clamp_y(transform.translation.y + step);
+        if dash.timer.finished() && grace.is_none() {
+            commands.entity(entity).remove::<Invincible>();
         }
     }
+    if dash.timer.finished() {
+        dash.active = false;
+    }
 }
 
 fn change_player_anim(
@@ -340,46 +832,142 @@ fn tick_attack_timer(time: Res<Time>, mut timer: ResMut<PlayerAttackTimer>) {
     }
 }
 
+fn cool_heat(time: Res<Time>, mut heat: ResMut<Heat>) {
+    heat.cool(time.delta_seconds());
+}
+
+// the hero stands and watches while a boss makes its entrance
+fn hold_still(mut player: Query<&mut PlayerDirection>) {
+    for mut dir in &mut player {
+        *dir = PlayerDirection::None;
+    }
+}
+
 fn handle_input(
     mut commands: Commands,
-    input: Res<Input<KeyCode>>,
-    player_attack: Res<PlayerAttackSprite>,
+    time: Res<Time>,
+    actions: Actions,
+    mut weapons: ResMut<Weapons>,
     mut player_attack_timer: ResMut<PlayerAttackTimer>,
-    mut player: Query<(&mut PlayerDirection, &Transform)>,
+    mut heat: ResMut<Heat>,
+    orientation: Res<PlayfieldOrientation>,
+    mut dash: ResMut<Dash>,
+    mut attack_pool: ResMut<AttackPool>,
+    invert_vertical: Res<InvertVertical>,
+    aim_assist: Res<AimAssist>,
+    modifiers: Res<RunModifiers>,
+    mut sfx: EventWriter<Sfx>,
+    mut player: Query<(Entity, &mut PlayerDirection, &Transform)>,
+    enemies: Query<(&Transform, &AnimationComponent), (With<Enemy>, Without<Player>)>,
 ) {
     let query = player.get_single_mut();
-    if let Ok((mut dir, transform)) = query {
-        if input.pressed(KeyCode::W) {
-            *dir = PlayerDirection::Up
-        } else if input.pressed(KeyCode::S) {
-            *dir = PlayerDirection::Down
+    if let Ok((entity, mut dir, transform)) = query {
+        let (up, down) = if invert_vertical.0 {
+            (PlayerDirection::Down, PlayerDirection::Up)
+        } else {
+            (PlayerDirection::Up, PlayerDirection::Down)
+        };
+        if actions.pressed(Action::MoveUp) {
+            *dir = up
+        } else if actions.pressed(Action::MoveDown) {
+            *dir = down
         } else {
             *dir = PlayerDirection::None
         }
 
-        if input.pressed(KeyCode::Space) && !player_attack_timer.attacked {
+        if actions.just_pressed(Action::Dash) {
+            let direction = match *dir {
+                PlayerDirection::Up => 1.0,
+                PlayerDirection::Down => -1.0,
+                PlayerDirection::None => 0.0,
+            };
+            if dash.start(direction) {
+                commands.entity(entity).insert(Invincible);
+            }
+        }
+
+        if actions.just_pressed(Action::SwitchWeapon) {
+            weapons.equip_next();
+        }
+
+        let (Some(sprite), Some(attack)) = (weapons.sprite(), weapons.attack()) else {
+            return;
+        };
+        let wants_attack = player_attack_timer.wants_attack(
+            actions.pressed(Action::Attack),
+            actions.just_pressed(Action::Attack),
+            time.elapsed(),
+        );
+        if wants_attack && !heat.overheated {
             player_attack_timer.attacked = true;
             player_attack_timer.timer.reset();
-            commands.spawn((
-                SpriteBundle {
-                    texture: player_attack.sprite.clone(),
-                    transform: Transform::from_translation(Vec3::new(
-                        transform.translation.x + 5.0,
-                        transform.translation.y,
-                        0.0,
-                    ))
-                    .with_scale(Vec3::splat(0.75)),
-                    visibility: Visibility::Visible,
-                    ..default()
-                },
-                PlayerAttack::default(),
-                RigidBody::KinematicPositionBased,
-                Collider::capsule_y(10.0, 6.0),
-                Sensor,
-                ActiveEvents::COLLISION_EVENTS,
-                ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_KINEMATIC,
-                CollisionGroups::new(Group::GROUP_2, Group::GROUP_1),
-            ));
+            heat.add_shot();
+            sfx.send(Sfx::Attack);
+            // a ground slam hits everything around the hero at once instead of
+            // travelling down the lane
+            let (speed, collider, lifetime) = match attack.kind {
+                AttackKind::GroundSlam { radius } => (
+                    0.0,
+                    Collider::ball(radius),
+                    Some(Timer::new(Duration::from_secs_f32(0.1), TimerMode::Once)),
+                ),
+                _ => (attack.speed, Collider::capsule_y(10.0, 6.0), None),
+            };
+            let vertical = if aim_assist.0 && speed > 0.0 {
+                aim_assist_vertical(
+                    transform.translation.truncate(),
+                    speed,
+                    *orientation,
+                    enemies
+                        .iter()
+                        .filter(|(_, anim)| !anim.state.is_dying())
+                        .map(|(enemy, _)| enemy.translation.truncate()),
+                )
+            } else {
+                0.0
+            };
+            // every pellet of a spread is its own attack with its own pierce,
+            // the cooldown above already covers the whole volley
+            for angle in volley_angles(&attack.kind) {
+                let bundle = (
+                    SpriteBundle {
+                        sprite: Sprite {
+                            flip_x: orientation.flip_x(),
+                            ..default()
+                        },
+                        texture: sprite.clone(),
+                        transform: Transform::from_translation(Vec3::new(
+                            transform.translation.x + 5.0 * orientation.sign(),
+                            transform.translation.y,
+                            0.0,
+                        ))
+                        .with_scale(Vec3::splat(ATTACK_INITIAL_SCALE)),
+                        visibility: Visibility::Visible,
+                        ..default()
+                    },
+                    PlayerAttack {
+                        health: modifiers.pierce(attack.pierce),
+                        speed: speed * angle.cos(),
+                        vertical: vertical + speed * angle.sin(),
+                        lifetime: lifetime.clone(),
+                    },
+                    AttackEffect(attack.kind.clone()),
+                    RigidBody::KinematicPositionBased,
+                    collider.clone(),
+                    Sensor,
+                    ActiveEvents::COLLISION_EVENTS,
+                    ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_KINEMATIC,
+                    CollisionGroups::new(GROUP_PLAYER, GROUP_ENEMY),
+                );
+                match attack_pool.free.pop() {
+                    Some(pooled) => {
+                        commands.entity(pooled).insert(bundle);
+                    }
+                    None => {
+                        commands.spawn(bundle);
+                    }
+                }
+            }
         }
     }
 }
@@ -388,74 +976,337 @@ fn update_attack(
     mut commands: Commands,
     time: Res<Time>,
     start: Res<GameplayStart>,
-    mut query: Query<(Entity, &mut Transform, &PlayerAttack)>,
+    bounds: Res<PlayfieldBounds>,
+    orientation: Res<PlayfieldOrientation>,
+    mut attack_pool: ResMut<AttackPool>,
+    mut query: Query<(Entity, &mut Transform, &mut PlayerAttack)>,
 ) {
-    for (entity, mut transform, _) in &mut query {
+    for (entity, mut transform, mut attack) in &mut query {
         transform.scale = transform
             .scale
             .lerp(Vec3::splat(2.0), time.delta_seconds() * 2.0);
 
-        transform.translation.x += 150.0 * time.delta_seconds();
-        if transform.translation.x > start.camera_endpos.x + 450.0 {
-            commands.entity(entity).despawn();
+        transform.translation.x += attack.speed * orientation.sign() * time.delta_seconds();
+        transform.translation.y += attack.vertical * time.delta_seconds();
+        let expired = attack
+            .lifetime
+            .as_mut()
+            .is_some_and(|lifetime| lifetime.tick(time.delta()).finished());
+        if expired
+            || bounds.has_escaped(transform.translation.x, start.camera_endpos.x, *orientation)
+        {
+            attack_pool.release(&mut commands, entity);
         }
     }
 }
 
 fn react_to_player_attack_collision(
     mut commands: Commands,
-    mut collision_events: EventReader<CollisionEvent>,
+    mut collisions: SensorCollisions,
+    mut attack_pool: ResMut<AttackPool>,
     mut query: Query<(Entity, &mut PlayerAttack)>,
+    mut projectiles: Query<(&mut EnemyProjectile, &mut CollisionGroups)>,
 ) {
-    for event in collision_events.read() {
-        if let CollisionEvent::Started(a, b, flags) = event {
-            if flags.bits() & 0b01 == 0b01 {
-                let attack = if let Ok(result) = query.get_mut(*a) {
-                    Ok(result)
-                } else if let Ok(result) = query.get_mut(*b) {
-                    Ok(result)
-                } else {
-                    Err(())
-                };
-                if let Ok((entity, mut attack)) = attack {
-                    attack.health -= 1;
-                    if attack.health <= 0 {
-                        commands.entity(entity).despawn();
-                    }
-                }
+    for (a, b) in collisions.started() {
+        let (attack, other) = if let Ok(result) = query.get_mut(a) {
+            (Ok(result), b)
+        } else if let Ok(result) = query.get_mut(b) {
+            (Ok(result), a)
+        } else {
+            (Err(()), a)
+        };
+        if let Ok((entity, mut attack)) = attack {
+            // a parry costs the attack nothing
+            if let Ok((mut projectile, mut groups)) = projectiles.get_mut(other) {
+                projectile.reflect(&mut groups);
+                continue;
+            }
+            attack.health -= 1;
+            if attack.health <= 0 {
+                attack_pool.release(&mut commands, entity);
             }
         }
     }
 }
 
 fn react_to_player_collision(
-    mut collision_events: EventReader<CollisionEvent>,
-    mut next_state: ResMut<NextState<GameState>>,
+    mut collisions: SensorCollisions,
+    mut damage: EventWriter<DamagePlayer>,
+    player: Query<(), With<Player>>,
+    enemies: Query<&Transform, With<Enemy>>,
+) {
+    for (a, b) in collisions.started() {
+        let enemy = if player.contains(a) {
+            enemies.get(b)
+        } else if player.contains(b) {
+            enemies.get(a)
+        } else {
+            continue;
+        };
+        if let Ok(enemy) = enemy {
+            damage.send(DamagePlayer {
+                amount: 1,
+                from_y: Some(enemy.translation.y),
+            });
+        }
+    }
+}
+
+fn apply_player_damage(
+    mut commands: Commands,
+    mut damage: EventReader<DamagePlayer>,
     mut player_data: ResMut<PlayerData>,
-    mut query: Query<(Entity, &PlayerDirection)>,
+    mut death: ResMut<DeathSequence>,
+    mut shield: ResMut<Shield>,
+    mut time: ResMut<Time<Virtual>>,
+    mut kill: EventWriter<DamageEnemy>,
+    mut waves: ResMut<WaveState>,
+    player_anim: Res<PlayerAnimation>,
+    defense_line: Res<DefenseLine>,
+    orientation: Res<PlayfieldOrientation>,
+    dash: Res<Dash>,
+    mut knockback: ResMut<PlayerKnockback>,
+    mut query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Handle<TextureAtlas>,
+            &mut TextureAtlasSprite,
+            &mut AnimationComponent,
+            &mut Interpolated,
+        ),
+        (With<Player>, Without<Invincible>),
+    >,
+    enemies: Query<(Entity, &Enemy, &Transform, &AnimationComponent), Without<Player>>,
 ) {
-    for event in collision_events.read() {
-        if let CollisionEvent::Started(a, b, flags) = event {
-            if flags.bits() & 0b01 == 0b01 {
-                let player = if let Ok(result) = query.get_mut(*a) {
-                    Ok(result)
-                } else if let Ok(result) = query.get_mut(*b) {
-                    Ok(result)
-                } else {
-                    Err(())
-                };
-                if let Ok((_, _)) = player {
-                    player_data.health -= 1;
-                    player_data.timer.reset();
-                    if player_data.health <= 0 {
-                        next_state.set(GameState::GameOver);
-                    }
+    for event in damage.read() {
+        if death.active {
+            return;
+        }
+        let Ok((entity, mut transform, mut handle, mut sprite, mut anim, mut interpolated)) =
+            query.get_single_mut()
+        else {
+            return;
+        };
+        if shield.absorb() {
+            continue;
+        }
+        // only a hit that lands shoves the hero, and never mid dash
+        if let (Some(from_y), false) = (event.from_y, dash.active) {
+            knockback.push_away(transform.translation.y, from_y);
+        }
+        waves.took_damage_this_wave = true;
+        commands.entity(entity).insert(PlayerHitFlash::new());
+        player_data.health -= event.amount;
+        player_data.timer.reset();
+        if player_data.health <= 0 && player_data.lives > 1 {
+            player_data.lives -= 1;
+            player_data.health = player_data.max_health;
+            transform.translation.x = defense_line.player_x(*orientation);
+            transform.translation.y = 0.0;
+            // a teleport, not a move, so it isn't blended back from where it died
+            *interpolated = Interpolated::new(transform.translation);
+            anim.reset(AnimState::Idle);
+            sprite.index = anim.first;
+            *handle = player_anim.anims.get_handle(anim.state).unwrap();
+            // give the hero room to breathe instead of respawning into a crowd
+            let origin = transform.translation.truncate();
+            for (enemy_entity, enemy, enemy_transform, enemy_anim) in &enemies {
+                if !enemy_anim.state.is_dying()
+                    && enemy_transform.translation.truncate().distance(origin)
+                        < RESPAWN_CLEAR_RADIUS
+                {
+                    kill.send(DamageEnemy {
+                        entity: enemy_entity,
+                        amount: enemy.health,
+                    });
                 }
             }
+            commands.entity(entity).insert((
+                Invincible,
+                RespawnGrace {
+                    timer: Timer::new(Duration::from_secs_f32(2.0), TimerMode::Once),
+                },
+            ));
+            return;
+        }
+        if player_data.health <= 0 {
+            death.active = true;
+            death.timer.reset();
+            time.set_relative_speed(DEATH_TIME_SCALE);
+            anim.state = AnimState::Dying;
+            sprite.index = 0;
+            *handle = player_anim.anims.get_handle(anim.state).unwrap();
         }
     }
 }
 
+// starts fully red on the hit and eases back to white
+fn fade_hit_flash(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut player: Query<(Entity, &mut PlayerHitFlash, &mut TextureAtlasSprite)>,
+) {
+    for (entity, mut flash, mut sprite) in &mut player {
+        let t = flash.timer.tick(time.delta()).percent();
+        sprite.color = Color::rgb(1.0, t, t);
+        if flash.timer.finished() {
+            sprite.color = Color::WHITE;
+            commands.entity(entity).remove::<PlayerHitFlash>();
+        }
+    }
+}
+
+fn tick_respawn_grace(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut player: Query<(Entity, &mut RespawnGrace)>,
+) {
+    for (entity, mut grace) in &mut player {
+        grace.timer.tick(time.delta());
+        if grace.timer.finished() {
+            commands
+                .entity(entity)
+                .remove::<(Invincible, RespawnGrace)>();
+        }
+    }
+}
+
+// a restarted run puts the hero back on the line with nothing left in
+// flight, and undoes any slow motion a death or last stand left behind
+fn restart_player(
+    mut commands: Commands,
+    mut attack_pool: ResMut<AttackPool>,
+    mut time: ResMut<Time<Virtual>>,
+    player_anim: Res<PlayerAnimation>,
+    defense_line: Res<DefenseLine>,
+    orientation: Res<PlayfieldOrientation>,
+    attacks: Query<Entity, With<PlayerAttack>>,
+    mut player: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Interpolated,
+            &mut PlayerDirection,
+            &mut Handle<TextureAtlas>,
+            &mut TextureAtlasSprite,
+            &mut AnimationComponent,
+        ),
+        With<Player>,
+    >,
+) {
+    time.set_relative_speed(1.0);
+    for entity in &attacks {
+        attack_pool.release(&mut commands, entity);
+    }
+    for (entity, mut transform, mut interpolated, mut dir, mut handle, mut sprite, mut anim) in
+        &mut player
+    {
+        transform.translation.x = defense_line.player_x(*orientation);
+        transform.translation.y = 0.0;
+        *interpolated = Interpolated::new(transform.translation);
+        *dir = PlayerDirection::None;
+        anim.reset(AnimState::Idle);
+        sprite.index = anim.first;
+        sprite.color = Color::WHITE;
+        *handle = player_anim.anims.get_handle(anim.state).unwrap();
+        commands
+            .entity(entity)
+            .remove::<(Invincible, RespawnGrace, PlayerHitFlash)>();
+    }
+}
+
+fn reset_player_data(modifiers: Res<RunModifiers>, mut player_data: ResMut<PlayerData>) {
+    *player_data = PlayerData::for_run(&modifiers);
+}
+
+fn reset_shield(mut shield: ResMut<Shield>) {
+    *shield = Shield::default();
+}
+
+fn spawn_shield_count(mut commands: Commands, asset_server: Res<AssetServer>, theme: Res<Theme>) {
+    let mut style = theme.hud_style(&asset_server);
+    style.color = SHIELD_COLOR;
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section("", style).with_alignment(TextAlignment::Center),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        ShieldCount,
+    ));
+}
+
+fn draw_focus_hitbox(
+    mut gizmos: Gizmos,
+    actions: Actions,
+    player: Query<&Transform, With<Player>>,
+) {
+    if !actions.pressed(Action::Focus) {
+        return;
+    }
+    for transform in &player {
+        gizmos.rect_2d(
+            transform.translation.truncate(),
+            0.0,
+            PLAYER_HITBOX * 2.0 * transform.scale.truncate(),
+            FOCUS_HITBOX_COLOR,
+        );
+    }
+}
+
+fn draw_shield(
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+    mut shield: ResMut<Shield>,
+    player: Query<&Transform, With<Player>>,
+    mut counts: Query<
+        (&mut Text, &mut Transform, &mut Visibility),
+        (With<ShieldCount>, Without<Player>),
+    >,
+) {
+    shield.flash.tick(time.delta());
+    let Ok(player_transform) = player.get_single() else {
+        return;
+    };
+    let center = player_transform.translation.truncate();
+    let flashing = !shield.flash.finished();
+    if shield.charges > 0 || flashing {
+        let color = if flashing { Color::WHITE } else { SHIELD_COLOR };
+        gizmos.circle_2d(center, SHIELD_RADIUS, color);
+    }
+    for (mut text, mut transform, mut visibility) in &mut counts {
+        if shield.charges <= 0 {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        *visibility = Visibility::Inherited;
+        text.sections[0].value = shield.charges.to_string();
+        transform.translation = (center + Vec2::new(0.0, SHIELD_RADIUS + 12.0)).extend(10.0);
+    }
+}
+
+fn tick_elapsed(time: Res<Time>, mut elapsed: ResMut<ElapsedGameplay>) {
+    elapsed.seconds += time.delta_seconds();
+}
+
+fn tick_death_sequence(
+    real_time: Res<Time<bevy::time::Real>>,
+    mut death: ResMut<DeathSequence>,
+    mut time: ResMut<Time<Virtual>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !death.active {
+        return;
+    }
+    death.timer.tick(real_time.delta());
+    if death.timer.finished() {
+        death.active = false;
+        time.set_relative_speed(1.0);
+        next_state.set(GameState::GameOver);
+    }
+}
+
 fn load_player_animations(
     mut list: ResMut<AnimationList>,
     asset_server: Res<AssetServer>,
@@ -492,6 +1343,668 @@ fn load_player_animations(
             .anims
             .add_handle(name.clone(), texture_atlases.add(texture_atlas));
     }
+    player_anim.anims.frame_times = player.frame_times.clone();
     player_anim.loaded = true;
     list.loaded_players = true;
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::{
+        animation::AnimationHandles,
+        entities::{
+            attack::AttackDef,
+            enemy::EnemyTypes,
+            weapon::{WeaponEntry, WeaponListAsset},
+        },
+        input::MenuRepeat,
+        interpolation::InterpolationPlugin,
+        pause::PausePlugin,
+        testing::{
+            advance, advance_secs, collide, enter_state, headless_app, press, release, TEST_ENEMY,
+        },
+    };
+
+    const WEAPONS: [&str; 2] = ["bow", "staff"];
+
+    fn stub_weapons() -> Weapons {
+        let mut weapons = Weapons::default();
+        for (i, name) in WEAPONS.iter().enumerate() {
+            weapons
+                .sprites
+                .insert(name.to_string(), Handle::weak_from_u128(i as u128 + 1));
+            weapons
+                .attacks
+                .insert(name.to_string(), AttackDef::default());
+            weapons.names.push(name.to_string());
+        }
+        weapons.equipped = WEAPONS[0].to_string();
+        weapons.loaded = true;
+        weapons
+    }
+
+    // a hero on the line with every weapon in WEAPONS to hand
+    fn attack_app() -> (App, Entity) {
+        let mut app = headless_app();
+        app.init_resource::<PlayerAttackTimer>()
+            .init_resource::<Heat>()
+            .init_resource::<Dash>()
+            .init_resource::<AttackPool>()
+            .init_resource::<InvertVertical>()
+            .init_resource::<AimAssist>()
+            .insert_resource(stub_weapons())
+            .add_systems(Update, (tick_attack_timer, handle_input).chain());
+        let player = app
+            .world
+            .spawn((Player, PlayerDirection::None, Transform::default()))
+            .id();
+        (app, player)
+    }
+
+    fn attacks(app: &mut App) -> Vec<(Entity, Handle<Image>)> {
+        app.world
+            .query_filtered::<(Entity, &Handle<Image>), With<PlayerAttack>>()
+            .iter(&app.world)
+            .map(|(entity, handle)| (entity, handle.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn attack_uses_the_equipped_weapons_sprite() {
+        for name in WEAPONS {
+            let (mut app, _) = attack_app();
+            app.world.resource_mut::<Weapons>().equipped = name.to_string();
+            press(&mut app, KeyCode::Space);
+            advance(&mut app, 1);
+
+            let sprite = app.world.resource::<Weapons>().sprites[name].clone();
+            let spawned = attacks(&mut app);
+            assert_eq!(spawned.len(), 1);
+            assert_eq!(spawned[0].1, sprite);
+        }
+    }
+
+    const WEAPON_LIST: &str = r#"{
+        "weapons": [
+            { "name": "frost", "sprite": "", "speed": 130.0, "pierce": 3,
+              "kind": { "type": "frost", "slow": 0.5, "duration": 2.0 } },
+            { "name": "slam", "sprite": "", "kind": { "type": "ground_slam", "radius": 40.0 } },
+            { "name": "spread", "sprite": "", "kind": { "type": "spread", "count": 3, "angle": 15.0 } },
+            { "name": "mystery", "sprite": "", "kind": { "type": "lightning" } }
+        ]
+    }"#;
+
+    // what the equipped weapon puts into the lane for one press of attack
+    fn fire(entry: &WeaponEntry) -> Vec<(f32, i32, bool, AttackKind)> {
+        let (mut app, _) = attack_app();
+        let mut weapons = app.world.resource_mut::<Weapons>();
+        weapons
+            .sprites
+            .insert(entry.name.clone(), Handle::default());
+        weapons.attacks.insert(
+            entry.name.clone(),
+            entry.attack.clone().validated(&entry.name),
+        );
+        weapons.equipped = entry.name.clone();
+        press(&mut app, KeyCode::Space);
+        advance(&mut app, 1);
+        app.world
+            .query::<(&PlayerAttack, &AttackEffect)>()
+            .iter(&app.world)
+            .map(|(attack, effect)| {
+                (
+                    attack.speed,
+                    attack.health,
+                    attack.lifetime.is_some(),
+                    effect.0.clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn weapon_list_builds_each_kind_of_attack() {
+        let list: WeaponListAsset = serde_json::from_str(WEAPON_LIST).unwrap();
+
+        let frost = fire(&list.weapons[0]);
+        assert_eq!(frost.len(), 1);
+        assert!(matches!(
+            frost[0],
+            (130.0, 3, false, AttackKind::Frost { slow, .. }) if slow == 0.5
+        ));
+
+        let slam = fire(&list.weapons[1]);
+        assert_eq!(slam.len(), 1);
+        assert!(matches!(
+            slam[0],
+            (0.0, _, true, AttackKind::GroundSlam { .. })
+        ));
+
+        let spread = fire(&list.weapons[2]);
+        assert_eq!(spread.len(), 3);
+        assert!(spread
+            .iter()
+            .all(|(.., kind)| matches!(kind, AttackKind::Spread { count: 3, .. })));
+
+        let mystery = fire(&list.weapons[3]);
+        assert_eq!(mystery.len(), 1);
+        assert!(matches!(mystery[0], (_, _, false, AttackKind::Piercing)));
+    }
+
+    // the starting max health and the pierce of a first shot for one run
+    fn start_run(glass_cannon: bool) -> (i32, i32) {
+        let (mut app, _) = attack_app();
+        app.world.resource_mut::<RunModifiers>().glass_cannon = glass_cannon;
+        app.world.run_system_once(reset_player_data);
+        press(&mut app, KeyCode::Space);
+        advance(&mut app, 1);
+        let pierce = app.world.query::<&PlayerAttack>().single(&app.world).health;
+        (app.world.resource::<PlayerData>().max_health, pierce)
+    }
+
+    #[test]
+    fn glass_cannon_lowers_max_health_and_raises_pierce() {
+        let (max_health, pierce) = start_run(false);
+        let (glass_health, glass_pierce) = start_run(true);
+        assert!(glass_health < max_health);
+        assert!(glass_pierce > pierce);
+    }
+
+    #[test]
+    fn spread_fires_a_volley_of_distinct_directions_on_one_cooldown() {
+        let (mut app, _) = attack_app();
+        let mut weapons = app.world.resource_mut::<Weapons>();
+        let equipped = weapons.equipped.clone();
+        weapons.attacks.get_mut(&equipped).unwrap().kind = AttackKind::Spread {
+            count: 5,
+            angle: 10.0,
+        };
+        press(&mut app, KeyCode::Space);
+        advance(&mut app, 1);
+
+        let volley: Vec<Vec2> = app
+            .world
+            .query::<&PlayerAttack>()
+            .iter(&app.world)
+            .map(|attack| Vec2::new(attack.speed, attack.vertical).normalize())
+            .collect();
+        assert_eq!(volley.len(), 5);
+        for (i, a) in volley.iter().enumerate() {
+            assert!(volley[i + 1..]
+                .iter()
+                .all(|b| a.angle_between(*b).abs() > 0.01));
+        }
+        // the volley shares a cooldown and a single shot's worth of heat
+        assert!(app.world.resource::<PlayerAttackTimer>().attacked);
+        let mut single = Heat::default();
+        single.add_shot();
+        assert_eq!(app.world.resource::<Heat>().value, single.value);
+    }
+
+    #[test]
+    fn firing_after_a_despawn_reuses_the_attack() {
+        let (mut app, _) = attack_app();
+        app.add_systems(Update, update_attack.before(handle_input));
+        press(&mut app, KeyCode::Space);
+        advance(&mut app, 1);
+        let (first, _) = attacks(&mut app)[0].clone();
+        release(&mut app, KeyCode::Space);
+        advance_secs(&mut app, 1.0);
+
+        // escapes the lane the same frame the next attack is fired
+        app.world.get_mut::<Transform>(first).unwrap().translation.x = 10000.0;
+        press(&mut app, KeyCode::Space);
+        advance(&mut app, 1);
+
+        let spawned = attacks(&mut app);
+        assert_eq!(spawned.len(), 1);
+        assert_eq!(spawned[0].0, first);
+        assert_eq!(
+            app.world.get::<Visibility>(first),
+            Some(&Visibility::Visible)
+        );
+    }
+
+    fn vertical_with_an_enemy_at(offset: Option<Vec2>) -> f32 {
+        let (mut app, _) = attack_app();
+        app.world.resource_mut::<AimAssist>().0 = true;
+        if let Some(offset) = offset {
+            let sign = app.world.resource::<PlayfieldOrientation>().sign();
+            let info = app.world.resource::<EnemyTypes>().types[TEST_ENEMY].clone();
+            app.world.spawn((
+                Enemy::new(TEST_ENEMY, &info),
+                Transform::from_xyz(offset.x * sign, offset.y, 0.0),
+                AnimationComponent::new(AnimState::Walking),
+            ));
+        }
+        press(&mut app, KeyCode::Space);
+        advance(&mut app, 1);
+        app.world
+            .query::<&PlayerAttack>()
+            .single(&app.world)
+            .vertical
+    }
+
+    #[test]
+    fn aim_assist_nudges_the_attack_towards_an_enemy_above() {
+        let vertical = vertical_with_an_enemy_at(Some(Vec2::new(200.0, 20.0)));
+        assert!(vertical > 0.0);
+        assert!(vertical <= AIM_ASSIST_MAX_VERTICAL);
+        // nothing ahead, or well off to the side, and it flies straight
+        assert_eq!(vertical_with_an_enemy_at(None), 0.0);
+        assert_eq!(vertical_with_an_enemy_at(Some(Vec2::new(50.0, 100.0))), 0.0);
+    }
+
+    // fires once, then taps again `early` seconds before the cooldown is up
+    fn tap_before_ready(early: f32) -> App {
+        let (mut app, _) = attack_app();
+        press(&mut app, KeyCode::Space);
+        advance(&mut app, 1);
+        release(&mut app, KeyCode::Space);
+        let cooldown = app.world.resource::<PlayerAttackTimer>().timer.duration();
+        advance_secs(&mut app, cooldown.as_secs_f32() - early);
+        press(&mut app, KeyCode::Space);
+        advance(&mut app, 1);
+        release(&mut app, KeyCode::Space);
+        advance_secs(&mut app, early + 0.05);
+        app
+    }
+
+    #[test]
+    fn a_press_just_before_the_cooldown_fires_once_ready() {
+        let app = tap_before_ready(0.1);
+        assert!(app.world.resource::<PlayerAttackTimer>().attacked);
+    }
+
+    #[test]
+    fn a_stale_buffered_press_never_fires() {
+        let app = tap_before_ready(0.4);
+        assert!(!app.world.resource::<PlayerAttackTimer>().attacked);
+    }
+
+    #[test]
+    fn overheating_blocks_attacks_until_cooled() {
+        let (mut app, _) = attack_app();
+        app.add_systems(Update, cool_heat);
+        app.world
+            .resource_mut::<PlayerAttackTimer>()
+            .timer
+            .set_duration(Duration::from_secs_f32(0.05));
+        // held down the whole time, so only the heat holds it back
+        press(&mut app, KeyCode::Space);
+        advance_secs(&mut app, 0.5);
+        assert!(app.world.resource::<Heat>().overheated);
+        let fired = attacks(&mut app).len();
+
+        advance_secs(&mut app, 1.0);
+        assert!(app.world.resource::<Heat>().overheated);
+        assert_eq!(attacks(&mut app).len(), fired);
+
+        let heat = app.world.resource::<Heat>();
+        let cooling = (heat.value - heat.unlock_below) / heat.cool_rate;
+        advance_secs(&mut app, cooling + 0.1);
+        assert!(attacks(&mut app).len() > fired);
+    }
+
+    #[test]
+    fn inverted_controls_send_up_down() {
+        let (mut app, player) = attack_app();
+        app.insert_resource(InvertVertical(true));
+        press(&mut app, KeyCode::W);
+        advance(&mut app, 1);
+
+        assert!(matches!(
+            app.world.get::<PlayerDirection>(player),
+            Some(PlayerDirection::Down)
+        ));
+    }
+
+    #[test]
+    fn facing_left_sends_attacks_left() {
+        let (mut app, _) = attack_app();
+        app.insert_resource(PlayfieldOrientation::FacingLeft)
+            .add_systems(Update, update_attack.after(handle_input));
+        press(&mut app, KeyCode::Space);
+        advance(&mut app, 1);
+        let (attack, _) = attacks(&mut app)[0].clone();
+        let start = app.world.get::<Transform>(attack).unwrap().translation.x;
+        advance(&mut app, 5);
+
+        assert!(start < 0.0);
+        assert!(app.world.get::<Transform>(attack).unwrap().translation.x < start);
+    }
+
+    #[test]
+    fn dash_moves_the_set_distance_while_invincible() {
+        let (mut app, player) = attack_app();
+        app.add_systems(Update, dash_player.after(handle_input));
+        app.world
+            .get_mut::<Transform>(player)
+            .unwrap()
+            .translation
+            .y = -200.0;
+        // the cooldown only reads as finished once it has been ticked
+        advance(&mut app, 1);
+        press(&mut app, KeyCode::W);
+        app.world
+            .resource_mut::<Input<KeyCode>>()
+            .press(KeyCode::ShiftLeft);
+        advance(&mut app, 1);
+        assert!(app.world.resource::<Dash>().active);
+        assert!(app.world.get::<Invincible>(player).is_some());
+
+        // the hero is stopped from walking so only the dash moves it
+        app.world.resource_mut::<Input<KeyCode>>().reset_all();
+        let duration = app.world.resource::<Dash>().timer.duration();
+        advance_secs(&mut app, duration.as_secs_f32() + 0.1);
+
+        let distance = app.world.resource::<Dash>().distance;
+        let y = app.world.get::<Transform>(player).unwrap().translation.y;
+        assert!((y - (-200.0 + distance)).abs() < 0.01);
+        assert!(!app.world.resource::<Dash>().active);
+        assert!(app.world.get::<Invincible>(player).is_none());
+    }
+
+    #[test]
+    fn attack_reflects_an_enemy_projectile() {
+        let mut app = headless_app();
+        app.init_resource::<AttackPool>()
+            .add_systems(Update, react_to_player_attack_collision);
+        let attack = app
+            .world
+            .spawn(PlayerAttack {
+                health: 1,
+                speed: 0.0,
+                vertical: 0.0,
+                lifetime: None,
+            })
+            .id();
+        let projectile = app
+            .world
+            .spawn((
+                EnemyProjectile::new(Vec2::new(-100.0, 0.0)),
+                CollisionGroups::new(GROUP_ENEMY, GROUP_PLAYER),
+            ))
+            .id();
+        collide(&mut app, attack, projectile);
+        advance(&mut app, 1);
+
+        let reflected = app.world.get::<EnemyProjectile>(projectile).unwrap();
+        assert!(reflected.reflected);
+        assert!(reflected.velocity.x > 100.0);
+        assert_eq!(reflected.velocity.y, 0.0);
+        let groups = app.world.get::<CollisionGroups>(projectile).unwrap();
+        assert_eq!(groups.memberships, GROUP_PLAYER);
+        assert_eq!(groups.filters, GROUP_ENEMY);
+        assert_eq!(app.world.get::<PlayerAttack>(attack).unwrap().health, 1);
+    }
+
+    fn timer_fractions(app: &App) -> (f32, f32) {
+        (
+            app.world.resource::<PlayerData>().timer.percent(),
+            app.world.resource::<PlayerAttackTimer>().timer.percent(),
+        )
+    }
+
+    #[test]
+    fn pausing_leaves_timers_where_they_were() {
+        let mut app = headless_app();
+        app.init_resource::<PlayerAttackTimer>()
+            .init_resource::<MenuRepeat>()
+            .add_plugins(PausePlugin)
+            .preserve_timers_on_pause::<PlayerData>()
+            .preserve_timers_on_pause::<PlayerAttackTimer>()
+            // ticked in every state, so only the stopped clock and the
+            // snapshot keep them still
+            .add_systems(
+                Update,
+                (
+                    tick_attack_timer,
+                    |time: Res<Time>, mut player_data: ResMut<PlayerData>| {
+                        player_data.timer.tick(time.delta());
+                    },
+                ),
+            );
+        app.world
+            .resource_mut::<PlayerData>()
+            .timer
+            .set_elapsed(Duration::from_secs_f32(0.5));
+        app.world
+            .resource_mut::<PlayerAttackTimer>()
+            .timer
+            .set_elapsed(Duration::from_secs_f32(0.2));
+        let before = timer_fractions(&app);
+
+        enter_state(&mut app, GameState::Pause);
+        advance_secs(&mut app, 3.0);
+        enter_state(&mut app, GameState::GamePlay);
+
+        assert_eq!(timer_fractions(&app), before);
+    }
+
+    fn stub_player_animation() -> PlayerAnimation {
+        let mut anims = AnimationHandles::default();
+        for state in [
+            AnimState::Idle,
+            AnimState::Walking,
+            AnimState::Hurting,
+            AnimState::Dying,
+        ] {
+            anims.add_handle(state.to_string(), Handle::default());
+        }
+        PlayerAnimation {
+            loaded: true,
+            anims,
+        }
+    }
+
+    // a hero on the line that DamagePlayer events land on
+    fn damage_app() -> (App, Entity) {
+        let mut app = headless_app();
+        app.init_resource::<Shield>()
+            .init_resource::<Dash>()
+            .init_resource::<PlayerKnockback>()
+            .add_event::<DamageEnemy>()
+            .insert_resource(stub_player_animation())
+            .add_systems(Update, (apply_player_damage, tick_death_sequence).chain());
+        let player = app
+            .world
+            .spawn((
+                Player,
+                Transform::default(),
+                Handle::<TextureAtlas>::default(),
+                TextureAtlasSprite::default(),
+                AnimationComponent::new(AnimState::Idle),
+                Interpolated::new(Vec3::ZERO),
+            ))
+            .id();
+        (app, player)
+    }
+
+    fn state(app: &App) -> GameState {
+        *app.world.resource::<State<GameState>>().get()
+    }
+
+    #[test]
+    fn shield_takes_the_hit_instead_of_health() {
+        let (mut app, player) = damage_app();
+        app.world.resource_mut::<Shield>().charges = 2;
+        let health = app.world.resource::<PlayerData>().health;
+        app.world.send_event(DamagePlayer {
+            amount: 1,
+            from_y: None,
+        });
+        advance(&mut app, 1);
+        assert_eq!(app.world.resource::<Shield>().charges, 1);
+        assert_eq!(app.world.resource::<PlayerData>().health, health);
+
+        // nothing lands during i-frames, so no charge is spent either
+        app.world.entity_mut(player).insert(Invincible);
+        app.world.send_event(DamagePlayer {
+            amount: 1,
+            from_y: None,
+        });
+        advance(&mut app, 1);
+        assert_eq!(app.world.resource::<Shield>().charges, 1);
+        assert_eq!(app.world.resource::<PlayerData>().health, health);
+    }
+
+    #[test]
+    fn a_hit_flashes_the_hero_red_then_back_to_white() {
+        let (mut app, player) = damage_app();
+        app.add_systems(Update, fade_hit_flash.after(apply_player_damage));
+        let color = |app: &App| app.world.get::<TextureAtlasSprite>(player).unwrap().color;
+
+        app.world.send_event(DamagePlayer {
+            amount: 1,
+            from_y: None,
+        });
+        // the flash lands with the hit's commands and starts fading next frame
+        advance(&mut app, 2);
+        let flashed = color(&app);
+        assert_eq!(flashed.r(), 1.0);
+        assert!(flashed.g() < 0.2 && flashed.b() < 0.2);
+
+        advance_secs(&mut app, HIT_FLASH_SECONDS / 2.0);
+        assert!(color(&app).g() > flashed.g());
+        advance_secs(&mut app, HIT_FLASH_SECONDS / 2.0);
+        assert_eq!(color(&app), Color::WHITE);
+        assert!(app.world.get::<PlayerHitFlash>(player).is_none());
+    }
+
+    // a contact hit from above, with the hero free to be shoved about
+    fn knockback_app(shielded: bool) -> (App, Entity) {
+        let (mut app, player) = damage_app();
+        app.add_systems(Update, move_player.after(apply_player_damage));
+        app.world.entity_mut(player).insert(PlayerDirection::None);
+        if shielded {
+            app.world.resource_mut::<Shield>().charges = 1;
+        }
+        app.world.send_event(DamagePlayer {
+            amount: 1,
+            from_y: Some(20.0),
+        });
+        (app, player)
+    }
+
+    fn height(app: &App, player: Entity) -> f32 {
+        app.world.get::<Transform>(player).unwrap().translation.y
+    }
+
+    #[test]
+    fn a_landed_hit_shoves_the_hero_away_then_settles() {
+        let (mut app, player) = knockback_app(false);
+        advance(&mut app, 1);
+        let first = height(&app, player);
+        assert!(first < 0.0);
+        let speed = app.world.resource::<PlayerKnockback>().current();
+        advance(&mut app, 1);
+        let second = height(&app, player);
+        assert!(second < first);
+        assert!(app.world.resource::<PlayerKnockback>().current().abs() < speed.abs());
+
+        advance_secs(&mut app, KNOCKBACK_SECONDS);
+        let settled = height(&app, player);
+        advance(&mut app, 3);
+        assert_eq!(app.world.resource::<PlayerKnockback>().current(), 0.0);
+        assert_eq!(height(&app, player), settled);
+    }
+
+    #[test]
+    fn a_blocked_hit_does_not_shove_the_hero() {
+        let (mut app, player) = knockback_app(true);
+        advance(&mut app, 2);
+        assert_eq!(height(&app, player), 0.0);
+
+        app.world.entity_mut(player).insert(Invincible);
+        app.world.send_event(DamagePlayer {
+            amount: 1,
+            from_y: Some(20.0),
+        });
+        advance(&mut app, 2);
+        assert_eq!(height(&app, player), 0.0);
+    }
+
+    // how far the hero moves up in one frame, with or without focus held
+    fn step_up(focus: bool) -> f32 {
+        let mut app = headless_app();
+        app.init_resource::<Dash>()
+            .init_resource::<PlayerKnockback>()
+            .insert_resource(stub_player_animation())
+            .add_systems(Update, move_player);
+        let player = app
+            .world
+            .spawn((Player, PlayerDirection::Up, Transform::default()))
+            .id();
+        if focus {
+            press(&mut app, KeyCode::ControlLeft);
+        }
+        advance(&mut app, 1);
+        app.world.get::<Transform>(player).unwrap().translation.y
+    }
+
+    #[test]
+    fn holding_focus_slows_the_hero() {
+        let (full, focused) = (step_up(false), step_up(true));
+        assert!(full > 0.0);
+        assert!((focused - full * FOCUS_SPEED_MULT).abs() < 1e-4);
+    }
+
+    #[test]
+    fn dying_with_lives_left_respawns_at_full_health() {
+        let (mut app, player) = damage_app();
+        app.add_plugins(InterpolationPlugin);
+        let lives = app.world.resource::<PlayerData>().lives;
+        assert!(lives > 1);
+        let spawn_x = {
+            let line = app.world.resource::<DefenseLine>();
+            line.player_x(*app.world.resource::<PlayfieldOrientation>())
+        };
+        let died_at = Vec3::new(spawn_x + 40.0, 30.0, 0.0);
+        app.world.get_mut::<Transform>(player).unwrap().translation = died_at;
+        app.world
+            .entity_mut(player)
+            .insert(Interpolated::new(died_at));
+
+        app.world.send_event(DamagePlayer {
+            amount: 100,
+            from_y: None,
+        });
+        // checked on the respawn frame itself, where a stale blend would show
+        advance(&mut app, 1);
+
+        let data = app.world.resource::<PlayerData>();
+        assert_eq!(data.lives, lives - 1);
+        assert_eq!(data.health, data.max_health);
+        assert!(!app.world.resource::<DeathSequence>().active);
+        let translation = app.world.get::<Transform>(player).unwrap().translation;
+        assert_eq!(translation.truncate(), Vec2::new(spawn_x, 0.0));
+        assert!(app.world.get::<Invincible>(player).is_some());
+    }
+
+    #[test]
+    fn death_freezes_play_before_game_over() {
+        let (mut app, _) = damage_app();
+        app.world.resource_mut::<PlayerData>().lives = 1;
+        app.world.send_event(DamagePlayer {
+            amount: 10,
+            from_y: None,
+        });
+        advance(&mut app, 1);
+        assert!(app.world.resource::<DeathSequence>().active);
+        assert_eq!(
+            app.world.resource::<Time<Virtual>>().relative_speed(),
+            DEATH_TIME_SCALE
+        );
+
+        let freeze = app.world.resource::<DeathSequence>().timer.duration();
+        advance_secs(&mut app, freeze.as_secs_f32() - 0.1);
+        assert_eq!(state(&app), GameState::GamePlay);
+        advance_secs(&mut app, 0.2);
+        assert_eq!(state(&app), GameState::GameOver);
+        assert_eq!(app.world.resource::<Time<Virtual>>().relative_speed(), 1.0);
+    }
+}
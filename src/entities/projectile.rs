@@ -0,0 +1,240 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    animation::{AnimState, AnimationComponent, RangedEntry},
+    data::{playfield::PlayfieldOrientation, state::GameState},
+    entities::{
+        collision::{SensorCollisions, GROUP_ENEMY, GROUP_PLAYER},
+        enemy::Enemy,
+        player::{death_in_progress, DamagePlayer, Player},
+    },
+    pause::RestartSet,
+};
+
+const PROJECTILE_RADIUS: f32 = 3.0;
+const PROJECTILE_LIFETIME: f32 = 4.0;
+// a parry sends it back faster than it came
+const REFLECT_SPEED_MULT: f32 = 2.0;
+const PROJECTILE_COLOR: Color = Color::rgb(1.0, 0.5, 0.1);
+const REFLECTED_COLOR: Color = Color::rgb(0.4, 0.8, 1.0);
+
+pub struct ProjectilePlugin;
+
+// fires down the lane every `interval` seconds while walking
+#[derive(Component)]
+pub struct Ranged {
+    timer: Timer,
+    speed: f32,
+}
+
+impl Ranged {
+    pub fn new(entry: RangedEntry) -> Self {
+        Self {
+            timer: Timer::from_seconds(entry.interval, TimerMode::Repeating),
+            speed: entry.speed,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct EnemyProjectile {
+    pub velocity: Vec2,
+    pub reflected: bool,
+    lifetime: Timer,
+}
+
+impl EnemyProjectile {
+    pub fn new(velocity: Vec2) -> Self {
+        Self {
+            velocity,
+            reflected: false,
+            lifetime: Timer::from_seconds(PROJECTILE_LIFETIME, TimerMode::Once),
+        }
+    }
+
+    // turned round and handed to the hero's side, from then on it can only
+    // hit enemies
+    pub fn reflect(&mut self, groups: &mut CollisionGroups) {
+        if self.reflected {
+            return;
+        }
+        self.velocity *= -REFLECT_SPEED_MULT;
+        self.reflected = true;
+        self.lifetime.reset();
+        *groups = CollisionGroups::new(GROUP_PLAYER, GROUP_ENEMY);
+    }
+}
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (fire_projectiles, move_projectiles, projectile_hits)
+                .run_if(in_state(GameState::GamePlay).and_then(not(death_in_progress))),
+        )
+        .add_systems(
+            OnEnter(GameState::GamePlay),
+            despawn_projectiles.in_set(RestartSet),
+        );
+    }
+}
+
+fn fire_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    orientation: Res<PlayfieldOrientation>,
+    mut shooters: Query<(&mut Ranged, &Transform, &AnimationComponent), With<Enemy>>,
+) {
+    for (mut ranged, transform, anim) in &mut shooters {
+        if anim.state != AnimState::Walking || !ranged.timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: PROJECTILE_COLOR,
+                    custom_size: Some(Vec2::splat(PROJECTILE_RADIUS * 2.0)),
+                    ..default()
+                },
+                transform: Transform::from_translation(transform.translation),
+                ..default()
+            },
+            EnemyProjectile::new(Vec2::new(-ranged.speed * orientation.sign(), 0.0)),
+            RigidBody::KinematicPositionBased,
+            Collider::ball(PROJECTILE_RADIUS),
+            Sensor,
+            ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_KINEMATIC,
+            ActiveEvents::COLLISION_EVENTS,
+            CollisionGroups::new(GROUP_ENEMY, GROUP_PLAYER),
+        ));
+    }
+}
+
+fn move_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut projectiles: Query<(Entity, &mut EnemyProjectile, &mut Transform, &mut Sprite)>,
+) {
+    for (entity, mut projectile, mut transform, mut sprite) in &mut projectiles {
+        if projectile.lifetime.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation += (projectile.velocity * time.delta_seconds()).extend(0.0);
+        sprite.color = if projectile.reflected {
+            REFLECTED_COLOR
+        } else {
+            PROJECTILE_COLOR
+        };
+    }
+}
+
+// the enemy end of a reflected hit is damaged by react_to_collision like any
+// other player side hit, this only settles the projectile and the hero
+fn projectile_hits(
+    mut commands: Commands,
+    mut collisions: SensorCollisions,
+    mut damage: EventWriter<DamagePlayer>,
+    projectiles: Query<&EnemyProjectile>,
+    player: Query<(), With<Player>>,
+    enemies: Query<(), With<Enemy>>,
+) {
+    let mut spent = Vec::new();
+    for (a, b) in collisions.started() {
+        let (entity, other) = if projectiles.contains(a) {
+            (a, b)
+        } else if projectiles.contains(b) {
+            (b, a)
+        } else {
+            continue;
+        };
+        if spent.contains(&entity) {
+            continue;
+        }
+        let projectile = projectiles.get(entity).unwrap();
+        if projectile.reflected {
+            if !enemies.contains(other) {
+                continue;
+            }
+        } else if player.contains(other) {
+            damage.send(DamagePlayer {
+                amount: 1,
+                from_y: None,
+            });
+        } else {
+            continue;
+        }
+        commands.entity(entity).despawn();
+        spent.push(entity);
+    }
+}
+
+fn despawn_projectiles(mut commands: Commands, projectiles: Query<Entity, With<EnemyProjectile>>) {
+    for entity in &projectiles {
+        commands.entity(entity).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{advance, collide, headless_app};
+
+    fn projectile_app() -> App {
+        let mut app = headless_app();
+        app.add_systems(Update, projectile_hits);
+        app
+    }
+
+    fn spawn_projectile(app: &mut App, reflected: bool) -> Entity {
+        let mut projectile = EnemyProjectile::new(Vec2::new(-100.0, 0.0));
+        let mut groups = CollisionGroups::new(GROUP_ENEMY, GROUP_PLAYER);
+        if reflected {
+            projectile.reflect(&mut groups);
+        }
+        app.world.spawn((projectile, groups)).id()
+    }
+
+    fn damage_sent(app: &App) -> usize {
+        let events = app.world.resource::<Events<DamagePlayer>>();
+        events.get_reader().read(events).count()
+    }
+
+    #[test]
+    fn projectile_hurts_the_hero_and_is_spent() {
+        let mut app = projectile_app();
+        let player = app.world.spawn(Player).id();
+        let projectile = spawn_projectile(&mut app, false);
+        collide(&mut app, projectile, player);
+        advance(&mut app, 1);
+
+        assert_eq!(damage_sent(&app), 1);
+        assert!(app.world.get_entity(projectile).is_none());
+    }
+
+    #[test]
+    fn reflected_projectile_never_hurts_the_hero() {
+        let mut app = projectile_app();
+        let player = app.world.spawn(Player).id();
+        let projectile = spawn_projectile(&mut app, true);
+        collide(&mut app, projectile, player);
+        advance(&mut app, 1);
+
+        assert_eq!(damage_sent(&app), 0);
+        assert!(app.world.get_entity(projectile).is_some());
+    }
+
+    #[test]
+    fn reflecting_twice_changes_nothing() {
+        let mut projectile = EnemyProjectile::new(Vec2::new(-100.0, 0.0));
+        let mut groups = CollisionGroups::new(GROUP_ENEMY, GROUP_PLAYER);
+        projectile.reflect(&mut groups);
+        projectile.reflect(&mut groups);
+
+        assert_eq!(
+            projectile.velocity,
+            Vec2::new(100.0 * REFLECT_SPEED_MULT, 0.0)
+        );
+    }
+}
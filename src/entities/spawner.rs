@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+// the "tick a timer, act when it goes off" loop behind anything that shows up
+// on a schedule. works as a component for per-entity emitters or as a field
+// of a resource for global ones
+#[derive(Component)]
+pub struct IntervalSpawner {
+    pub timer: Timer,
+}
+
+impl IntervalSpawner {
+    pub fn new(seconds: f32) -> Self {
+        Self {
+            timer: Timer::new(Duration::from_secs_f32(seconds), TimerMode::Repeating),
+        }
+    }
+
+    // safe to call every frame, progress towards the next firing is kept
+    // unless the interval actually changes
+    pub fn set_interval(&mut self, seconds: f32) {
+        let interval = Duration::from_secs_f32(seconds);
+        if self.timer.duration() != interval {
+            self.timer.set_duration(interval);
+        }
+    }
+
+    // how many times it went off, more than once if the frame was longer
+    // than the interval
+    pub fn tick(&mut self, delta: Duration) -> u32 {
+        self.timer.tick(delta).times_finished_this_tick()
+    }
+
+    pub fn reset(&mut self) {
+        self.timer.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{advance, headless_app};
+
+    #[derive(Resource, Default)]
+    struct Fired(Vec<u32>);
+
+    fn fire(time: Res<Time>, mut fired: ResMut<Fired>, mut spawners: Query<&mut IntervalSpawner>) {
+        for mut spawner in &mut spawners {
+            fired.0.push(spawner.tick(time.delta()));
+        }
+    }
+
+    fn fired_over(app: &mut App, frames: usize) -> Vec<u32> {
+        app.world.resource_mut::<Fired>().0.clear();
+        advance(app, frames);
+        std::mem::take(&mut app.world.resource_mut::<Fired>().0)
+    }
+
+    #[test]
+    fn fires_once_per_interval_and_follows_a_new_interval() {
+        let mut app = headless_app();
+        app.init_resource::<Fired>().add_systems(Update, fire);
+        let spawner = app.world.spawn(IntervalSpawner::new(0.5)).id();
+
+        // a little over two seconds at 60 frames a second
+        let fired = fired_over(&mut app, 125);
+        assert!(fired.iter().all(|times| *times <= 1));
+        assert_eq!(fired.iter().sum::<u32>(), 4);
+
+        app.world
+            .get_mut::<IntervalSpawner>(spawner)
+            .unwrap()
+            .set_interval(0.25);
+        let fired = fired_over(&mut app, 120);
+        assert!(fired.iter().all(|times| *times <= 1));
+        assert_eq!(fired.iter().sum::<u32>(), 8);
+    }
+}
@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+
+use crate::{
+    data::{mode::GameMode, state::GameState},
+    entities::player::death_in_progress,
+    pause::RestartSet,
+};
+
+pub const TIME_ATTACK_SECONDS: f32 = 60.0;
+
+pub struct TimeAttackPlugin;
+
+#[derive(Resource, Default)]
+pub struct TimeAttack {
+    pub remaining: f32,
+}
+
+impl Plugin for TimeAttackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TimeAttack>()
+            .add_systems(OnEnter(GameState::TransitionToGamePlay), start_countdown)
+            .add_systems(
+                OnEnter(GameState::GamePlay),
+                start_countdown.in_set(RestartSet),
+            )
+            .add_systems(
+                Update,
+                tick_countdown.run_if(
+                    in_state(GameState::GamePlay)
+                        .and_then(time_attack_enabled)
+                        .and_then(not(death_in_progress)),
+                ),
+            );
+    }
+}
+
+pub fn time_attack_enabled(mode: Res<GameMode>) -> bool {
+    mode.time_attack.is_some()
+}
+
+fn start_countdown(mode: Res<GameMode>, mut time_attack: ResMut<TimeAttack>) {
+    time_attack.remaining = mode.time_attack.unwrap_or_default();
+}
+
+// only ticks in GamePlay, so the Pause state holds the clock
+fn tick_countdown(
+    time: Res<Time>,
+    mut time_attack: ResMut<TimeAttack>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    time_attack.remaining = (time_attack.remaining - time.delta_seconds()).max(0.0);
+    if time_attack.remaining <= 0.0 {
+        next_state.set(GameState::GameOver);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        menu::MenuItem,
+        testing::{advance_secs, enter_state, headless_app},
+    };
+
+    fn state(app: &App) -> GameState {
+        *app.world.resource::<State<GameState>>().get()
+    }
+
+    fn remaining(app: &App) -> f32 {
+        app.world.resource::<TimeAttack>().remaining
+    }
+
+    #[test]
+    fn countdown_runs_in_gameplay_holds_in_pause_and_ends_the_run() {
+        let mut app = headless_app();
+        app.insert_resource(MenuItem::TimeAttack.mode())
+            .add_plugins(TimeAttackPlugin);
+        app.world.resource_mut::<TimeAttack>().remaining = 1.0;
+
+        advance_secs(&mut app, 0.5);
+        let left = remaining(&app);
+        assert!(left < 1.0 && left > 0.0);
+
+        enter_state(&mut app, GameState::Pause);
+        advance_secs(&mut app, 2.0);
+        assert_eq!(remaining(&app), left);
+        assert_eq!(state(&app), GameState::Pause);
+
+        enter_state(&mut app, GameState::GamePlay);
+        advance_secs(&mut app, left + 0.1);
+        assert_eq!(remaining(&app), 0.0);
+        assert_eq!(state(&app), GameState::GameOver);
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::{persist, state::GameState},
+    entities::{
+        enemy::{EnemyInfo, EnemyTypes},
+        player::GameStats,
+    },
+};
+
+const UNLOCKS_FILE: &str = "unlocks.json";
+
+pub struct UnlockPlugin;
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Unlocks {
+    pub enemies: HashSet<String>,
+}
+
+impl Unlocks {
+    pub fn is_unlocked(&self, name: &str, info: &EnemyInfo) -> bool {
+        info.unlock_score.is_none() || self.enemies.contains(name)
+    }
+}
+
+impl Plugin for UnlockPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(persist::load::<Unlocks>(UNLOCKS_FILE))
+            .add_systems(
+                Update,
+                unlock_milestones.run_if(in_state(GameState::GamePlay)),
+            );
+    }
+}
+
+fn unlock_milestones(
+    stats: Res<GameStats>,
+    enemy_types: Res<EnemyTypes>,
+    mut unlocks: ResMut<Unlocks>,
+) {
+    if !stats.is_changed() {
+        return;
+    }
+    let mut unlocked = false;
+    for (name, info) in enemy_types.types.iter() {
+        let Some(score) = info.unlock_score else {
+            continue;
+        };
+        if stats.villagers_saved >= score && !unlocks.enemies.contains(name) {
+            info!("unlocked enemy `{}`", name);
+            unlocks.enemies.insert(name.clone());
+            unlocked = true;
+        }
+    }
+    if unlocked {
+        persist::save(UNLOCKS_FILE, &*unlocks);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::rng::GameRng,
+        testing::{advance, headless_app, TEST_ENEMY},
+    };
+
+    const GATED: &str = "gated";
+
+    fn draws(app: &mut App) -> Vec<String> {
+        let world = app.world.cell();
+        let types = world.resource::<EnemyTypes>();
+        let unlocks = world.resource::<Unlocks>();
+        let mut rng = world.resource_mut::<GameRng>();
+        (0..50)
+            .filter_map(|_| types.choose(&unlocks, &default(), rng.rng()).cloned())
+            .collect()
+    }
+
+    #[test]
+    fn gated_enemy_only_spawns_once_unlocked() {
+        let mut app = headless_app();
+        app.add_systems(Update, unlock_milestones);
+        let mut types = app.world.resource_mut::<EnemyTypes>();
+        let mut gated = types.types[TEST_ENEMY].clone();
+        gated.unlock_score = Some(5);
+        gated.weight = 100.0;
+        types.types.insert(GATED.to_string(), gated);
+        advance(&mut app, 1);
+        assert!(!draws(&mut app).iter().any(|name| name == GATED));
+
+        app.world.resource_mut::<GameStats>().villagers_saved = 5;
+        advance(&mut app, 1);
+        assert!(draws(&mut app).iter().any(|name| name == GATED));
+        let saved = persist::load::<Unlocks>(UNLOCKS_FILE);
+        assert!(saved.enemies.contains(GATED));
+    }
+}
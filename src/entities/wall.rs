@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+
+use crate::{
+    data::{
+        playfield::{DefenseLine, PlayfieldBounds, PlayfieldOrientation},
+        state::GameState,
+    },
+    pause::RestartSet,
+};
+
+const WALL_WIDTH: f32 = 12.0;
+const WALL_COLOR: Color = Color::rgb(0.55, 0.45, 0.35);
+const DAMAGE_STATES: i32 = 3;
+
+pub struct WallPlugin;
+
+#[derive(Resource)]
+pub struct Wall {
+    pub hp: i32,
+    pub max_hp: i32,
+}
+
+impl Default for Wall {
+    fn default() -> Self {
+        Self { hp: 20, max_hp: 20 }
+    }
+}
+
+impl Wall {
+    pub fn damage(&mut self, amount: i32) {
+        self.hp = (self.hp - amount).max(0);
+    }
+
+    pub fn is_breached(&self) -> bool {
+        self.hp <= 0
+    }
+
+    // 0 while intact, up to DAMAGE_STATES as it crumbles
+    pub fn damage_state(&self) -> i32 {
+        let lost = (self.max_hp - self.hp).max(0) as f32 / self.max_hp.max(1) as f32;
+        (lost * DAMAGE_STATES as f32).ceil() as i32
+    }
+}
+
+#[derive(Component)]
+struct WallSprite;
+
+impl Plugin for WallPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Wall>()
+            .add_systems(Startup, spawn_wall)
+            .add_systems(OnEnter(GameState::TransitionToGamePlay), reset_wall)
+            .add_systems(OnEnter(GameState::GamePlay), reset_wall.in_set(RestartSet))
+            .add_systems(
+                Update,
+                (update_wall, draw_cracks).run_if(in_state(GameState::GamePlay)),
+            )
+            .add_systems(Update, move_wall.run_if(resource_changed::<DefenseLine>()));
+    }
+}
+
+fn wall_x(line: &DefenseLine, orientation: PlayfieldOrientation) -> f32 {
+    line.x - WALL_WIDTH * orientation.sign()
+}
+
+fn spawn_wall(
+    mut commands: Commands,
+    line: Res<DefenseLine>,
+    bounds: Res<PlayfieldBounds>,
+    orientation: Res<PlayfieldOrientation>,
+) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: WALL_COLOR,
+                custom_size: Some(Vec2::new(WALL_WIDTH, bounds.max_y - bounds.min_y)),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(
+                wall_x(&line, *orientation),
+                (bounds.min_y + bounds.max_y) / 2.0,
+                -0.5,
+            )),
+            ..default()
+        },
+        WallSprite,
+    ));
+}
+
+fn move_wall(
+    line: Res<DefenseLine>,
+    orientation: Res<PlayfieldOrientation>,
+    mut sprites: Query<&mut Transform, With<WallSprite>>,
+) {
+    for mut transform in &mut sprites {
+        transform.translation.x = wall_x(&line, *orientation);
+    }
+}
+
+fn reset_wall(mut wall: ResMut<Wall>) {
+    *wall = Wall::default();
+}
+
+fn update_wall(wall: Res<Wall>, mut sprites: Query<&mut Sprite, With<WallSprite>>) {
+    if !wall.is_changed() {
+        return;
+    }
+    let shade = 1.0 - 0.2 * wall.damage_state() as f32;
+    for mut sprite in &mut sprites {
+        sprite.color = Color::rgb(
+            WALL_COLOR.r() * shade,
+            WALL_COLOR.g() * shade,
+            WALL_COLOR.b() * shade,
+        );
+    }
+}
+
+fn draw_cracks(
+    mut gizmos: Gizmos,
+    wall: Res<Wall>,
+    line: Res<DefenseLine>,
+    bounds: Res<PlayfieldBounds>,
+    orientation: Res<PlayfieldOrientation>,
+) {
+    let x = wall_x(&line, *orientation);
+    let height = bounds.max_y - bounds.min_y;
+    for crack in 0..wall.damage_state() {
+        let y = bounds.min_y + height * (crack as f32 + 0.5) / DAMAGE_STATES as f32;
+        let half = WALL_WIDTH / 2.0;
+        gizmos.line_2d(Vec2::new(x - half, y + 8.0), Vec2::new(x, y), Color::BLACK);
+        gizmos.line_2d(Vec2::new(x, y), Vec2::new(x + half, y - 10.0), Color::BLACK);
+    }
+}
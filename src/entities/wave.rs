@@ -0,0 +1,739 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::{prelude::*, rngs::StdRng};
+
+use crate::{
+    animation::EnemyAnimations,
+    data::{
+        localization::Localization,
+        mode::waves_enabled,
+        playfield::{PlayfieldBounds, PlayfieldOrientation},
+        rng::GameRng,
+        settings::{Difficulty, Endless},
+        state::GameState,
+        theme::Theme,
+    },
+    effects::spawn_fading_text,
+    entities::{
+        boss::boss_intro_active,
+        enemy::{
+            live_counts, manual_spawn, spawn_named_enemy, spawning_enabled, Enemy, EnemyInfo,
+            EnemySet, EnemySpawnData, EnemyTypes, SpawnConfig,
+        },
+        player::{death_in_progress, ElapsedGameplay, GameStats},
+        unlock::Unlocks,
+    },
+    pause::{PausableTimers, PauseAppExt},
+    GameplayStart,
+};
+
+const ENDLESS_GROWTH: f32 = 0.25;
+const FORMATION_SPACING: f32 = 30.0;
+// odd, so every (wave, difficulty) pair lands on a different roster seed
+const ROSTER_SEED_MIX: u64 = 0x9e37_79b9_7f4a_7c15;
+const PERFECT_WAVE_BONUS: i32 = 10;
+const PERFECT_OFFSET: Vec2 = Vec2::new(0.0, 150.0);
+const PERFECT_COLOR: Color = Color::rgb(0.4, 1.0, 0.4);
+
+pub struct WavePlugin;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WaveKind {
+    Normal {
+        count: usize,
+    },
+    Swarm {
+        count: usize,
+        interval: f32,
+        speed_mult: f32,
+    },
+}
+
+impl WaveKind {
+    pub fn count(&self) -> usize {
+        match self {
+            WaveKind::Normal { count } | WaveKind::Swarm { count, .. } => *count,
+        }
+    }
+
+    pub fn interval(&self, config: &SpawnConfig, elapsed: f32) -> f32 {
+        match self {
+            WaveKind::Normal { .. } => config.interval_at(elapsed),
+            WaveKind::Swarm { interval, .. } => *interval,
+        }
+    }
+
+    pub fn apply(&self, enemy: &mut Enemy) {
+        if let WaveKind::Swarm { speed_mult, .. } = self {
+            enemy.speed *= speed_mult;
+            enemy.health = 1;
+        }
+    }
+}
+
+// how an opening burst is laid out. it only decides where each member
+// starts, after that they walk in like any other enemy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Formation {
+    // side by side down the lane
+    #[default]
+    Column,
+    // one behind the other along the middle of the lane
+    Line,
+    // a chevron with its point nearest the line
+    V,
+}
+
+impl Formation {
+    // (depth behind the spawn edge, y) for each of `count` members
+    pub fn placements(&self, count: usize, bounds: &PlayfieldBounds) -> Vec<Vec2> {
+        let middle = (count.max(1) - 1) as f32 / 2.0;
+        (0..count)
+            .map(|i| match self {
+                Formation::Column => Vec2::new(0.0, bounds.lane_slot(i, count)),
+                Formation::Line => Vec2::new(
+                    FORMATION_SPACING * i as f32,
+                    (bounds.min_y + bounds.max_y) / 2.0,
+                ),
+                Formation::V => Vec2::new(
+                    FORMATION_SPACING * (i as f32 - middle).abs(),
+                    bounds.lane_slot(i, count),
+                ),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Wave {
+    pub kind: WaveKind,
+    // spawned all at once across the lane when the wave starts, and counted
+    // as part of the wave's total
+    pub opening_burst: usize,
+    pub formation: Formation,
+    // enemy type that closes out the wave after a short intro
+    pub boss: Option<&'static str>,
+}
+
+impl Wave {
+    pub fn new(kind: WaveKind) -> Self {
+        Self {
+            kind,
+            opening_burst: 0,
+            formation: Formation::default(),
+            boss: None,
+        }
+    }
+
+    pub fn with_burst(mut self, opening_burst: usize) -> Self {
+        self.opening_burst = opening_burst;
+        self
+    }
+
+    pub fn with_formation(mut self, formation: Formation) -> Self {
+        self.formation = formation;
+        self
+    }
+
+    pub fn with_boss(mut self, boss: &'static str) -> Self {
+        self.boss = Some(boss);
+        self
+    }
+}
+
+#[derive(Resource)]
+pub struct WaveState {
+    pub waves: Vec<Wave>,
+    pub authored: usize,
+    pub current: usize,
+    pub spawned: usize,
+    pub timer: Timer,
+    pub break_timer: Timer,
+    pub completed: bool,
+    // cleared when a wave starts, set whenever a hit gets through
+    pub took_damage_this_wave: bool,
+    // what the current wave still has to send, drawn up when it starts
+    pub roster: Vec<String>,
+    pub roster_wave: Option<usize>,
+}
+
+// sent as each wave is cleared, `perfect` when the hero was never hurt in it
+#[derive(Event)]
+pub struct WaveCleared {
+    pub perfect: bool,
+}
+
+impl WaveState {
+    pub fn wave(&self) -> Option<Wave> {
+        self.waves.get(self.current).copied()
+    }
+
+    pub fn remaining_to_spawn(&self) -> usize {
+        self.wave()
+            .map_or(0, |wave| wave.kind.count().saturating_sub(self.spawned))
+    }
+
+    // how many waves past the authored ones the run has got to
+    pub fn endless_level(&self) -> usize {
+        (self.current + 1).saturating_sub(self.authored)
+    }
+
+    // endless waves alternate like the authored ones but grow with every
+    // round, both in size and in how hard each enemy is to put down
+    pub fn extend_endless(&mut self) {
+        // cycles the last two authored waves, normally a normal and a swarm
+        let cycle = self.authored.clamp(1, 2);
+        while self.current >= self.waves.len() {
+            let level = self.waves.len() + 1 - self.authored;
+            let growth = 1.0 + ENDLESS_GROWTH * level as f32;
+            let template = self.authored.saturating_sub(cycle) + (level - 1) % cycle;
+            let Some(template) = self.waves.get(template).copied() else {
+                return;
+            };
+            let kind = match template.kind {
+                WaveKind::Normal { count } => WaveKind::Normal {
+                    count: (count as f32 * growth) as usize,
+                },
+                WaveKind::Swarm {
+                    count,
+                    interval,
+                    speed_mult,
+                } => WaveKind::Swarm {
+                    count: (count as f32 * growth) as usize,
+                    interval,
+                    speed_mult,
+                },
+            };
+            self.waves.push(Wave { kind, ..template });
+        }
+    }
+}
+
+impl PausableTimers for WaveState {
+    fn timers_mut(&mut self) -> Vec<&mut Timer> {
+        vec![&mut self.timer, &mut self.break_timer]
+    }
+}
+
+impl Default for WaveState {
+    fn default() -> Self {
+        let waves = vec![
+            Wave::new(WaveKind::Normal { count: 20 }),
+            Wave::new(WaveKind::Swarm {
+                count: 30,
+                interval: 0.05,
+                speed_mult: 1.8,
+            }),
+            Wave::new(WaveKind::Normal { count: 40 })
+                .with_burst(4)
+                .with_formation(Formation::V),
+            Wave::new(WaveKind::Swarm {
+                count: 60,
+                interval: 0.04,
+                speed_mult: 2.0,
+            })
+            .with_burst(6)
+            .with_formation(Formation::Line)
+            .with_boss("devil"),
+        ];
+        Self {
+            authored: waves.len(),
+            waves,
+            current: 0,
+            spawned: 0,
+            timer: Timer::new(Duration::from_secs_f32(0.1), TimerMode::Repeating),
+            break_timer: Timer::new(Duration::from_secs_f32(3.0), TimerMode::Once),
+            completed: false,
+            took_damage_this_wave: false,
+            roster: Vec::new(),
+            roster_wave: None,
+        }
+    }
+}
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<WaveCleared>()
+            .init_resource::<WaveState>()
+            .preserve_timers_on_pause::<WaveState>()
+            .reset_on_restart::<WaveState>()
+            .add_systems(
+                Update,
+                (
+                    spawn_wave_enemies.in_set(EnemySet::Spawn).run_if(
+                        not(manual_spawn)
+                            .and_then(spawning_enabled)
+                            .and_then(not(boss_intro_active)),
+                    ),
+                    check_wave_complete.in_set(EnemySet::Cleanup),
+                )
+                    .run_if(
+                        in_state(GameState::GamePlay)
+                            .and_then(waves_enabled)
+                            .and_then(not(death_in_progress)),
+                    ),
+            );
+    }
+}
+
+fn spawn_wave_enemies(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut waves: ResMut<WaveState>,
+    mut spawn_data: ResMut<EnemySpawnData>,
+    mut status: ResMut<GameStats>,
+    config: Res<SpawnConfig>,
+    elapsed: Res<ElapsedGameplay>,
+    gameplay_start: Res<GameplayStart>,
+    bounds: Res<PlayfieldBounds>,
+    orientation: Res<PlayfieldOrientation>,
+    enemy_anims: Res<EnemyAnimations>,
+    enemy_types: Res<EnemyTypes>,
+    unlocks: Res<Unlocks>,
+    difficulty: Res<Difficulty>,
+    mut game_rng: ResMut<GameRng>,
+    live: Query<&Enemy>,
+) {
+    if !waves.break_timer.finished() {
+        waves.break_timer.tick(time.delta());
+        return;
+    }
+    let Some(wave) = waves.wave() else {
+        return;
+    };
+    let interval = Duration::from_secs_f32(wave.kind.interval(&config, elapsed.seconds));
+    if waves.timer.duration() != interval {
+        waves.timer.set_duration(interval);
+    }
+    waves.timer.tick(time.delta());
+    if waves.roster_wave != Some(waves.current) {
+        let mut roster = wave_roster(
+            game_rng.seed,
+            *difficulty,
+            waves.current,
+            wave.kind.count(),
+            &enemy_types,
+            &unlocks,
+        );
+        // a resumed run has already sent the start of it
+        roster.drain(..waves.spawned.min(roster.len()));
+        waves.roster = roster;
+        waves.roster_wave = Some(waves.current);
+    }
+    let mut counts = live_counts(live.iter());
+    let rng = game_rng.rng();
+    // a burst held back by the live cap goes out once there is room for it,
+    // otherwise whatever fit is the burst and pacing carries on from there
+    let burst = if waves.spawned == 0 {
+        wave.opening_burst.min(wave.kind.count())
+    } else {
+        0
+    };
+    let paced = waves.timer.times_finished_this_tick() as usize;
+    let placements = wave
+        .formation
+        .placements(burst, &bounds)
+        .into_iter()
+        .map(Some)
+        .chain(std::iter::repeat_n(None, paced));
+    for (live_count, placement) in (live.iter().count()..).zip(placements) {
+        if waves.remaining_to_spawn() == 0 || live_count >= config.max_live_enemies {
+            break;
+        }
+        // a type at its max_concurrent waits its turn, the wave still sends
+        // exactly what is on the roster
+        let Some(slot) = waves.roster.iter().position(|name| {
+            enemy_types.types.get(name).is_some_and(|info| {
+                info.max_concurrent
+                    .is_none_or(|max| counts.get(name).copied().unwrap_or(0) < max)
+            })
+        }) else {
+            break;
+        };
+        let enemy_name = waves.roster.remove(slot);
+        *counts.entry(enemy_name.clone()).or_default() += 1;
+        let info = &enemy_types.types[&enemy_name];
+        let mut enemy = Enemy::new(&enemy_name, info);
+        wave.kind.apply(&mut enemy);
+        enemy.health += waves.endless_level() as i32;
+        spawn_named_enemy(
+            &mut commands,
+            &enemy_anims,
+            enemy,
+            info,
+            Vec3::new(
+                bounds.spawn_x(gameplay_start.camera_endpos.x, *orientation)
+                    + placement.map_or(0.0, |p| p.x) * orientation.sign(),
+                placement.map_or_else(|| rng.gen_range(bounds.min_y..bounds.max_y), |p| p.y),
+                0.,
+            ),
+            *orientation,
+        );
+        waves.spawned += 1;
+        spawn_data.curr_spawned += 1;
+        status.entites_spawned += 1;
+    }
+}
+
+// each wave draws its lineup from its own generator, so it doesn't depend on
+// anything else the run rolled and can be worked out before the run starts
+pub fn wave_roster(
+    seed: u64,
+    difficulty: Difficulty,
+    index: usize,
+    count: usize,
+    enemy_types: &EnemyTypes,
+    unlocks: &Unlocks,
+) -> Vec<String> {
+    let mut available: Vec<(&String, &EnemyInfo)> = enemy_types
+        .types
+        .iter()
+        .filter(|(name, info)| unlocks.is_unlocked(name, info))
+        .collect();
+    // the map's order changes between runs, the draw mustn't
+    available.sort_by(|a, b| a.0.cmp(b.0));
+    let mix = (index as u64 * 3 + difficulty as u64 + 1).wrapping_mul(ROSTER_SEED_MIX);
+    let mut rng = StdRng::seed_from_u64(seed ^ mix);
+    (0..count)
+        .filter_map(|_| {
+            available
+                .choose_weighted(&mut rng, |(_, info)| info.weight)
+                .ok()
+                .map(|(name, _)| (*name).clone())
+        })
+        .collect()
+}
+
+// how many of each enemy wave `index` will send, in order of first appearance
+pub fn preview_wave(
+    seed: u64,
+    difficulty: Difficulty,
+    index: usize,
+    waves: &WaveState,
+    enemy_types: &EnemyTypes,
+    unlocks: &Unlocks,
+) -> Vec<(String, usize)> {
+    let Some(wave) = waves.waves.get(index) else {
+        return Vec::new();
+    };
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    let roster = wave_roster(
+        seed,
+        difficulty,
+        index,
+        wave.kind.count(),
+        enemy_types,
+        unlocks,
+    );
+    for name in roster {
+        match counts.iter_mut().find(|(counted, _)| *counted == name) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((name, 1)),
+        }
+    }
+    counts
+}
+
+fn check_wave_complete(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    localization: Res<Localization>,
+    endless: Res<Endless>,
+    mut waves: ResMut<WaveState>,
+    mut stats: ResMut<GameStats>,
+    mut cleared: EventWriter<WaveCleared>,
+    live: Query<(), With<Enemy>>,
+    cameras: Query<&Transform, With<Camera2d>>,
+) {
+    if !waves.break_timer.finished() || waves.completed {
+        return;
+    }
+    if waves.remaining_to_spawn() == 0 && live.is_empty() {
+        let perfect = !waves.took_damage_this_wave;
+        cleared.send(WaveCleared { perfect });
+        if perfect {
+            stats.villagers_saved += PERFECT_WAVE_BONUS;
+            if let Ok(camera) = cameras.get_single() {
+                let mut style = theme.hud_style(&asset_server);
+                style.color = PERFECT_COLOR;
+                spawn_fading_text(
+                    &mut commands,
+                    (camera.translation.truncate() + PERFECT_OFFSET).extend(20.0),
+                    format!(
+                        "{} +{}",
+                        localization.get("wave_perfect"),
+                        PERFECT_WAVE_BONUS
+                    ),
+                    style,
+                    Vec2::new(0.0, 20.0),
+                    2.0,
+                );
+            }
+        }
+        waves.took_damage_this_wave = false;
+        waves.current += 1;
+        waves.spawned = 0;
+        waves.timer.reset();
+        waves.break_timer.reset();
+        if endless.0 {
+            waves.extend_endless();
+        }
+        waves.completed = waves.current >= waves.waves.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::mode::GameMode,
+        menu::MenuItem,
+        testing::{advance_secs, headless_app, stub_enemy_animations, TEST_ENEMY},
+    };
+
+    const OTHER_ENEMY: &str = "other_enemy";
+
+    fn wave_app(mode: GameMode) -> App {
+        let mut app = headless_app();
+        app.insert_resource(mode).add_plugins(WavePlugin);
+        app
+    }
+
+    fn live_enemies(app: &mut App) -> usize {
+        app.world.query::<&Enemy>().iter(&app.world).count()
+    }
+
+    // skips straight past the break before wave `index`
+    fn start_wave(app: &mut App, index: usize) {
+        let mut waves = app.world.resource_mut::<WaveState>();
+        waves.current = index;
+        let break_time = waves.break_timer.duration();
+        waves.break_timer.tick(break_time);
+        app.update();
+    }
+
+    // as if wave `index` had been sent in full and every enemy in it was dealt with
+    fn clear_wave(app: &mut App, index: usize) {
+        let mut waves = app.world.resource_mut::<WaveState>();
+        waves.spawned = waves.waves[index].kind.count();
+        start_wave(app, index);
+    }
+
+    #[test]
+    fn waves_menu_item_drives_the_wave_spawner() {
+        let mut app = wave_app(MenuItem::Waves.mode());
+        // the first wave waits out the break before sending anyone
+        advance_secs(&mut app, 4.0);
+
+        let spawned = app.world.resource::<WaveState>().spawned;
+        assert!(spawned > 0);
+        assert_eq!(live_enemies(&mut app), spawned);
+    }
+
+    #[test]
+    fn plain_start_leaves_the_wave_spawner_idle() {
+        let mut app = wave_app(MenuItem::Start.mode());
+        advance_secs(&mut app, 4.0);
+
+        assert_eq!(app.world.resource::<WaveState>().spawned, 0);
+        assert_eq!(live_enemies(&mut app), 0);
+    }
+
+    #[test]
+    fn endless_keeps_a_wave_run_going_past_the_authored_waves() {
+        let mut app = wave_app(MenuItem::Waves.mode());
+        app.insert_resource(Endless(true));
+        let authored = app.world.resource::<WaveState>().authored;
+        clear_wave(&mut app, authored - 1);
+
+        let waves = app.world.resource::<WaveState>();
+        assert!(!waves.completed);
+        assert_eq!(waves.current, authored);
+        assert_eq!(waves.endless_level(), 1);
+        assert!(waves.wave().is_some());
+    }
+
+    #[test]
+    fn without_endless_the_last_authored_wave_ends_the_run() {
+        let mut app = wave_app(MenuItem::Waves.mode());
+        let authored = app.world.resource::<WaveState>().authored;
+        clear_wave(&mut app, authored - 1);
+
+        let waves = app.world.resource::<WaveState>();
+        assert!(waves.completed);
+        assert_eq!(waves.waves.len(), authored);
+    }
+
+    #[test]
+    fn opening_burst_arrives_as_the_wave_starts() {
+        let mut app = wave_app(MenuItem::Waves.mode());
+        let index = WaveState::default()
+            .waves
+            .iter()
+            .position(|wave| wave.opening_burst > 0)
+            .unwrap();
+        start_wave(&mut app, index);
+
+        let waves = app.world.resource::<WaveState>();
+        let burst = waves.waves[index].opening_burst;
+        assert_eq!(waves.spawned, burst);
+        assert_eq!(live_enemies(&mut app), burst);
+    }
+
+    #[test]
+    fn formations_place_members_behind_the_spawn_edge() {
+        let bounds = PlayfieldBounds::default();
+        let depths = |formation: Formation| -> Vec<f32> {
+            formation
+                .placements(4, &bounds)
+                .iter()
+                .map(|p| p.x)
+                .collect()
+        };
+        assert_eq!(depths(Formation::Column), vec![0.0; 4]);
+        assert_eq!(depths(Formation::Line), vec![0.0, 30.0, 60.0, 90.0]);
+        // the point is in the middle, nearest the line
+        assert_eq!(depths(Formation::V), vec![45.0, 15.0, 15.0, 45.0]);
+    }
+
+    #[test]
+    fn burst_is_spawned_in_the_wave_formation() {
+        let mut app = wave_app(MenuItem::Waves.mode());
+        let index = WaveState::default()
+            .waves
+            .iter()
+            .position(|wave| wave.opening_burst > 0 && wave.formation != Formation::Column)
+            .unwrap();
+        start_wave(&mut app, index);
+
+        let wave = app.world.resource::<WaveState>().waves[index];
+        // the harness plays on the default bounds
+        let bounds = PlayfieldBounds::default();
+        let orientation = *app.world.resource::<PlayfieldOrientation>();
+        let spawn_x = bounds.spawn_x(
+            app.world.resource::<GameplayStart>().camera_endpos.x,
+            orientation,
+        );
+        let mut expected = wave.formation.placements(wave.opening_burst, &bounds);
+        let mut placed: Vec<Vec2> = app
+            .world
+            .query_filtered::<&Transform, With<Enemy>>()
+            .iter(&app.world)
+            .map(|t| {
+                Vec2::new(
+                    (t.translation.x - spawn_x) * orientation.sign(),
+                    t.translation.y,
+                )
+            })
+            .collect();
+        let by_y = |a: &Vec2, b: &Vec2| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x));
+        expected.sort_by(by_y);
+        placed.sort_by(by_y);
+        assert_eq!(placed.len(), expected.len());
+        for (placed, expected) in placed.iter().zip(&expected) {
+            assert!(
+                placed.abs_diff_eq(*expected, 0.01),
+                "{placed} != {expected}"
+            );
+        }
+    }
+
+    fn cleared(app: &App) -> Vec<bool> {
+        let events = app.world.resource::<Events<WaveCleared>>();
+        events
+            .get_reader()
+            .read(events)
+            .map(|e| e.perfect)
+            .collect()
+    }
+
+    #[test]
+    fn clearing_a_wave_untouched_pays_the_perfect_bonus() {
+        let mut app = wave_app(MenuItem::Waves.mode());
+        clear_wave(&mut app, 0);
+
+        assert_eq!(cleared(&app), vec![true]);
+        assert_eq!(
+            app.world.resource::<GameStats>().villagers_saved,
+            PERFECT_WAVE_BONUS
+        );
+    }
+
+    #[test]
+    fn taking_a_hit_loses_the_perfect_bonus() {
+        let mut app = wave_app(MenuItem::Waves.mode());
+        app.world.resource_mut::<WaveState>().took_damage_this_wave = true;
+        clear_wave(&mut app, 0);
+
+        assert_eq!(cleared(&app), vec![false]);
+        assert_eq!(app.world.resource::<GameStats>().villagers_saved, 0);
+        // the next wave starts with a clean slate
+        assert!(!app.world.resource::<WaveState>().took_damage_this_wave);
+    }
+
+    // a second, rarer type so the draw has something to choose between
+    fn add_other_enemy(app: &mut App) {
+        let mut info = app.world.resource::<EnemyTypes>().types[TEST_ENEMY].clone();
+        info.weight = 0.5;
+        app.world
+            .resource_mut::<EnemyTypes>()
+            .types
+            .insert(OTHER_ENEMY.to_string(), info);
+        let handles = stub_enemy_animations().enemies.remove(TEST_ENEMY).unwrap();
+        app.world
+            .resource_mut::<EnemyAnimations>()
+            .enemies
+            .insert(OTHER_ENEMY.to_string(), handles);
+    }
+
+    #[test]
+    fn roster_depends_only_on_seed_difficulty_and_wave() {
+        let mut app = headless_app();
+        add_other_enemy(&mut app);
+        let types = app.world.resource::<EnemyTypes>();
+        let unlocks = Unlocks::default();
+        let roster =
+            |seed, difficulty, index| wave_roster(seed, difficulty, index, 20, types, &unlocks);
+
+        let first = roster(7, Difficulty::Normal, 0);
+        assert_eq!(first.len(), 20);
+        assert_eq!(first, roster(7, Difficulty::Normal, 0));
+        assert_ne!(first, roster(8, Difficulty::Normal, 0));
+        assert_ne!(first, roster(7, Difficulty::Hard, 0));
+        assert_ne!(first, roster(7, Difficulty::Normal, 1));
+    }
+
+    #[test]
+    fn preview_matches_what_the_wave_sends() {
+        let mut app = wave_app(MenuItem::Waves.mode());
+        add_other_enemy(&mut app);
+        let seed = 7;
+        app.insert_resource(GameRng::from_seed(seed));
+        let mut preview = preview_wave(
+            seed,
+            *app.world.resource::<Difficulty>(),
+            0,
+            app.world.resource::<WaveState>(),
+            app.world.resource::<EnemyTypes>(),
+            app.world.resource::<Unlocks>(),
+        );
+        start_wave(&mut app, 0);
+        while app.world.resource::<WaveState>().remaining_to_spawn() > 0 {
+            advance_secs(&mut app, 1.0);
+        }
+
+        let mut sent: Vec<(String, usize)> = Vec::new();
+        for enemy in app.world.query::<&Enemy>().iter(&app.world) {
+            match sent.iter_mut().find(|(name, _)| *name == enemy.name) {
+                Some((_, count)) => *count += 1,
+                None => sent.push((enemy.name.clone(), 1)),
+            }
+        }
+        sent.sort();
+        assert_eq!(preview.len(), 2);
+        preview.sort();
+        assert_eq!(sent, preview);
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    animation::ImagesToLoad,
+    data::{json::*, state::GameState},
+    entities::attack::AttackDef,
+};
+
+pub struct WeaponPlugin;
+
+#[derive(Asset, TypePath, Debug, Deserialize, Default)]
+pub struct WeaponEntry {
+    pub name: String,
+    pub sprite: String,
+    #[serde(flatten)]
+    pub attack: AttackDef,
+}
+
+#[derive(Asset, TypePath, Debug, Deserialize, Default)]
+pub struct WeaponListAsset {
+    pub weapons: Vec<WeaponEntry>,
+}
+
+#[derive(Resource, Default)]
+pub struct Weapons {
+    pub handle: Handle<WeaponListAsset>,
+    pub sprites: HashMap<String, Handle<Image>>,
+    pub attacks: HashMap<String, AttackDef>,
+    pub names: Vec<String>,
+    pub equipped: String,
+    pub loaded: bool,
+}
+
+impl Weapons {
+    pub fn sprite(&self) -> Option<Handle<Image>> {
+        self.sprites.get(&self.equipped).cloned()
+    }
+
+    pub fn attack(&self) -> Option<AttackDef> {
+        self.attacks.get(&self.equipped).cloned()
+    }
+
+    pub fn equip_next(&mut self) {
+        if let Some(index) = self.names.iter().position(|name| *name == self.equipped) {
+            self.equipped = self.names[(index + 1) % self.names.len()].clone();
+        }
+    }
+}
+
+impl Plugin for WeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(JsonPlugin::<WeaponListAsset> {
+            extensions: vec!["weaponinfo.json"],
+            ..default()
+        })
+        .init_resource::<Weapons>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, load_weapons.run_if(in_state(GameState::Loading)));
+    }
+}
+
+fn setup(mut weapons: ResMut<Weapons>, asset_server: Res<AssetServer>) {
+    weapons.handle = asset_server.load("weapons/list.weaponinfo.json");
+}
+
+fn load_weapons(
+    asset_server: Res<AssetServer>,
+    weapon_assets: Res<Assets<WeaponListAsset>>,
+    mut images_to_load: ResMut<ImagesToLoad>,
+    mut weapons: ResMut<Weapons>,
+) {
+    if weapons.loaded || !asset_server.is_loaded_with_dependencies(&weapons.handle) {
+        return;
+    }
+    let weapon_list = weapon_assets.get(&weapons.handle).unwrap();
+    let mut sprites = HashMap::new();
+    let mut attacks = HashMap::new();
+    let mut names = Vec::new();
+    for weapon in weapon_list.weapons.iter() {
+        let handle: Handle<Image> = asset_server.load(weapon.sprite.clone());
+        images_to_load.images.push(handle.id());
+        sprites.insert(weapon.name.clone(), handle);
+        attacks.insert(
+            weapon.name.clone(),
+            weapon.attack.clone().validated(&weapon.name),
+        );
+        names.push(weapon.name.clone());
+    }
+    if let Some(first) = names.first() {
+        weapons.equipped = first.clone();
+    }
+    weapons.sprites = sprites;
+    weapons.attacks = attacks;
+    weapons.names = names;
+    weapons.loaded = true;
+}
@@ -0,0 +1,280 @@
+use bevy::{app::AppExit, prelude::*};
+
+use crate::data::{
+    localization::{Localization, LocalizedText},
+    rng::GameRng,
+    state::GameState,
+    theme::Theme,
+};
+
+const GUARD_SECONDS: f32 = 0.5;
+
+pub struct GameOverPlugin;
+
+// keeps a mashed attack button from skipping straight past the game over
+// screen, and makes sure one press is only acted on once
+#[derive(Resource)]
+pub struct GameOverInputGuard {
+    pub timer: Timer,
+    pub consumed: bool,
+}
+
+impl Default for GameOverInputGuard {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(GUARD_SECONDS, TimerMode::Once),
+            consumed: false,
+        }
+    }
+}
+
+impl GameOverInputGuard {
+    pub fn accept(&mut self, pressed: bool) -> bool {
+        if !pressed || self.consumed || !self.timer.finished() {
+            return false;
+        }
+        self.consumed = true;
+        true
+    }
+}
+
+// how long the death gets to play out before the results come up, a key
+// press brings them up early once `min_skip` has passed
+#[derive(Resource)]
+pub struct GameOverTiming {
+    pub death_delay: f32,
+    pub min_skip: f32,
+    pub fade_in: f32,
+    elapsed: f32,
+    shown: bool,
+}
+
+impl Default for GameOverTiming {
+    fn default() -> Self {
+        Self {
+            death_delay: 2.0,
+            min_skip: 0.5,
+            fade_in: 0.5,
+            elapsed: 0.0,
+            shown: false,
+        }
+    }
+}
+
+impl GameOverTiming {
+    pub fn ready(&self, skip: bool) -> bool {
+        !self.shown && (self.elapsed >= self.death_delay || (skip && self.elapsed >= self.min_skip))
+    }
+
+    pub fn restart(&mut self) {
+        self.elapsed = 0.0;
+        self.shown = false;
+    }
+}
+
+#[derive(Component)]
+struct ContinuePrompt;
+
+#[derive(Component)]
+struct FadeIn(Timer);
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameOverInputGuard>()
+            .init_resource::<GameOverTiming>()
+            .add_systems(OnEnter(GameState::GameOver), reset_timing)
+            .add_systems(
+                Update,
+                (
+                    reveal_results,
+                    (fade_in_results, tick_guard, continue_on_input).run_if(results_shown),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::GameOver)),
+            );
+    }
+}
+
+fn results_shown(timing: Res<GameOverTiming>) -> bool {
+    timing.shown
+}
+
+fn reset_timing(mut timing: ResMut<GameOverTiming>) {
+    timing.restart();
+}
+
+// the guard starts counting once the results are up, so the key that skipped
+// the delay can't also quit
+fn reveal_results(
+    mut commands: Commands,
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<GamepadButton>>,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    localization: Res<Localization>,
+    rng: Res<GameRng>,
+    mut timing: ResMut<GameOverTiming>,
+    mut guard: ResMut<GameOverInputGuard>,
+) {
+    if timing.shown {
+        return;
+    }
+    timing.elapsed += time.delta_seconds();
+    let skip =
+        keys.get_just_pressed().next().is_some() || buttons.get_just_pressed().next().is_some();
+    if !timing.ready(skip) {
+        return;
+    }
+    timing.shown = true;
+    *guard = GameOverInputGuard::default();
+    let mut style = theme.hud_style(&asset_server);
+    style.color.set_a(0.0);
+    commands.spawn((
+        TextBundle {
+            text: Text::from_section(format!("{}: {}", localization.get("seed"), rng.seed), style),
+            style: Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Percent(12.0),
+                left: Val::Percent(35.0),
+                ..default()
+            },
+            ..default()
+        },
+        FadeIn(Timer::from_seconds(timing.fade_in, TimerMode::Once)),
+    ));
+    commands.spawn((
+        TextBundle {
+            text: Text::from_section(
+                localization.get("game_over_prompt"),
+                theme.hud_style(&asset_server),
+            ),
+            style: Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Percent(20.0),
+                left: Val::Percent(35.0),
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        ContinuePrompt,
+        LocalizedText("game_over_prompt"),
+    ));
+}
+
+fn fade_in_results(time: Res<Time>, mut texts: Query<(&mut FadeIn, &mut Text)>) {
+    for (mut fade, mut text) in &mut texts {
+        let alpha = fade.0.tick(time.delta()).percent();
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(alpha);
+        }
+    }
+}
+
+fn tick_guard(
+    time: Res<Time>,
+    mut guard: ResMut<GameOverInputGuard>,
+    mut prompts: Query<&mut Visibility, With<ContinuePrompt>>,
+) {
+    if guard.timer.tick(time.delta()).just_finished() {
+        for mut visibility in &mut prompts {
+            *visibility = Visibility::Inherited;
+        }
+    }
+}
+
+fn continue_on_input(
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<GamepadButton>>,
+    mut guard: ResMut<GameOverInputGuard>,
+    mut app_exit: EventWriter<AppExit>,
+) {
+    let pressed =
+        keys.get_just_pressed().next().is_some() || buttons.get_just_pressed().next().is_some();
+    if guard.accept(pressed) {
+        app_exit.send(AppExit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{advance, advance_secs, enter_state, headless_app, press, release};
+
+    #[derive(Resource, Default)]
+    struct Exits(usize);
+
+    fn count_exits(mut exits: EventReader<AppExit>, mut count: ResMut<Exits>) {
+        count.0 += exits.read().count();
+    }
+
+    fn tap(app: &mut App) -> usize {
+        press(app, KeyCode::Space);
+        advance(app, 1);
+        release(app, KeyCode::Space);
+        advance(app, 1);
+        app.world.get_resource::<Exits>().map_or(0, |exits| exits.0)
+    }
+
+    // straight to the results, so only the guard is left between a key and
+    // leaving the screen
+    fn results_up(app: &mut App) {
+        enter_state(app, GameState::GameOver);
+        let delay = app.world.resource::<GameOverTiming>().death_delay;
+        advance_secs(app, delay);
+    }
+
+    fn results_spawned(app: &mut App) -> bool {
+        app.world
+            .query_filtered::<(), With<ContinuePrompt>>()
+            .iter(&app.world)
+            .next()
+            .is_some()
+    }
+
+    #[test]
+    fn results_wait_for_the_death_delay() {
+        let mut app = headless_app();
+        app.add_plugins(GameOverPlugin);
+        enter_state(&mut app, GameState::GameOver);
+        let delay = app.world.resource::<GameOverTiming>().death_delay;
+        advance_secs(&mut app, delay - 0.1);
+        assert!(!results_spawned(&mut app));
+        advance_secs(&mut app, 0.1);
+        assert!(results_spawned(&mut app));
+    }
+
+    #[test]
+    fn a_key_skips_the_delay_after_the_minimum() {
+        let mut app = headless_app();
+        app.add_plugins(GameOverPlugin);
+        enter_state(&mut app, GameState::GameOver);
+        let min_skip = app.world.resource::<GameOverTiming>().min_skip;
+        tap(&mut app);
+        assert!(!results_spawned(&mut app));
+        advance_secs(&mut app, min_skip);
+        tap(&mut app);
+        assert!(results_spawned(&mut app));
+    }
+
+    #[test]
+    fn presses_during_the_guard_are_ignored_then_one_is_taken() {
+        let mut app = headless_app();
+        app.add_plugins(GameOverPlugin)
+            .init_resource::<Exits>()
+            .add_systems(Last, count_exits);
+        results_up(&mut app);
+
+        assert_eq!(tap(&mut app), 0);
+        advance_secs(&mut app, GUARD_SECONDS);
+        assert_eq!(tap(&mut app), 1);
+        assert_eq!(tap(&mut app), 1);
+
+        // the next game over starts with a fresh guard
+        enter_state(&mut app, GameState::MainMenu);
+        results_up(&mut app);
+        assert!(!app.world.resource::<GameOverInputGuard>().consumed);
+        assert_eq!(tap(&mut app), 1);
+    }
+}
@@ -0,0 +1,883 @@
+use bevy::{asset::LoadState, prelude::*};
+use serde::Deserialize;
+
+use crate::{
+    animation::AnimationComponent,
+    data::{json::*, localization::Localization, mode::GameMode, state::GameState, theme::Theme},
+    entities::{
+        enemy::Enemy,
+        player::{GameStats, Heat, PlayerData},
+        time_attack::TimeAttack,
+        wave::WaveState,
+    },
+    input::{Action, Actions},
+};
+
+const HEALTH_DRAIN_RATE: f32 = 15.0;
+const ICON_SIZE: f32 = 20.0;
+const MAX_HEART_ICONS: i32 = 10;
+const LOST_HEART_COLOR: Color = Color::rgba(0.3, 0.3, 0.3, 0.6);
+const HEAT_COLOR: Color = Color::rgb(1.0, 0.6, 0.1);
+const OVERHEAT_COLORS: [Color; 2] = [Color::rgb(1.0, 0.0, 0.0), Color::rgb(0.4, 0.0, 0.0)];
+const OVERHEAT_FLASH_RATE: f32 = 8.0;
+const LABEL_OFFSET: f32 = 14.0;
+const LABEL_FONT_SIZE: f32 = 10.0;
+
+pub struct HudPlugin;
+
+// corners rather than absolute positions, so elements stay put relative to
+// the edge they belong to whatever the window size
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HudAnchor {
+    #[default]
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct HudElement {
+    pub anchor: HudAnchor,
+    #[serde(default)]
+    pub offset: [f32; 2],
+    pub font_size: Option<f32>,
+}
+
+impl HudElement {
+    pub const fn new(anchor: HudAnchor, x: f32, y: f32) -> Self {
+        Self {
+            anchor,
+            offset: [x, y],
+            font_size: None,
+        }
+    }
+
+    // the offset is measured inwards from the anchored corner
+    pub fn style(&self) -> Style {
+        let [x, y] = self.offset.map(Val::Px);
+        let (left, right) = match self.anchor {
+            HudAnchor::TopLeft | HudAnchor::BottomLeft => (x, Val::Auto),
+            HudAnchor::TopRight | HudAnchor::BottomRight => (Val::Auto, x),
+        };
+        let (top, bottom) = match self.anchor {
+            HudAnchor::TopLeft | HudAnchor::TopRight => (y, Val::Auto),
+            HudAnchor::BottomLeft | HudAnchor::BottomRight => (Val::Auto, y),
+        };
+        Style {
+            position_type: PositionType::Absolute,
+            left,
+            right,
+            top,
+            bottom,
+            ..default()
+        }
+    }
+
+    pub fn text_style(&self, mut style: TextStyle) -> TextStyle {
+        if let Some(font_size) = self.font_size {
+            style.font_size = font_size;
+        }
+        style
+    }
+}
+
+#[derive(Asset, Resource, TypePath, Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct HudLayout {
+    pub score: HudElement,
+    pub health: HudElement,
+    pub wave: HudElement,
+    pub timer: HudElement,
+}
+
+impl Default for HudLayout {
+    fn default() -> Self {
+        Self {
+            score: HudElement::new(HudAnchor::TopLeft, 0.0, 0.0),
+            health: HudElement::new(HudAnchor::TopRight, 10.0, 10.0),
+            wave: HudElement::new(HudAnchor::TopRight, 10.0, 40.0),
+            timer: HudElement::new(HudAnchor::TopRight, 10.0, 70.0),
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+struct HudLayoutHandle {
+    handle: Handle<HudLayout>,
+    resolved: bool,
+}
+
+#[derive(Component)]
+pub struct HealthBar {
+    pub displayed_health: f32,
+}
+
+impl HealthBar {
+    pub fn approach(&mut self, target: f32, delta: f32) {
+        let t = (delta * HEALTH_DRAIN_RATE).min(1.0);
+        self.displayed_health += (target - self.displayed_health) * t;
+    }
+}
+
+#[derive(Component)]
+struct HealthBarFill;
+
+#[derive(Component)]
+struct WaveCounter;
+
+#[derive(Component)]
+struct HeatGauge;
+
+#[derive(Component)]
+struct Countdown;
+
+#[derive(Resource, Default)]
+pub struct ShowEnemyLabels(pub bool);
+
+// off for clean screenshots, the run carries on as normal
+#[derive(Resource)]
+pub struct HudVisible(pub bool);
+
+impl Default for HudVisible {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+// the top level of a hud element that goes away with the rest of the hud.
+// the countdown is left out on purpose, a time attack run needs it on screen
+#[derive(Component)]
+pub struct HudRoot;
+
+#[derive(Component)]
+struct EnemyLabel;
+
+#[derive(Component)]
+struct Labelled(Entity);
+
+#[derive(Resource, Default)]
+struct HudIcons {
+    heart: Handle<Image>,
+    coin: Handle<Image>,
+}
+
+#[derive(Component)]
+struct HeartRow;
+
+#[derive(Component)]
+struct HeartIcon(i32);
+
+#[derive(Component)]
+struct HeartCount;
+
+#[derive(Component)]
+struct CoinCount;
+
+// past MAX_HEART_ICONS a single heart with a count replaces the row
+fn heart_slots(max_health: i32) -> i32 {
+    if max_health > MAX_HEART_ICONS {
+        1
+    } else {
+        max_health.max(0)
+    }
+}
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(JsonPlugin::<HudLayout> {
+            extensions: vec!["hud.json"],
+            ..default()
+        })
+        .init_resource::<HudLayout>()
+        .init_resource::<HudLayoutHandle>()
+        .init_resource::<HudIcons>()
+        .init_resource::<ShowEnemyLabels>()
+        .init_resource::<HudVisible>()
+        .add_systems(Startup, (load_icons, load_layout))
+        .add_systems(Update, (resolve_layout, apply_hud_visibility))
+        .add_systems(OnEnter(GameState::TransitionToGamePlay), show_hud)
+        .add_systems(OnEnter(GameState::GameOver), show_hud)
+        .add_systems(
+            OnExit(GameState::TransitionToGamePlay),
+            (
+                spawn_health_bar,
+                spawn_heat_gauge,
+                spawn_wave_counter,
+                spawn_countdown,
+                spawn_icon_hud,
+            ),
+        )
+        .add_systems(
+            Update,
+            (
+                update_health_bar,
+                update_heat_gauge,
+                update_wave_counter,
+                update_countdown,
+                update_hearts,
+                update_coins,
+                (toggle_enemy_labels, sync_enemy_labels, update_enemy_labels).chain(),
+                toggle_hud,
+            )
+                .run_if(in_state(GameState::GamePlay)),
+        );
+    }
+}
+
+fn load_layout(mut layout_handle: ResMut<HudLayoutHandle>, asset_server: Res<AssetServer>) {
+    layout_handle.handle = asset_server.load("default.hud.json");
+}
+
+fn resolve_layout(
+    asset_server: Res<AssetServer>,
+    layouts: Res<Assets<HudLayout>>,
+    mut layout_handle: ResMut<HudLayoutHandle>,
+    mut layout: ResMut<HudLayout>,
+) {
+    if layout_handle.resolved {
+        return;
+    }
+    if let Some(loaded) = layouts.get(&layout_handle.handle) {
+        *layout = loaded.clone();
+        layout_handle.resolved = true;
+    } else if asset_server.get_load_state(&layout_handle.handle) == Some(LoadState::Failed) {
+        warn!("failed to load the hud layout, using the built-in one");
+        layout_handle.resolved = true;
+    }
+}
+
+fn spawn_health_bar(mut commands: Commands, layout: Res<HudLayout>, player_data: Res<PlayerData>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(200.0),
+                    height: Val::Px(20.0),
+                    ..layout.health.style()
+                },
+                background_color: Color::rgb(0.2, 0.2, 0.2).into(),
+                ..default()
+            },
+            HealthBar {
+                displayed_health: player_data.health as f32,
+            },
+            HudRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    background_color: Color::rgb(0.8, 0.1, 0.1).into(),
+                    ..default()
+                },
+                HealthBarFill,
+            ));
+        });
+}
+
+fn update_health_bar(
+    time: Res<Time>,
+    player_data: Res<PlayerData>,
+    mut bars: Query<(&mut HealthBar, &Children)>,
+    mut fills: Query<&mut Style, With<HealthBarFill>>,
+) {
+    for (mut bar, children) in &mut bars {
+        bar.approach(player_data.health as f32, time.delta_seconds());
+        let ratio = (bar.displayed_health / player_data.max_health as f32).clamp(0.0, 1.0);
+        for child in children.iter() {
+            if let Ok(mut style) = fills.get_mut(*child) {
+                style.width = Val::Percent(ratio * 100.0);
+            }
+        }
+    }
+}
+
+fn spawn_heat_gauge(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(10.0),
+                    top: Val::Px(32.0),
+                    width: Val::Px(200.0),
+                    height: Val::Px(6.0),
+                    ..default()
+                },
+                background_color: Color::rgb(0.2, 0.2, 0.2).into(),
+                ..default()
+            },
+            HudRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    background_color: HEAT_COLOR.into(),
+                    ..default()
+                },
+                HeatGauge,
+            ));
+        });
+}
+
+fn update_heat_gauge(
+    time: Res<Time>,
+    heat: Res<Heat>,
+    mut gauges: Query<(&mut Style, &mut BackgroundColor), With<HeatGauge>>,
+) {
+    for (mut style, mut color) in &mut gauges {
+        style.width = Val::Percent(heat.ratio() * 100.0);
+        *color = if heat.overheated {
+            let flash = (time.elapsed_seconds() * OVERHEAT_FLASH_RATE) as usize % 2;
+            OVERHEAT_COLORS[flash]
+        } else {
+            HEAT_COLOR
+        }
+        .into();
+    }
+}
+
+fn toggle_enemy_labels(actions: Actions, mut labels: ResMut<ShowEnemyLabels>) {
+    if actions.just_pressed(Action::ToggleLabels) {
+        labels.0 = !labels.0;
+    }
+}
+
+// labels are children so they follow their enemy and go with it when it is
+// despawned recursively
+fn sync_enemy_labels(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    labels: Res<ShowEnemyLabels>,
+    unlabelled: Query<Entity, (With<Enemy>, Without<Labelled>)>,
+    labelled: Query<(Entity, &Labelled)>,
+) {
+    if !labels.is_changed() && !labels.0 {
+        return;
+    }
+    if !labels.0 {
+        for (enemy, label) in &labelled {
+            commands.entity(label.0).despawn_recursive();
+            commands.entity(enemy).remove::<Labelled>();
+        }
+        return;
+    }
+    let mut style = theme.hud_style(&asset_server);
+    style.font_size = LABEL_FONT_SIZE;
+    for enemy in &unlabelled {
+        let label = commands
+            .spawn((
+                Text2dBundle {
+                    text: Text::from_section("", style.clone())
+                        .with_alignment(TextAlignment::Center),
+                    // enemies are drawn at double scale
+                    transform: Transform::from_xyz(0.0, LABEL_OFFSET, 1.0)
+                        .with_scale(Vec3::splat(0.5)),
+                    ..default()
+                },
+                EnemyLabel,
+            ))
+            .id();
+        commands
+            .entity(enemy)
+            .add_child(label)
+            .insert(Labelled(label));
+    }
+}
+
+fn update_enemy_labels(
+    enemies: Query<(&Enemy, &AnimationComponent, &Labelled)>,
+    mut labels: Query<(&mut Text, &mut Visibility), With<EnemyLabel>>,
+) {
+    for (enemy, anim, labelled) in &enemies {
+        let Ok((mut text, mut visibility)) = labels.get_mut(labelled.0) else {
+            continue;
+        };
+        if anim.state.is_dying() {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        text.sections[0].value = format!("{} {}", enemy.name, enemy.health);
+    }
+}
+
+fn toggle_hud(actions: Actions, mut hud: ResMut<HudVisible>) {
+    if actions.just_pressed(Action::ToggleHud) {
+        hud.0 = !hud.0;
+    }
+}
+
+fn show_hud(mut hud: ResMut<HudVisible>) {
+    hud.0 = true;
+}
+
+// elements spawned while the hud is off start out hidden too
+fn apply_hud_visibility(hud: Res<HudVisible>, mut roots: Query<(Ref<HudRoot>, &mut Visibility)>) {
+    for (root, mut visibility) in &mut roots {
+        if hud.is_changed() || root.is_added() {
+            *visibility = if hud.0 {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+}
+
+fn spawn_countdown(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    layout: Res<HudLayout>,
+) {
+    commands.spawn((
+        TextBundle::from_section("", layout.timer.text_style(theme.hud_style(&asset_server)))
+            .with_style(layout.timer.style()),
+        Countdown,
+    ));
+}
+
+fn update_countdown(
+    mode: Res<GameMode>,
+    localization: Res<Localization>,
+    time_attack: Res<TimeAttack>,
+    mut countdowns: Query<(&mut Text, &mut Visibility), With<Countdown>>,
+) {
+    for (mut text, mut visibility) in &mut countdowns {
+        if mode.time_attack.is_none() {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        *visibility = Visibility::Inherited;
+        text.sections[0].value = format!(
+            "{}: {}",
+            localization.get("hud_time"),
+            time_attack.remaining.ceil() as i32
+        );
+    }
+}
+
+fn spawn_wave_counter(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    layout: Res<HudLayout>,
+) {
+    commands.spawn((
+        TextBundle::from_section("", layout.wave.text_style(theme.hud_style(&asset_server)))
+            .with_style(layout.wave.style()),
+        WaveCounter,
+    ));
+}
+
+// shows and hides itself every frame, so it follows the hud toggle here
+// rather than through HudRoot
+fn update_wave_counter(
+    mode: Res<GameMode>,
+    hud: Res<HudVisible>,
+    localization: Res<Localization>,
+    waves: Res<WaveState>,
+    enemies: Query<&AnimationComponent, With<Enemy>>,
+    mut counters: Query<(&mut Text, &mut Visibility), With<WaveCounter>>,
+) {
+    for (mut text, mut visibility) in &mut counters {
+        // endless mode has no wave to clear
+        if !mode.waves || !hud.0 {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        *visibility = Visibility::Inherited;
+        let alive = enemies.iter().filter(|anim| !anim.state.is_dying()).count();
+        text.sections[0].value = format!(
+            "{}: {}",
+            localization.get("hud_enemies_left"),
+            waves.remaining_to_spawn() + alive
+        );
+    }
+}
+
+fn load_icons(mut icons: ResMut<HudIcons>, asset_server: Res<AssetServer>) {
+    icons.heart = asset_server.load("sprites/hud/heart.png");
+    icons.coin = asset_server.load("sprites/hud/coin.png");
+}
+
+fn icon(image: Handle<Image>) -> ImageBundle {
+    ImageBundle {
+        style: Style {
+            width: Val::Px(ICON_SIZE),
+            height: Val::Px(ICON_SIZE),
+            margin: UiRect::right(Val::Px(2.0)),
+            ..default()
+        },
+        image: UiImage::new(image),
+        ..default()
+    }
+}
+
+fn icon_row(top: f32) -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.0),
+            top: Val::Px(top),
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        ..default()
+    }
+}
+
+fn spawn_icon_hud(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    icons: Res<HudIcons>,
+) {
+    commands
+        .spawn((icon_row(40.0), HudRoot))
+        .with_children(|parent| {
+            parent.spawn((NodeBundle::default(), HeartRow));
+            parent.spawn((
+                TextBundle::from_section("", theme.hud_style(&asset_server)),
+                HeartCount,
+            ));
+        });
+    commands
+        .spawn((icon_row(40.0 + ICON_SIZE + 6.0), HudRoot))
+        .with_children(|parent| {
+            parent.spawn(icon(icons.coin.clone()));
+            parent.spawn((
+                TextBundle::from_section("", theme.hud_style(&asset_server)),
+                CoinCount,
+            ));
+        });
+}
+
+fn update_hearts(
+    mut commands: Commands,
+    player_data: Res<PlayerData>,
+    icons: Res<HudIcons>,
+    rows: Query<(Entity, Option<&Children>), With<HeartRow>>,
+    mut hearts: Query<(&HeartIcon, &mut BackgroundColor)>,
+    mut counts: Query<&mut Text, With<HeartCount>>,
+) {
+    let slots = heart_slots(player_data.max_health);
+    let compact = player_data.max_health > MAX_HEART_ICONS;
+    for (row, children) in &rows {
+        if children.map_or(0, |c| c.len()) != slots as usize {
+            commands.entity(row).despawn_descendants();
+            commands.entity(row).with_children(|parent| {
+                for i in 0..slots {
+                    parent.spawn((icon(icons.heart.clone()), HeartIcon(i)));
+                }
+            });
+        }
+    }
+    for (heart, mut color) in &mut hearts {
+        let lit = if compact {
+            player_data.health > 0
+        } else {
+            heart.0 < player_data.health
+        };
+        *color = if lit { Color::WHITE } else { LOST_HEART_COLOR }.into();
+    }
+    for mut text in &mut counts {
+        text.sections[0].value = if compact {
+            format!(" x {}", player_data.health.max(0))
+        } else {
+            String::new()
+        };
+    }
+}
+
+fn update_coins(stats: Res<GameStats>, mut counts: Query<&mut Text, With<CoinCount>>) {
+    for mut text in &mut counts {
+        text.sections[0].value = format!(" {}", stats.villagers_saved);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::{
+        animation::AnimState,
+        entities::{
+            enemy::EnemyTypes,
+            pickup::{MaxHealthPickup, PickupPlugin},
+            player::{Player, Shield},
+        },
+        menu::MenuItem,
+        testing::{
+            advance, advance_secs, collide, enter_state, headless_app, press, release, TEST_ENEMY,
+        },
+    };
+
+    fn counter_app(item: MenuItem) -> (App, Entity) {
+        let mut app = headless_app();
+        app.insert_resource(item.mode())
+            .init_resource::<HudVisible>()
+            .add_systems(Update, update_wave_counter);
+        let counter = app
+            .world
+            .spawn((
+                Text::from_section("", TextStyle::default()),
+                Visibility::Hidden,
+                WaveCounter,
+            ))
+            .id();
+        (app, counter)
+    }
+
+    fn spawn_enemy(app: &mut App, state: AnimState) -> Entity {
+        let info = app.world.resource::<EnemyTypes>().types[TEST_ENEMY].clone();
+        app.world
+            .spawn((
+                Enemy::new(TEST_ENEMY, &info),
+                AnimationComponent::new(state),
+            ))
+            .id()
+    }
+
+    #[test]
+    fn wave_counter_adds_unsent_and_living_enemies() {
+        let (mut app, counter) = counter_app(MenuItem::Waves);
+        app.world.resource_mut::<WaveState>().spawned = 5;
+        spawn_enemy(&mut app, AnimState::Walking);
+        spawn_enemy(&mut app, AnimState::Walking);
+        // already on its way out, so not left to beat
+        spawn_enemy(&mut app, AnimState::Dying);
+        advance(&mut app, 1);
+
+        let remaining = app.world.resource::<WaveState>().remaining_to_spawn() + 2;
+        let entity = app.world.entity(counter);
+        assert_eq!(
+            entity.get::<Text>().unwrap().sections[0].value,
+            format!("Enemies left: {remaining}")
+        );
+        assert_eq!(entity.get::<Visibility>(), Some(&Visibility::Inherited));
+    }
+
+    #[test]
+    fn wave_counter_is_hidden_outside_wave_runs() {
+        let (mut app, counter) = counter_app(MenuItem::Start);
+        advance(&mut app, 1);
+
+        assert_eq!(
+            app.world.entity(counter).get::<Visibility>(),
+            Some(&Visibility::Hidden)
+        );
+    }
+
+    fn labels(app: &mut App) -> usize {
+        app.world
+            .query_filtered::<(), With<EnemyLabel>>()
+            .iter(&app.world)
+            .count()
+    }
+
+    #[test]
+    fn each_enemy_gets_a_label_child_while_labels_are_on() {
+        let mut app = headless_app();
+        app.init_resource::<ShowEnemyLabels>()
+            .add_systems(Update, (sync_enemy_labels, update_enemy_labels).chain());
+        let enemies = [
+            spawn_enemy(&mut app, AnimState::Walking),
+            spawn_enemy(&mut app, AnimState::Walking),
+        ];
+        advance(&mut app, 1);
+        assert_eq!(labels(&mut app), 0);
+
+        app.world.resource_mut::<ShowEnemyLabels>().0 = true;
+        advance(&mut app, 2);
+        assert_eq!(labels(&mut app), enemies.len());
+        for enemy in enemies {
+            let label = app.world.get::<Labelled>(enemy).unwrap().0;
+            assert!(app.world.get::<Children>(enemy).unwrap().contains(&label));
+            let text = &app.world.get::<Text>(label).unwrap().sections[0].value;
+            assert_eq!(text, &format!("{TEST_ENEMY} 1"));
+        }
+
+        app.world.entity_mut(enemies[0]).despawn_recursive();
+        app.world.resource_mut::<ShowEnemyLabels>().0 = false;
+        advance(&mut app, 1);
+        assert_eq!(labels(&mut app), 0);
+    }
+
+    #[test]
+    fn bottom_right_anchor_positions_from_that_corner() {
+        let mut app = headless_app();
+        let layout: HudLayout = serde_json::from_str(
+            r#"{ "health": { "anchor": "bottom_right", "offset": [12.0, 8.0] } }"#,
+        )
+        .unwrap();
+        app.insert_resource(layout);
+        app.world.run_system_once(spawn_health_bar);
+
+        let style = app
+            .world
+            .query_filtered::<&Style, With<HealthBar>>()
+            .single(&app.world);
+        assert_eq!(style.position_type, PositionType::Absolute);
+        assert_eq!((style.right, style.bottom), (Val::Px(12.0), Val::Px(8.0)));
+        assert_eq!((style.left, style.top), (Val::Auto, Val::Auto));
+        // the bar keeps its own size whatever the anchor
+        assert_eq!(style.width, Val::Px(200.0));
+    }
+
+    fn lit_hearts(app: &mut App) -> usize {
+        app.world
+            .query_filtered::<&BackgroundColor, With<HeartIcon>>()
+            .iter(&app.world)
+            .filter(|color| color.0 == Color::WHITE)
+            .count()
+    }
+
+    #[test]
+    fn lit_hearts_match_the_heros_health() {
+        let mut app = headless_app();
+        app.init_resource::<HudIcons>()
+            .add_systems(Update, update_hearts);
+        app.world.spawn((NodeBundle::default(), HeartRow));
+        app.world.resource_mut::<PlayerData>().max_health = 5;
+        for health in [5, 3, 0] {
+            app.world.resource_mut::<PlayerData>().health = health;
+            // the row is filled in on the first frame and lit on the next
+            advance(&mut app, 2);
+            assert_eq!(lit_hearts(&mut app), health as usize);
+        }
+        let hearts = app
+            .world
+            .query_filtered::<(), With<HeartIcon>>()
+            .iter(&app.world)
+            .count();
+        assert_eq!(hearts, 5);
+    }
+
+    #[test]
+    fn health_bar_drains_towards_the_real_health() {
+        let mut app = headless_app();
+        app.add_systems(Update, update_health_bar);
+        let fill = app.world.spawn((Style::default(), HealthBarFill)).id();
+        let bar = app
+            .world
+            .spawn(HealthBar {
+                displayed_health: 10.0,
+            })
+            .push_children(&[fill])
+            .id();
+        app.world.resource_mut::<PlayerData>().health = 4;
+
+        let mut last = 10.0;
+        for _ in 0..30 {
+            advance(&mut app, 1);
+            let displayed = app.world.get::<HealthBar>(bar).unwrap().displayed_health;
+            assert!(displayed < last && displayed >= 4.0);
+            last = displayed;
+        }
+        assert!((last - 4.0).abs() < 0.01);
+        assert_eq!(
+            app.world.get::<Style>(fill).unwrap().width,
+            Val::Percent(last / 10.0 * 100.0)
+        );
+    }
+
+    #[test]
+    fn a_max_health_pickup_raises_the_cap_and_the_bar_follows() {
+        let mut app = headless_app();
+        app.init_resource::<Shield>()
+            .add_plugins(PickupPlugin)
+            .add_systems(Update, update_health_bar);
+        let player = app.world.spawn(Player).id();
+        let fill = app.world.spawn((Style::default(), HealthBarFill)).id();
+        app.world
+            .spawn(HealthBar {
+                displayed_health: 6.0,
+            })
+            .push_children(&[fill]);
+        let max_health = {
+            let mut data = app.world.resource_mut::<PlayerData>();
+            data.health = 6;
+            data.max_health
+        };
+
+        let pickup = app.world.spawn(MaxHealthPickup { amount: 2 }).id();
+        collide(&mut app, player, pickup);
+        advance_secs(&mut app, 2.0);
+
+        let data = app.world.resource::<PlayerData>();
+        assert_eq!(data.max_health, max_health + 2);
+        assert_eq!(data.health, 8);
+        let Val::Percent(width) = app.world.get::<Style>(fill).unwrap().width else {
+            panic!("the fill is sized as a percentage");
+        };
+        assert!((width - 8.0 / (max_health + 2) as f32 * 100.0).abs() < 0.1);
+    }
+
+    fn tap_h(app: &mut App) {
+        press(app, KeyCode::H);
+        advance(app, 1);
+        release(app, KeyCode::H);
+        advance(app, 1);
+    }
+
+    fn hud_text(app: &mut App) -> Entity {
+        app.world
+            .spawn((
+                TextBundle::from_section("score", TextStyle::default()),
+                HudRoot,
+            ))
+            .id()
+    }
+
+    #[test]
+    fn h_hides_and_restores_the_hud_but_never_the_countdown() {
+        let mut app = headless_app();
+        app.init_resource::<HudVisible>()
+            .add_systems(Update, (toggle_hud, apply_hud_visibility).chain())
+            .add_systems(OnEnter(GameState::GameOver), show_hud);
+        let score = hud_text(&mut app);
+        let countdown = app
+            .world
+            .spawn((
+                TextBundle::from_section("30", TextStyle::default()),
+                Countdown,
+            ))
+            .id();
+        let visibility = |app: &App, entity| *app.world.get::<Visibility>(entity).unwrap();
+        advance(&mut app, 1);
+        assert_eq!(visibility(&app, score), Visibility::Inherited);
+
+        tap_h(&mut app);
+        assert!(!app.world.resource::<HudVisible>().0);
+        assert_eq!(visibility(&app, score), Visibility::Hidden);
+        assert_eq!(visibility(&app, countdown), Visibility::Inherited);
+        // anything that turns up while the hud is off stays off with it
+        let late = hud_text(&mut app);
+        advance(&mut app, 1);
+        assert_eq!(visibility(&app, late), Visibility::Hidden);
+
+        tap_h(&mut app);
+        assert_eq!(visibility(&app, score), Visibility::Inherited);
+        assert_eq!(visibility(&app, late), Visibility::Inherited);
+
+        tap_h(&mut app);
+        enter_state(&mut app, GameState::GameOver);
+        advance(&mut app, 1);
+        assert_eq!(visibility(&app, score), Visibility::Inherited);
+    }
+}
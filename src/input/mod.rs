@@ -0,0 +1,352 @@
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use crate::{data::state::GameState, demo::DemoPlayer};
+
+const MIN_REPEAT_RATE: f32 = 0.01;
+
+pub struct ActionPlugin;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    Attack,
+    SwitchWeapon,
+    Dash,
+    Focus,
+    Confirm,
+    Pause,
+    Back,
+    Quit,
+    ToggleLabels,
+    ToggleHud,
+}
+
+impl Action {
+    pub const ALL: [Action; 12] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::Attack,
+        Action::SwitchWeapon,
+        Action::Dash,
+        Action::Focus,
+        Action::Confirm,
+        Action::Pause,
+        Action::Back,
+        Action::Quit,
+        Action::ToggleLabels,
+        Action::ToggleHud,
+    ];
+
+    pub fn scope(&self) -> ActionScope {
+        match self {
+            Action::MoveUp | Action::MoveDown | Action::Pause => ActionScope::Both,
+            Action::Attack
+            | Action::SwitchWeapon
+            | Action::Dash
+            | Action::Focus
+            | Action::ToggleLabels
+            | Action::ToggleHud => ActionScope::Gameplay,
+            Action::Confirm | Action::Back | Action::Quit => ActionScope::Menu,
+        }
+    }
+}
+
+// where an action is read. a binding only has to be unique among actions
+// whose scopes overlap, so space can attack in a run and confirm in a menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionScope {
+    Gameplay,
+    Menu,
+    Both,
+}
+
+impl ActionScope {
+    pub fn overlaps(&self, other: ActionScope) -> bool {
+        *self == other || *self == ActionScope::Both || other == ActionScope::Both
+    }
+}
+
+#[derive(Resource)]
+pub struct InputMap {
+    keys: HashMap<Action, Vec<KeyCode>>,
+    buttons: HashMap<Action, Vec<GamepadButtonType>>,
+}
+
+impl InputMap {
+    pub fn empty() -> Self {
+        Self {
+            keys: HashMap::new(),
+            buttons: HashMap::new(),
+        }
+    }
+
+    pub fn bind_key(&mut self, action: Action, key: KeyCode) -> &mut Self {
+        self.keys.entry(action).or_default().push(key);
+        self
+    }
+
+    pub fn bind_button(&mut self, action: Action, button: GamepadButtonType) -> &mut Self {
+        self.buttons.entry(action).or_default().push(button);
+        self
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.keys
+            .get(&action)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn buttons(&self, action: Action) -> &[GamepadButtonType] {
+        self.buttons
+            .get(&action)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    // pairs of actions that share a key or button while both can be read
+    pub fn conflicts(&self) -> Vec<(Action, Action)> {
+        let mut conflicts = Vec::new();
+        for (i, a) in Action::ALL.iter().enumerate() {
+            for b in &Action::ALL[i + 1..] {
+                if !a.scope().overlaps(b.scope()) {
+                    continue;
+                }
+                let shares_key = self.keys(*a).iter().any(|key| self.keys(*b).contains(key));
+                let shares_button = self
+                    .buttons(*a)
+                    .iter()
+                    .any(|button| self.buttons(*b).contains(button));
+                if shares_key || shares_button {
+                    conflicts.push((*a, *b));
+                }
+            }
+        }
+        conflicts
+    }
+
+    pub fn pressed(
+        &self,
+        action: Action,
+        keys: &Input<KeyCode>,
+        buttons: &Input<GamepadButton>,
+        gamepads: &Gamepads,
+    ) -> bool {
+        keys.any_pressed(self.keys(action).iter().copied())
+            || gamepads.iter().any(|gamepad| {
+                buttons.any_pressed(
+                    self.buttons(action)
+                        .iter()
+                        .map(|button| GamepadButton::new(gamepad, *button)),
+                )
+            })
+    }
+
+    pub fn just_pressed(
+        &self,
+        action: Action,
+        keys: &Input<KeyCode>,
+        buttons: &Input<GamepadButton>,
+        gamepads: &Gamepads,
+    ) -> bool {
+        keys.any_just_pressed(self.keys(action).iter().copied())
+            || gamepads.iter().any(|gamepad| {
+                buttons.any_just_pressed(
+                    self.buttons(action)
+                        .iter()
+                        .map(|button| GamepadButton::new(gamepad, *button)),
+                )
+            })
+    }
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        let mut map = Self::empty();
+        map.bind_key(Action::MoveUp, KeyCode::W)
+            .bind_key(Action::MoveUp, KeyCode::Up)
+            .bind_button(Action::MoveUp, GamepadButtonType::DPadUp)
+            .bind_key(Action::MoveDown, KeyCode::S)
+            .bind_key(Action::MoveDown, KeyCode::Down)
+            .bind_button(Action::MoveDown, GamepadButtonType::DPadDown)
+            .bind_key(Action::Attack, KeyCode::Space)
+            .bind_button(Action::Attack, GamepadButtonType::South)
+            .bind_key(Action::SwitchWeapon, KeyCode::Q)
+            .bind_button(Action::SwitchWeapon, GamepadButtonType::RightTrigger)
+            .bind_key(Action::Dash, KeyCode::ShiftLeft)
+            .bind_key(Action::Dash, KeyCode::ShiftRight)
+            .bind_button(Action::Dash, GamepadButtonType::LeftTrigger)
+            // shift is already the dash, so focus sits on control
+            .bind_key(Action::Focus, KeyCode::ControlLeft)
+            .bind_key(Action::Focus, KeyCode::ControlRight)
+            .bind_button(Action::Focus, GamepadButtonType::LeftTrigger2)
+            .bind_key(Action::Confirm, KeyCode::Space)
+            .bind_key(Action::Confirm, KeyCode::Return)
+            .bind_button(Action::Confirm, GamepadButtonType::South)
+            .bind_key(Action::Pause, KeyCode::Escape)
+            .bind_key(Action::Pause, KeyCode::P)
+            .bind_button(Action::Pause, GamepadButtonType::Start)
+            // escape already pauses and unpauses, back only closes the pause menu
+            .bind_key(Action::Back, KeyCode::Back)
+            .bind_button(Action::Back, GamepadButtonType::East)
+            .bind_key(Action::Quit, KeyCode::Q)
+            .bind_button(Action::Quit, GamepadButtonType::Select)
+            .bind_key(Action::ToggleLabels, KeyCode::L)
+            .bind_key(Action::ToggleHud, KeyCode::H);
+        map
+    }
+}
+
+#[derive(SystemParam)]
+pub struct Actions<'w> {
+    map: Res<'w, InputMap>,
+    keys: Res<'w, Input<KeyCode>>,
+    buttons: Res<'w, Input<GamepadButton>>,
+    gamepads: Res<'w, Gamepads>,
+    demo: Option<Res<'w, DemoPlayer>>,
+    state: Option<Res<'w, State<GameState>>>,
+}
+
+impl<'w> Actions<'w> {
+    // a run is the only place gameplay actions are read, every other state
+    // is some kind of menu
+    fn in_scope(&self, action: Action) -> bool {
+        let scope = match self.state.as_deref().map(State::get) {
+            None => return true,
+            Some(GameState::GamePlay) => ActionScope::Gameplay,
+            Some(_) => ActionScope::Menu,
+        };
+        action.scope().overlaps(scope)
+    }
+
+    pub fn pressed(&self, action: Action) -> bool {
+        if !self.in_scope(action) {
+            return false;
+        }
+        if let Some(demo) = &self.demo {
+            return demo.pressed(action);
+        }
+        self.map
+            .pressed(action, &self.keys, &self.buttons, &self.gamepads)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        if !self.in_scope(action) {
+            return false;
+        }
+        if let Some(demo) = &self.demo {
+            return demo.just_pressed(action);
+        }
+        self.map
+            .just_pressed(action, &self.keys, &self.buttons, &self.gamepads)
+    }
+}
+
+// how a held up/down steps through menus, both in seconds. the delay keeps a
+// single tap from moving more than one item
+#[derive(Resource, Clone, Copy)]
+pub struct MenuRepeat {
+    pub initial_delay: f32,
+    pub repeat_rate: f32,
+}
+
+impl Default for MenuRepeat {
+    fn default() -> Self {
+        Self {
+            initial_delay: 0.4,
+            repeat_rate: 0.1,
+        }
+    }
+}
+
+// per menu state for a held direction, kept in a Local by each navigate system
+#[derive(Default)]
+pub struct MenuHold {
+    action: Option<Action>,
+    held_for: f32,
+    next_step: f32,
+}
+
+impl MenuHold {
+    // a fresh press steps once straight away, holding steps again after the
+    // delay and then at the repeat rate. letting go starts the delay over
+    pub fn step(&mut self, actions: &Actions, repeat: &MenuRepeat, delta: f32) -> Option<Action> {
+        for action in [Action::MoveDown, Action::MoveUp] {
+            if actions.just_pressed(action) {
+                *self = Self {
+                    action: Some(action),
+                    held_for: 0.0,
+                    next_step: repeat.initial_delay,
+                };
+                return Some(action);
+            }
+        }
+        let Some(action) = self.action.filter(|action| actions.pressed(*action)) else {
+            *self = Self::default();
+            return None;
+        };
+        self.held_for += delta;
+        if self.held_for < self.next_step {
+            return None;
+        }
+        // at most one step a frame, a long frame shouldn't skip items
+        self.next_step = self.held_for + repeat.repeat_rate.max(MIN_REPEAT_RATE);
+        Some(action)
+    }
+}
+
+impl Plugin for ActionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputMap>()
+            .init_resource::<MenuRepeat>()
+            .add_systems(Startup, warn_conflicts);
+    }
+}
+
+fn warn_conflicts(map: Res<InputMap>) {
+    for (a, b) in map.conflicts() {
+        warn!("{:?} and {:?} share a binding", a, b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_map_has_no_conflicts() {
+        assert_eq!(InputMap::default().conflicts(), vec![]);
+    }
+
+    #[test]
+    fn shared_binding_in_one_scope_is_a_conflict() {
+        let mut map = InputMap::empty();
+        map.bind_key(Action::Pause, KeyCode::Escape)
+            .bind_key(Action::Back, KeyCode::Escape)
+            .bind_key(Action::Attack, KeyCode::Space)
+            .bind_key(Action::Confirm, KeyCode::Space);
+        assert_eq!(map.conflicts(), vec![(Action::Pause, Action::Back)]);
+    }
+
+    #[test]
+    fn action_is_pressed_while_its_key_is_down() {
+        let map = InputMap::default();
+        let mut keys = Input::<KeyCode>::default();
+        let buttons = Input::<GamepadButton>::default();
+        let gamepads = Gamepads::default();
+        keys.press(KeyCode::Up);
+
+        assert!(map.pressed(Action::MoveUp, &keys, &buttons, &gamepads));
+        assert!(map.just_pressed(Action::MoveUp, &keys, &buttons, &gamepads));
+        assert!(!map.pressed(Action::MoveDown, &keys, &buttons, &gamepads));
+
+        keys.clear();
+        assert!(map.pressed(Action::MoveUp, &keys, &buttons, &gamepads));
+        assert!(!map.just_pressed(Action::MoveUp, &keys, &buttons, &gamepads));
+
+        keys.release(KeyCode::Up);
+        assert!(!map.pressed(Action::MoveUp, &keys, &buttons, &gamepads));
+    }
+}
@@ -0,0 +1,99 @@
+use bevy::{prelude::*, transform::TransformSystem};
+use bevy_rapier2d::prelude::*;
+
+use crate::data::playfield::PlayfieldBounds;
+
+pub struct InterpolationPlugin;
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FixedMovementSet;
+
+#[derive(Component)]
+pub struct Interpolated {
+    previous: Vec3,
+    logical: Vec3,
+}
+
+#[derive(Component)]
+pub struct YSorted;
+
+impl Interpolated {
+    pub fn new(translation: Vec3) -> Self {
+        Self {
+            previous: translation,
+            logical: translation,
+        }
+    }
+}
+
+impl Plugin for InterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.configure_sets(FixedUpdate, FixedMovementSet)
+            .add_systems(FixedUpdate, store_previous.before(FixedMovementSet))
+            .add_systems(First, restore_logical)
+            .add_systems(
+                PostUpdate,
+                (interpolate, y_sort)
+                    .chain()
+                    .after(PhysicsSet::Writeback)
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+fn store_previous(mut query: Query<(&Transform, &mut Interpolated)>) {
+    for (transform, mut interpolated) in &mut query {
+        interpolated.previous = transform.translation;
+    }
+}
+
+fn interpolate(time: Res<Time<Fixed>>, mut query: Query<(&mut Transform, &mut Interpolated)>) {
+    let alpha = time.overstep_percentage();
+    for (mut transform, mut interpolated) in &mut query {
+        interpolated.logical = transform.translation;
+        transform.translation = interpolated.previous.lerp(interpolated.logical, alpha);
+    }
+}
+
+// runs after interpolation in the same frame, so freshly spawned entities
+// are sorted before they are first rendered
+fn y_sort(bounds: Res<PlayfieldBounds>, mut query: Query<&mut Transform, With<YSorted>>) {
+    for mut transform in &mut query {
+        transform.translation.z = bounds.depth(transform.translation.y);
+    }
+}
+
+fn restore_logical(mut query: Query<(&mut Transform, &Interpolated)>) {
+    for (mut transform, interpolated) in &mut query {
+        transform.translation = interpolated.logical;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{advance, headless_app};
+
+    fn z_after_a_frame_at(app: &mut App, entity: Entity, translation: Vec3) -> f32 {
+        app.world.entity_mut(entity).insert((
+            Transform::from_translation(translation),
+            Interpolated::new(translation),
+        ));
+        advance(app, 1);
+        app.world.get::<Transform>(entity).unwrap().translation.z
+    }
+
+    #[test]
+    fn reused_entity_is_sorted_on_its_first_frame() {
+        let mut app = headless_app();
+        app.add_plugins(InterpolationPlugin);
+        let entity = app.world.spawn(YSorted).id();
+        let bottom = z_after_a_frame_at(&mut app, entity, Vec3::new(0.0, -200.0, 0.0));
+
+        // picked up again elsewhere, the way a pooled entity comes back
+        let top = z_after_a_frame_at(&mut app, entity, Vec3::new(0.0, 200.0, 0.0));
+        let bounds = app.world.resource::<PlayfieldBounds>();
+        assert_eq!(bottom, bounds.depth(-200.0));
+        assert_eq!(top, bounds.depth(200.0));
+    }
+}
@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+
+use crate::data::{
+    localization::{Localization, LocalizedText},
+    state::GameState,
+    theme::Theme,
+};
+
+const OVERLAY_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.85);
+const OVERLAY_Z: i32 = 20;
+const SPINNER_SIZE: f32 = 32.0;
+// radians per second
+const SPINNER_SPEED: f32 = 4.0;
+
+pub struct LoadingScreenPlugin;
+
+#[derive(Component)]
+struct LoadingScreen;
+
+#[derive(Component)]
+struct Spinner;
+
+impl Plugin for LoadingScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Loading), spawn_loading_screen)
+            .add_systems(
+                Update,
+                spin.run_if(in_state(GameState::Loading).or_else(in_state(GameState::Waiting))),
+            )
+            .add_systems(OnEnter(GameState::MainMenu), remove_loading_screen);
+    }
+}
+
+// covers whatever setup drew underneath until everything the menu needs is in
+fn spawn_loading_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    localization: Res<Localization>,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: OVERLAY_COLOR.into(),
+                z_index: ZIndex::Global(OVERLAY_Z),
+                ..default()
+            },
+            LoadingScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                ImageBundle {
+                    style: Style {
+                        width: Val::Px(SPINNER_SIZE),
+                        height: Val::Px(SPINNER_SIZE),
+                        ..default()
+                    },
+                    image: UiImage::new(asset_server.load("sprites/hud/coin.png")),
+                    ..default()
+                },
+                Spinner,
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    localization.get("loading"),
+                    theme.hud_style(&asset_server),
+                ),
+                LocalizedText("loading"),
+            ));
+        });
+}
+
+// ui layout only moves nodes, so the rotation set here sticks
+fn spin(time: Res<Time>, mut spinners: Query<&mut Transform, With<Spinner>>) {
+    for mut transform in &mut spinners {
+        transform.rotate_z(-SPINNER_SPEED * time.delta_seconds());
+    }
+}
+
+fn remove_loading_screen(mut commands: Commands, screens: Query<Entity, With<LoadingScreen>>) {
+    for entity in &screens {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{advance, enter_state, HeadlessTestPlugin};
+
+    fn screens(app: &mut App) -> usize {
+        app.world
+            .query_filtered::<(), With<LoadingScreen>>()
+            .iter(&app.world)
+            .count()
+    }
+
+    #[test]
+    fn overlay_covers_loading_and_is_gone_by_the_menu() {
+        // built by hand, headless_app would already be past Loading
+        let mut app = App::new();
+        app.add_plugins((HeadlessTestPlugin, LoadingScreenPlugin))
+            .init_asset::<Image>();
+        advance(&mut app, 1);
+        assert_eq!(screens(&mut app), 1);
+
+        enter_state(&mut app, GameState::Waiting);
+        assert_eq!(screens(&mut app), 1);
+
+        enter_state(&mut app, GameState::MainMenu);
+        assert_eq!(screens(&mut app), 0);
+        enter_state(&mut app, GameState::GamePlay);
+        assert_eq!(screens(&mut app), 0);
+    }
+}
@@ -1,52 +1,125 @@
 #![allow(clippy::too_many_arguments, clippy::type_complexity)]
 
 mod animation;
+mod audio;
 mod data;
+#[cfg(debug_assertions)]
+mod debug;
+mod demo;
+mod effects;
 mod entities;
+mod gameover;
+mod hud;
+mod input;
+mod interpolation;
+mod loading;
+mod menu;
+mod pause;
+mod save;
+#[cfg(test)]
+mod testing;
 
 use crate::entities::enemy;
 use animation::{AnimationList, AnimationLoadPlugin};
+use audio::{MusicPlugin, SfxPlugin};
 use bevy::{asset::AssetMetaCheck, prelude::*, window::WindowTheme};
 use bevy_rapier2d::prelude::*;
-use data::state::GameState;
+use data::{
+    layout::{LayoutPlugin, ScreenLayout},
+    localization::{Localization, LocalizationPlugin, LocalizedText},
+    mode::GameMode,
+    playfield::{DefenseLine, DefenseLineSprite, PlayfieldBounds, PlayfieldOrientation},
+    rng::GameRng,
+    settings::{Difficulty, SettingsPlugin},
+    state::{advance, GameState, Transition},
+    theme::{TextScale, Theme, ThemePlugin, TitleText},
+};
+use demo::DemoPlugin;
+use effects::EffectsPlugin;
+use entities::achievement::AchievementPlugin;
+use entities::attack::AttackPlugin;
+use entities::boss::BossPlugin;
 use entities::enemy::EnemySpawnPlugin;
+use entities::hazard::HazardPlugin;
+use entities::last_stand::LastStandPlugin;
+use entities::pickup::PickupPlugin;
 use entities::player::PlayerPlugin;
+use entities::projectile::ProjectilePlugin;
+use entities::time_attack::TimeAttackPlugin;
+use entities::unlock::UnlockPlugin;
+use entities::wall::WallPlugin;
+use entities::wave::WavePlugin;
+use entities::weapon::WeaponPlugin;
+use gameover::GameOverPlugin;
+use hud::HudPlugin;
+use input::ActionPlugin;
+use interpolation::InterpolationPlugin;
+use loading::LoadingScreenPlugin;
+use menu::MenuPlugin;
+use pause::PausePlugin;
+use save::SavePlugin;
+
+#[derive(Resource)]
+pub struct CameraConfig {
+    pub menu_pos: Vec3,
+    pub gameplay_pos: Vec3,
+    pub intro_speed: f32,
+}
+
+impl Default for CameraConfig {
+    fn default() -> Self {
+        Self {
+            menu_pos: Vec3::new(-500.0, 0.0, 100.0),
+            gameplay_pos: Vec3::new(500.0, 0.0, 100.0),
+            intro_speed: 200.0,
+        }
+    }
+}
 
 #[derive(Resource)]
 pub struct GameplayStart {
     pub camera_endpos: Vec3,
-    pub player_endpos: Vec3,
     pub camera_inplace: bool,
     pub play_inplace: bool,
 }
 
 impl GameplayStart {
-    pub fn can_start(&self) -> bool {
-        self.camera_inplace && self.play_inplace
-    }
-}
-
-impl Default for GameplayStart {
-    fn default() -> Self {
+    pub fn new(camera: &CameraConfig) -> Self {
         Self {
-            camera_endpos: Vec3::new(500.0, 0.0, 0.0),
-            player_endpos: Vec3::new(150.0, 0.0, 0.0),
+            camera_endpos: camera.gameplay_pos,
             camera_inplace: false,
             play_inplace: false,
         }
     }
+
+    pub fn can_start(&self) -> bool {
+        self.camera_inplace && self.play_inplace
+    }
 }
 
 fn main() {
+    let camera = CameraConfig::default();
+    let bounds = PlayfieldBounds::default();
+    // refit once the saved orientation is known, see LayoutPlugin
+    let orientation = PlayfieldOrientation::default();
     App::new()
         .insert_resource(AssetMetaCheck::Never)
-        .insert_resource(GameplayStart::default())
+        .insert_resource(GameplayStart::new(&camera))
+        .insert_resource(DefenseLine::new(
+            camera.gameplay_pos.x,
+            &bounds,
+            orientation,
+        ))
+        .insert_resource(camera)
+        .insert_resource(bounds)
+        .init_resource::<GameMode>()
+        .init_resource::<GameRng>()
         .add_plugins((
             DefaultPlugins
                 .set(WindowPlugin {
                     primary_window: Some(Window {
                         title: "Hold The Line".into(),
-                        resolution: (800.0, 600.0).into(),
+                        resolution: Vec2::new(800.0, 600.0).into(),
                         resizable: false,
                         window_theme: Some(WindowTheme::Dark),
                         enabled_buttons: bevy::window::EnabledButtons {
@@ -58,19 +131,47 @@ fn main() {
                     ..default()
                 })
                 .set(ImagePlugin::default_nearest()),
+            ActionPlugin,
             PlayerPlugin,
             EnemySpawnPlugin,
+            WavePlugin,
+            PickupPlugin,
+            WeaponPlugin,
+            LastStandPlugin,
+            HudPlugin,
+            EffectsPlugin,
             AnimationLoadPlugin,
+            InterpolationPlugin,
+            PausePlugin,
+            LocalizationPlugin,
+            LayoutPlugin,
+        ))
+        .add_plugins((
+            MenuPlugin,
+            SettingsPlugin,
+            AttackPlugin,
+            SavePlugin,
+            UnlockPlugin,
+            WallPlugin,
+            TimeAttackPlugin,
+            ThemePlugin,
+            SfxPlugin,
+            MusicPlugin,
+            DemoPlugin,
+            GameOverPlugin,
+            AchievementPlugin,
             RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0),
             #[cfg(debug_assertions)]
-            RapierDebugRenderPlugin::default(),
+            debug::DebugPlugin,
+        ))
+        .add_plugins((
+            HazardPlugin,
+            BossPlugin,
+            ProjectilePlugin,
+            LoadingScreenPlugin,
         ))
         .add_state::<GameState>()
         .add_systems(Startup, setup)
-        .add_systems(
-            Update,
-            (main_menu_input).run_if(in_state(GameState::MainMenu)),
-        )
         .add_systems(OnExit(GameState::MainMenu), remove_text)
         .add_systems(
             Update,
@@ -86,25 +187,51 @@ fn remove_text(mut commands: Commands, query: Query<Entity, With<Text>>) {
     }
 }
 
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    text_scale: Res<TextScale>,
+    localization: Res<Localization>,
+    layout: Res<ScreenLayout>,
+    camera: Res<CameraConfig>,
+    bounds: Res<PlayfieldBounds>,
+    defense_line: Res<DefenseLine>,
+) {
     commands.spawn(Camera2dBundle {
-        transform: Transform::from_translation(Vec3::new(-500.0, 0.0, 100.0)),
+        transform: Transform::from_translation(layout.menu_pos(&camera, &defense_line)),
         ..default()
     });
 
-    commands.spawn(Text2dBundle {
-        text: Text::from_section(
-            "Hold The Line",
-            TextStyle {
-                font: asset_server.load("fonts/plop.ttf"),
-                font_size: 99.0,
-                color: Color::rgb(1.0, 1.0, 0.0),
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                localization.get("title"),
+                theme.title_style(&asset_server, &text_scale),
+            )
+            .with_alignment(TextAlignment::Center),
+            transform: Transform::from_translation(layout.title_pos(&camera, &defense_line)),
+            ..default()
+        },
+        TitleText,
+        LocalizedText("title"),
+    ));
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(1.0, 1.0, 1.0, 0.3),
+                custom_size: Some(Vec2::new(4.0, bounds.max_y - bounds.min_y)),
+                ..default()
             },
-        )
-        .with_alignment(TextAlignment::Center),
-        transform: Transform::from_translation(Vec3::new(-500.0, 200.0, 0.0)),
-        ..default()
-    });
+            transform: Transform::from_translation(Vec3::new(
+                defense_line.x,
+                (bounds.min_y + bounds.max_y) / 2.0,
+                -0.5,
+            )),
+            ..default()
+        },
+        DefenseLineSprite,
+    ));
     commands.spawn(SpriteBundle {
         texture: asset_server.load("sprites/map/map.png"),
         transform: Transform::from_scale(Vec3::new(1.25, 1.25, 1.0))
@@ -123,29 +250,101 @@ fn transition_to_gameplay(
     time: Res<Time>,
     state: Res<State<GameState>>,
     list: Res<AnimationList>,
+    config: Res<CameraConfig>,
+    difficulty: Res<Difficulty>,
+    layout: Res<ScreenLayout>,
+    defense_line: Res<DefenseLine>,
     mut gameplay_start: ResMut<GameplayStart>,
     mut next_state: ResMut<NextState<GameState>>,
     mut camera: Query<(&Camera2d, &mut Transform)>,
 ) {
     if !gameplay_start.camera_inplace {
+        let target = layout.gameplay_pos(&config, &defense_line).x;
         for (_, mut transform) in camera.iter_mut() {
-            transform.translation.x += 200.0 * time.delta_seconds();
-            if transform.translation.x >= gameplay_start.camera_endpos.x {
+            let step = config.intro_speed * difficulty.transition_speed() * time.delta_seconds();
+            let remaining = target - transform.translation.x;
+            if remaining.abs() <= step {
+                transform.translation.x = target;
                 gameplay_start.camera_inplace = true;
+            } else {
+                transform.translation.x += step * remaining.signum();
             }
         }
     }
     if gameplay_start.can_start() && list.is_loaded() {
-        next_state.set(state.transition());
+        advance(&state, &mut next_state, Transition::IntroFinished);
     }
 }
 
-fn main_menu_input(
-    input: Res<Input<KeyCode>>,
-    game_state: Res<State<GameState>>,
-    mut next_game_state: ResMut<NextState<GameState>>,
-) {
-    if input.pressed(KeyCode::Space) {
-        next_game_state.set(game_state.transition());
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::testing::{advance, advance_secs, enter_state, headless_app};
+
+    fn camera_x(app: &mut App) -> f32 {
+        app.world
+            .query_filtered::<&Transform, With<Camera2d>>()
+            .single(&app.world)
+            .translation
+            .x
+    }
+
+    // a camera set up at the menu, panning to 400 once the intro starts
+    fn intro_app(intro_speed: f32, difficulty: Difficulty) -> App {
+        let mut app = headless_app();
+        let camera = CameraConfig {
+            menu_pos: Vec3::new(-200.0, 0.0, 100.0),
+            gameplay_pos: Vec3::new(400.0, 0.0, 100.0),
+            intro_speed,
+        };
+        app.init_asset::<Image>()
+            .insert_resource(GameplayStart::new(&camera))
+            .insert_resource(camera)
+            .insert_resource(difficulty)
+            .init_resource::<TextScale>()
+            .init_resource::<AnimationList>()
+            .add_systems(
+                Update,
+                transition_to_gameplay.run_if(in_state(GameState::TransitionToGamePlay)),
+            );
+        app.world.run_system_once(setup);
+        assert_eq!(camera_x(&mut app), -200.0);
+        enter_state(&mut app, GameState::TransitionToGamePlay);
+        app
+    }
+
+    // frames until the camera settles, checking it never passes the end
+    fn frames_to_pan(difficulty: Difficulty) -> usize {
+        let mut app = intro_app(200.0, difficulty);
+        let mut frames = 0;
+        while !app.world.resource::<GameplayStart>().camera_inplace {
+            advance(&mut app, 1);
+            frames += 1;
+            assert!(camera_x(&mut app) <= 400.0);
+            assert!(frames < 1000);
+        }
+        assert_eq!(camera_x(&mut app), 400.0);
+        frames
+    }
+
+    #[test]
+    fn harder_difficulties_pan_in_quicker_and_still_stop_on_the_mark() {
+        let (easy, normal, hard) = (
+            frames_to_pan(Difficulty::Easy),
+            frames_to_pan(Difficulty::Normal),
+            frames_to_pan(Difficulty::Hard),
+        );
+        assert!(hard < normal && normal < easy);
+    }
+
+    #[test]
+    fn intro_pans_from_the_menu_to_the_gameplay_position() {
+        let mut app = intro_app(1000.0, Difficulty::Normal);
+        advance_secs(&mut app, 1.0);
+        assert_eq!(camera_x(&mut app), 400.0);
+        assert!(app.world.resource::<GameplayStart>().camera_inplace);
+        assert_eq!(app.world.resource::<GameplayStart>().camera_endpos.x, 400.0);
     }
 }
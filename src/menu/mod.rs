@@ -0,0 +1,728 @@
+use bevy::{app::AppExit, ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    data::{
+        layout::ScreenLayout,
+        localization::{Localization, LANGUAGES},
+        mode::{GameMode, RunModifiers, VILLAGER_LIMIT},
+        playfield::{DefenseLine, PlayfieldOrientation},
+        rng::{parse_seed, GameRng},
+        settings::{AimAssist, Difficulty, Endless, InvertVertical, Language, ZoomSetting},
+        state::{advance, GameState, Transition},
+        theme::TextScale,
+    },
+    demo::DemoPlayer,
+    entities::{
+        enemy::EnemyTypes,
+        time_attack::TIME_ATTACK_SECONDS,
+        unlock::Unlocks,
+        wave::{preview_wave, WaveState},
+    },
+    input::{Action, Actions, MenuHold, MenuRepeat},
+    save::{has_save, load_save, ResumeRun},
+    CameraConfig,
+};
+
+const MENU_COLOR: Color = Color::rgb(0.8, 0.8, 0.8);
+const SELECTED_COLOR: Color = Color::rgb(1.0, 1.0, 0.0);
+const MENU_FONT_SIZE: f32 = 40.0;
+const MENU_SPACING: f32 = 50.0;
+// the first row sits this far up the window, just under the title
+const MENU_TOP: f32 = 1.0 / 6.0;
+const MENU_BOTTOM_MARGIN: f32 = 30.0;
+const PREVIEW_FONT_SIZE: f32 = 24.0;
+const MAX_SEED_DIGITS: usize = 20;
+
+pub struct MenuPlugin;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MenuItem {
+    Continue,
+    Start,
+    Waves,
+    TimeAttack,
+    Villagers,
+    InvertVertical,
+    AimAssist,
+    TextScale,
+    Zoom,
+    Endless,
+    Difficulty,
+    GlassCannon,
+    Mirror,
+    Seed,
+    Language,
+    Quit,
+}
+
+impl MenuItem {
+    pub fn label(&self, settings: &MenuSettings) -> String {
+        let MenuSettings {
+            invert_vertical,
+            aim_assist,
+            text_scale,
+            zoom,
+            endless,
+            difficulty,
+            modifiers,
+            orientation,
+            seed,
+            localization,
+        } = settings;
+        let toggle = |on: bool| localization.get(if on { "on" } else { "off" });
+        match self {
+            MenuItem::Continue => localization.get("menu_continue").to_string(),
+            MenuItem::Start => localization.get("menu_start").to_string(),
+            MenuItem::Waves => localization.get("menu_waves").to_string(),
+            MenuItem::TimeAttack => localization.get("menu_time_attack").to_string(),
+            MenuItem::Villagers => localization.get("menu_villagers").to_string(),
+            MenuItem::InvertVertical => format!(
+                "{}: {}",
+                localization.get("menu_invert_vertical"),
+                toggle(invert_vertical.0)
+            ),
+            MenuItem::AimAssist => format!(
+                "{}: {}",
+                localization.get("menu_aim_assist"),
+                toggle(aim_assist.0)
+            ),
+            MenuItem::TextScale => format!(
+                "{}: {}%",
+                localization.get("menu_text_scale"),
+                (text_scale.factor() * 100.0).round()
+            ),
+            MenuItem::Zoom => format!(
+                "{}: {}%",
+                localization.get("menu_zoom"),
+                (zoom.scale() * 100.0).round()
+            ),
+            MenuItem::Endless => format!(
+                "{}: {}",
+                localization.get("menu_endless"),
+                toggle(endless.0)
+            ),
+            MenuItem::Difficulty => format!(
+                "{}: {}",
+                localization.get("menu_difficulty"),
+                localization.get(difficulty.name_key())
+            ),
+            MenuItem::GlassCannon => format!(
+                "{}: {}",
+                localization.get("menu_glass_cannon"),
+                toggle(modifiers.glass_cannon)
+            ),
+            MenuItem::Mirror => format!(
+                "{}: {}",
+                localization.get("menu_mirror"),
+                toggle(orientation.flip_x())
+            ),
+            MenuItem::Seed => format!(
+                "{}: {}",
+                localization.get("seed"),
+                if seed.0.is_empty() {
+                    localization.get("seed_random")
+                } else {
+                    seed.0.as_str()
+                }
+            ),
+            MenuItem::Language => format!(
+                "{}: {}",
+                localization.get("menu_language"),
+                localization.get("language_name")
+            ),
+            MenuItem::Quit => localization.get("menu_quit").to_string(),
+        }
+    }
+
+    // what a run started from this item plays, items that don't start one
+    // get the plain mode
+    pub fn mode(&self) -> GameMode {
+        match self {
+            MenuItem::Waves => GameMode {
+                waves: true,
+                ..default()
+            },
+            MenuItem::TimeAttack => GameMode {
+                time_attack: Some(TIME_ATTACK_SECONDS),
+                ..default()
+            },
+            MenuItem::Villagers => GameMode {
+                villager_limit: Some(VILLAGER_LIMIT),
+                ..default()
+            },
+            _ => GameMode::default(),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct MenuSelection {
+    pub items: Vec<MenuItem>,
+    pub selected: usize,
+}
+
+impl Default for MenuSelection {
+    fn default() -> Self {
+        Self {
+            items: vec![
+                MenuItem::Start,
+                MenuItem::Waves,
+                MenuItem::TimeAttack,
+                MenuItem::Villagers,
+                MenuItem::InvertVertical,
+                MenuItem::AimAssist,
+                MenuItem::TextScale,
+                MenuItem::Zoom,
+                MenuItem::Endless,
+                MenuItem::Difficulty,
+                MenuItem::GlassCannon,
+                MenuItem::Mirror,
+                MenuItem::Seed,
+                MenuItem::Language,
+                MenuItem::Quit,
+            ],
+            selected: 0,
+        }
+    }
+}
+
+impl MenuSelection {
+    pub fn next(&mut self) {
+        self.selected = (self.selected + 1) % self.items.len();
+    }
+
+    pub fn previous(&mut self) {
+        self.selected = (self.selected + self.items.len() - 1) % self.items.len();
+    }
+
+    pub fn current(&self) -> MenuItem {
+        self.items[self.selected]
+    }
+
+    // the first of `rows` items on screen, chosen to keep the selected one
+    // near the middle
+    pub fn first_visible(&self, rows: usize) -> usize {
+        self.selected
+            .saturating_sub(rows / 2)
+            .min(self.items.len().saturating_sub(rows))
+    }
+}
+
+fn row_spacing(text_scale: &TextScale) -> f32 {
+    MENU_SPACING * text_scale.factor()
+}
+
+// the wave preview runs along the bottom of the window
+fn preview_y(layout: &ScreenLayout) -> f32 {
+    -layout.size.y / 2.0 + MENU_BOTTOM_MARGIN
+}
+
+// how many items fit between the title and the wave preview, any more scroll
+fn visible_rows(layout: &ScreenLayout, text_scale: &TextScale) -> usize {
+    let top = layout.size.y * MENU_TOP;
+    let bottom = preview_y(layout) + text_scale.font_size(PREVIEW_FONT_SIZE);
+    (((top - bottom) / row_spacing(text_scale)) as usize).max(1)
+}
+
+// everything a menu label shows
+#[derive(SystemParam)]
+pub struct MenuSettings<'w> {
+    invert_vertical: Res<'w, InvertVertical>,
+    aim_assist: Res<'w, AimAssist>,
+    text_scale: Res<'w, TextScale>,
+    zoom: Res<'w, ZoomSetting>,
+    endless: Res<'w, Endless>,
+    difficulty: Res<'w, Difficulty>,
+    modifiers: Res<'w, RunModifiers>,
+    orientation: Res<'w, PlayfieldOrientation>,
+    seed: Res<'w, SeedEntry>,
+    localization: Res<'w, Localization>,
+}
+
+// digits typed while the seed item is selected, shared so a run can be replayed
+#[derive(Resource, Default)]
+pub struct SeedEntry(pub String);
+
+#[derive(Component)]
+struct MenuText(usize);
+
+// what the first wave holds for the typed seed, below the menu items
+#[derive(Component)]
+struct WavePreview;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuSelection>()
+            .init_resource::<SeedEntry>()
+            .init_resource::<RunModifiers>()
+            .add_systems(
+                OnEnter(GameState::MainMenu),
+                (reset_modifiers, spawn_menu, spawn_wave_preview).chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    navigate_menu,
+                    type_seed,
+                    activate_menu,
+                    highlight_menu,
+                    relabel_menu.run_if(
+                        resource_changed::<InvertVertical>()
+                            .or_else(resource_changed::<AimAssist>())
+                            .or_else(resource_changed::<TextScale>())
+                            .or_else(resource_changed::<ZoomSetting>())
+                            .or_else(resource_changed::<Endless>())
+                            .or_else(resource_changed::<Difficulty>())
+                            .or_else(resource_changed::<RunModifiers>())
+                            .or_else(resource_changed::<PlayfieldOrientation>())
+                            .or_else(resource_changed::<SeedEntry>())
+                            .or_else(resource_changed::<Localization>()),
+                    ),
+                    update_wave_preview,
+                    place_menu.run_if(
+                        resource_changed::<MenuSelection>()
+                            .or_else(resource_changed::<TextScale>())
+                            .or_else(resource_changed::<ScreenLayout>())
+                            .or_else(resource_changed::<DefenseLine>()),
+                    ),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::MainMenu)),
+            );
+    }
+}
+
+fn spawn_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: MenuSettings,
+    mut selection: ResMut<MenuSelection>,
+) {
+    let text_scale = &settings.text_scale;
+    *selection = MenuSelection::default();
+    if has_save() {
+        selection.items.insert(0, MenuItem::Continue);
+    }
+    for (i, item) in selection.items.iter().enumerate() {
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    item.label(&settings),
+                    TextStyle {
+                        font: asset_server.load("fonts/plop.ttf"),
+                        font_size: text_scale.font_size(MENU_FONT_SIZE),
+                        color: MENU_COLOR,
+                    },
+                )
+                .with_alignment(TextAlignment::Center),
+                // placed by place_menu before the first frame is drawn
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            MenuText(i),
+        ));
+    }
+}
+
+fn reset_modifiers(mut modifiers: ResMut<RunModifiers>) {
+    *modifiers = RunModifiers::default();
+}
+
+fn navigate_menu(
+    actions: Actions,
+    time: Res<Time<Real>>,
+    repeat: Res<MenuRepeat>,
+    mut hold: Local<MenuHold>,
+    mut selection: ResMut<MenuSelection>,
+) {
+    match hold.step(&actions, &repeat, time.delta_seconds()) {
+        Some(Action::MoveDown) => selection.next(),
+        Some(Action::MoveUp) => selection.previous(),
+        _ => {}
+    }
+}
+
+fn type_seed(
+    keys: Res<Input<KeyCode>>,
+    mut typed: EventReader<ReceivedCharacter>,
+    selection: Res<MenuSelection>,
+    mut seed: ResMut<SeedEntry>,
+) {
+    if selection.current() != MenuItem::Seed {
+        typed.clear();
+        return;
+    }
+    for event in typed.read() {
+        if event.char.is_ascii_digit() && seed.0.len() < MAX_SEED_DIGITS {
+            seed.0.push(event.char);
+        }
+    }
+    if keys.just_pressed(KeyCode::Back) {
+        seed.0.pop();
+    }
+}
+
+// a blank entry rolls a fresh random seed for the run. a demo being played
+// back keeps the seed it was recorded with whatever was typed
+fn apply_seed(commands: &mut Commands, seed: &SeedEntry, replaying: bool) {
+    if replaying {
+        return;
+    }
+    let rng = match parse_seed(&seed.0) {
+        Some(seed) => GameRng::from_seed(seed),
+        None => {
+            if !seed.0.trim().is_empty() {
+                warn!("seed `{}` is not a valid seed, using a random one", seed.0);
+            }
+            GameRng::default()
+        }
+    };
+    commands.insert_resource(rng);
+}
+
+fn activate_menu(
+    mut commands: Commands,
+    actions: Actions,
+    selection: Res<MenuSelection>,
+    game_state: Res<State<GameState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut resume: ResMut<ResumeRun>,
+    mut mode: ResMut<GameMode>,
+    (mut invert_vertical, mut aim_assist, mut text_scale, mut zoom, mut endless): (
+        ResMut<InvertVertical>,
+        ResMut<AimAssist>,
+        ResMut<TextScale>,
+        ResMut<ZoomSetting>,
+        ResMut<Endless>,
+    ),
+    mut difficulty: ResMut<Difficulty>,
+    mut modifiers: ResMut<RunModifiers>,
+    mut orientation: ResMut<PlayfieldOrientation>,
+    seed: Res<SeedEntry>,
+    demo: Option<Res<DemoPlayer>>,
+    mut language: ResMut<Language>,
+    mut app_exit: EventWriter<AppExit>,
+) {
+    if !actions.just_pressed(Action::Confirm) {
+        return;
+    }
+    match selection.current() {
+        MenuItem::Continue => {
+            // the mode is back before the run starts, so everything set up
+            // from it on the way in matches the saved run
+            resume.save = load_save();
+            if let Some(save) = &resume.save {
+                *mode = save.mode.clone();
+            }
+            advance(&game_state, &mut next_game_state, Transition::StartRun);
+        }
+        item @ (MenuItem::Start | MenuItem::Waves | MenuItem::TimeAttack | MenuItem::Villagers) => {
+            apply_seed(&mut commands, &seed, demo.is_some());
+            *mode = item.mode();
+            advance(&game_state, &mut next_game_state, Transition::StartRun);
+        }
+        MenuItem::InvertVertical => invert_vertical.0 = !invert_vertical.0,
+        MenuItem::AimAssist => aim_assist.0 = !aim_assist.0,
+        MenuItem::TextScale => text_scale.setting = text_scale.next_step(),
+        MenuItem::Zoom => zoom.0 = zoom.next_step(),
+        MenuItem::Endless => endless.0 = !endless.0,
+        MenuItem::Difficulty => *difficulty = difficulty.next(),
+        MenuItem::GlassCannon => modifiers.glass_cannon = !modifiers.glass_cannon,
+        MenuItem::Mirror => *orientation = orientation.flipped(),
+        MenuItem::Seed => {}
+        MenuItem::Language => {
+            let current = LANGUAGES.iter().position(|lang| *lang == language.0);
+            let next = current.map_or(0, |i| (i + 1) % LANGUAGES.len());
+            language.0 = LANGUAGES[next].to_string();
+        }
+        MenuItem::Quit => app_exit.send(AppExit),
+    }
+}
+
+fn highlight_menu(selection: Res<MenuSelection>, mut texts: Query<(&MenuText, &mut Text)>) {
+    if !selection.is_changed() {
+        return;
+    }
+    for (item, mut text) in &mut texts {
+        text.sections[0].style.color = if item.0 == selection.selected {
+            SELECTED_COLOR
+        } else {
+            MENU_COLOR
+        };
+    }
+}
+
+fn relabel_menu(
+    selection: Res<MenuSelection>,
+    settings: MenuSettings,
+    mut texts: Query<(&MenuText, &mut Text)>,
+) {
+    for (item, mut text) in &mut texts {
+        text.sections[0].value = selection.items[item.0].label(&settings);
+    }
+}
+
+fn place_menu(
+    text_scale: Res<TextScale>,
+    layout: Res<ScreenLayout>,
+    camera: Res<CameraConfig>,
+    defense_line: Res<DefenseLine>,
+    selection: Res<MenuSelection>,
+    mut texts: Query<(&MenuText, &mut Text, &mut Transform, &mut Visibility), Without<WavePreview>>,
+    mut previews: Query<(&mut Text, &mut Transform), With<WavePreview>>,
+) {
+    let menu_x = layout.menu_pos(&camera, &defense_line).x;
+    let rows = visible_rows(&layout, &text_scale);
+    let first = selection.first_visible(rows);
+    for (item, mut text, mut transform, mut visibility) in &mut texts {
+        let row = item.0 as f32 - first as f32;
+        text.sections[0].style.font_size = text_scale.font_size(MENU_FONT_SIZE);
+        transform.translation.x = menu_x;
+        transform.translation.y = layout.size.y * MENU_TOP - row_spacing(&text_scale) * row;
+        *visibility = if (first..first + rows).contains(&item.0) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+    for (mut text, mut transform) in &mut previews {
+        text.sections[0].style.font_size = text_scale.font_size(PREVIEW_FONT_SIZE);
+        transform.translation.x = menu_x;
+        transform.translation.y = preview_y(&layout);
+    }
+}
+
+fn spawn_wave_preview(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    text_scale: Res<TextScale>,
+    layout: Res<ScreenLayout>,
+    camera: Res<CameraConfig>,
+    defense_line: Res<DefenseLine>,
+) {
+    let menu_x = layout.menu_pos(&camera, &defense_line).x;
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/plop.ttf"),
+                    font_size: text_scale.font_size(PREVIEW_FONT_SIZE),
+                    color: MENU_COLOR,
+                },
+            )
+            .with_alignment(TextAlignment::Center),
+            transform: Transform::from_translation(Vec3::new(menu_x, preview_y(&layout), 0.0)),
+            ..default()
+        },
+        WavePreview,
+    ));
+}
+
+// only a typed seed is known ahead of time, a random one shows nothing
+fn update_wave_preview(
+    seed: Res<SeedEntry>,
+    difficulty: Res<Difficulty>,
+    localization: Res<Localization>,
+    waves: Res<WaveState>,
+    enemy_types: Res<EnemyTypes>,
+    unlocks: Res<Unlocks>,
+    mut previews: Query<(Ref<WavePreview>, &mut Text)>,
+) {
+    for (preview, mut text) in &mut previews {
+        if !(preview.is_added()
+            || seed.is_changed()
+            || difficulty.is_changed()
+            || localization.is_changed())
+        {
+            continue;
+        }
+        text.sections[0].value = match parse_seed(&seed.0) {
+            Some(seed) => {
+                let lineup = preview_wave(seed, *difficulty, 0, &waves, &enemy_types, &unlocks)
+                    .into_iter()
+                    .map(|(name, count)| format!("{} {}", count, name))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{} 1: {}", localization.get("wave_preview"), lineup)
+            }
+            None => String::new(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::{
+        input::MenuRepeat,
+        testing::{advance, advance_secs, enter_state, headless_app, press, release},
+    };
+
+    fn menu_app() -> App {
+        let mut app = headless_app();
+        app.add_event::<ReceivedCharacter>()
+            .init_resource::<InvertVertical>()
+            .init_resource::<AimAssist>()
+            .init_resource::<TextScale>()
+            .insert_resource(ZoomSetting(1.0))
+            .insert_resource(Language("en".to_string()))
+            .init_resource::<ResumeRun>()
+            .init_resource::<MenuRepeat>()
+            .add_plugins(MenuPlugin);
+        enter_state(&mut app, GameState::MainMenu);
+        app
+    }
+
+    fn tap(app: &mut App, key: KeyCode) {
+        press(app, key);
+        advance(app, 1);
+        release(app, key);
+        advance(app, 1);
+    }
+
+    fn selected(app: &App) -> usize {
+        app.world.resource::<MenuSelection>().selected
+    }
+
+    #[test]
+    fn arrows_wrap_around_the_menu() {
+        let mut app = menu_app();
+        let last = app.world.resource::<MenuSelection>().items.len() - 1;
+
+        tap(&mut app, KeyCode::Up);
+        assert_eq!(selected(&app), last);
+        tap(&mut app, KeyCode::Down);
+        assert_eq!(selected(&app), 0);
+        tap(&mut app, KeyCode::Down);
+        assert_eq!(selected(&app), 1);
+    }
+
+    #[test]
+    fn a_tap_moves_once_and_holding_repeats_after_the_delay() {
+        let mut app = menu_app();
+        let count = app.world.resource::<MenuSelection>().items.len();
+        let repeat = MenuRepeat::default();
+
+        tap(&mut app, KeyCode::Down);
+        advance(&mut app, 30);
+        assert_eq!(selected(&app), 1);
+
+        press(&mut app, KeyCode::Down);
+        advance(&mut app, 1);
+        // still held, just no longer a fresh press
+        app.world.resource_mut::<Input<KeyCode>>().clear();
+        assert_eq!(selected(&app), 2 % count);
+        advance_secs(&mut app, repeat.initial_delay - 0.05);
+        assert_eq!(selected(&app), 2 % count);
+        advance_secs(&mut app, 0.1);
+        assert_eq!(selected(&app), 3 % count);
+        advance_secs(&mut app, repeat.repeat_rate);
+        assert_eq!(selected(&app), 4 % count);
+
+        // letting go and holding again waits out the whole delay once more
+        release(&mut app, KeyCode::Down);
+        advance(&mut app, 1);
+        press(&mut app, KeyCode::Down);
+        advance(&mut app, 1);
+        app.world.resource_mut::<Input<KeyCode>>().clear();
+        assert_eq!(selected(&app), 5 % count);
+        advance_secs(&mut app, repeat.initial_delay - 0.05);
+        assert_eq!(selected(&app), 5 % count);
+    }
+
+    #[test]
+    fn confirming_start_begins_the_run() {
+        let mut app = menu_app();
+        assert!(app.world.resource::<MenuSelection>().current() == MenuItem::Start);
+        tap(&mut app, KeyCode::Return);
+
+        assert_eq!(
+            *app.world.resource::<State<GameState>>().get(),
+            GameState::TransitionToGamePlay
+        );
+    }
+
+    #[test]
+    fn selected_item_is_always_on_screen() {
+        let mut app = menu_app();
+        let half_height = app.world.resource::<ScreenLayout>().size.y / 2.0;
+        let count = app.world.resource::<MenuSelection>().items.len();
+        for i in 0..count {
+            app.world.resource_mut::<MenuSelection>().selected = i;
+            advance(&mut app, 1);
+            let shown = app
+                .world
+                .query::<(&MenuText, &Transform, &Visibility)>()
+                .iter(&app.world)
+                .filter(|(_, _, visibility)| **visibility != Visibility::Hidden)
+                .map(|(item, transform, _)| (item.0, transform.translation.y))
+                .collect::<Vec<_>>();
+            assert!(shown.iter().any(|(item, _)| *item == i));
+            assert!(shown
+                .iter()
+                .all(|(_, y)| y.abs() + MENU_FONT_SIZE / 2.0 <= half_height));
+        }
+    }
+
+    #[test]
+    fn wave_preview_is_on_screen_below_the_menu() {
+        let mut app = menu_app();
+        app.world.resource_mut::<SeedEntry>().0 = "42".to_string();
+        advance(&mut app, 1);
+        let half_height = app.world.resource::<ScreenLayout>().size.y / 2.0;
+        let (preview, preview_y) = app
+            .world
+            .query_filtered::<(&Text, &Transform), With<WavePreview>>()
+            .iter(&app.world)
+            .map(|(text, transform)| (text.sections[0].value.clone(), transform.translation.y))
+            .next()
+            .unwrap();
+        assert!(!preview.is_empty());
+        assert!(preview_y.abs() + PREVIEW_FONT_SIZE / 2.0 <= half_height);
+
+        let lowest = app
+            .world
+            .query::<(&MenuText, &Transform, &Visibility)>()
+            .iter(&app.world)
+            .filter(|(_, _, visibility)| **visibility != Visibility::Hidden)
+            .map(|(_, transform, _)| transform.translation.y)
+            .fold(f32::INFINITY, f32::min);
+        assert!(lowest - MENU_FONT_SIZE / 2.0 > preview_y + PREVIEW_FONT_SIZE / 2.0);
+    }
+
+    fn start_with_seed(entry: &str) -> GameRng {
+        let mut app = menu_app();
+        let items = app.world.resource::<MenuSelection>().items.clone();
+        let at = |item| items.iter().position(|i| *i == item).unwrap();
+        app.world.resource_mut::<MenuSelection>().selected = at(MenuItem::Seed);
+        let window = app.world.spawn_empty().id();
+        for char in entry.chars() {
+            app.world.send_event(ReceivedCharacter { window, char });
+        }
+        advance(&mut app, 1);
+        assert_eq!(app.world.resource::<SeedEntry>().0, entry);
+
+        app.world.resource_mut::<MenuSelection>().selected = at(MenuItem::Start);
+        tap(&mut app, KeyCode::Return);
+        app.world.remove_resource::<GameRng>().unwrap()
+    }
+
+    #[test]
+    fn an_entered_seed_gives_that_seeds_sequence() {
+        let mut rng = start_with_seed("12345");
+        assert_eq!(rng.seed, 12345);
+        let mut expected = GameRng::from_seed(12345);
+        for _ in 0..5 {
+            assert_eq!(rng.rng().gen::<u64>(), expected.rng().gen::<u64>());
+        }
+    }
+
+    #[test]
+    fn a_blank_seed_rolls_a_fresh_one() {
+        // the harness starts every app on seed 0
+        assert_ne!(start_with_seed("").seed, 0);
+    }
+}
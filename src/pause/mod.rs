@@ -0,0 +1,349 @@
+use std::{marker::PhantomData, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::{
+    data::{
+        localization::{Localization, LocalizedText},
+        rng::GameRng,
+        state::{advance, GameState, Transition},
+    },
+    input::{Action, Actions, MenuHold, MenuRepeat},
+};
+
+const MENU_COLOR: Color = Color::rgb(0.8, 0.8, 0.8);
+const SELECTED_COLOR: Color = Color::rgb(1.0, 1.0, 0.0);
+
+pub struct PausePlugin;
+
+pub trait PausableTimers: Resource {
+    fn timers_mut(&mut self) -> Vec<&mut Timer>;
+}
+
+#[derive(Resource)]
+struct TimerSnapshot<R> {
+    elapsed: Vec<Duration>,
+    marker: PhantomData<fn() -> R>,
+}
+
+// set when restart is picked from the pause menu, everything in RestartSet
+// runs on the way back into GamePlay and then it is cleared again
+#[derive(Resource, Default)]
+pub struct RestartRun(pub bool);
+
+pub fn restart_requested(restart: Res<RestartRun>) -> bool {
+    restart.0
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RestartSet;
+
+pub trait PauseAppExt {
+    fn preserve_timers_on_pause<R: PausableTimers>(&mut self) -> &mut Self;
+    fn reset_on_restart<R: Resource + Default>(&mut self) -> &mut Self;
+}
+
+impl PauseAppExt for App {
+    fn preserve_timers_on_pause<R: PausableTimers>(&mut self) -> &mut Self {
+        self.add_systems(OnEnter(GameState::Pause), snapshot_timers::<R>)
+            .add_systems(OnExit(GameState::Pause), restore_timers::<R>)
+    }
+
+    fn reset_on_restart<R: Resource + Default>(&mut self) -> &mut Self {
+        self.add_systems(
+            OnEnter(GameState::GamePlay),
+            reset_resource::<R>.in_set(RestartSet),
+        )
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PauseItem {
+    Resume,
+    Restart,
+}
+
+impl PauseItem {
+    const ALL: [PauseItem; 2] = [PauseItem::Resume, PauseItem::Restart];
+
+    fn key(&self) -> &'static str {
+        match self {
+            PauseItem::Resume => "pause_resume",
+            PauseItem::Restart => "pause_restart",
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+struct PauseSelection(usize);
+
+#[derive(Component)]
+struct PausedText;
+
+#[derive(Component)]
+struct PauseMenuText(usize);
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RestartRun>()
+            .init_resource::<PauseSelection>()
+            .add_systems(
+                Update,
+                toggle_pause
+                    .run_if(in_state(GameState::GamePlay).or_else(in_state(GameState::Pause))),
+            )
+            .add_systems(
+                Update,
+                (
+                    navigate_pause_menu,
+                    activate_pause_menu,
+                    highlight_pause_menu,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Pause)),
+            )
+            .add_systems(OnEnter(GameState::Pause), pause_time)
+            .add_systems(OnExit(GameState::Pause), resume_time)
+            .configure_sets(
+                OnEnter(GameState::GamePlay),
+                RestartSet.run_if(restart_requested),
+            )
+            .add_systems(
+                OnEnter(GameState::GamePlay),
+                finish_restart.after(RestartSet),
+            );
+    }
+}
+
+fn toggle_pause(
+    actions: Actions,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let paused = *state.get() == GameState::Pause;
+    if actions.just_pressed(Action::Pause) || (paused && actions.just_pressed(Action::Back)) {
+        let transition = if paused {
+            Transition::Resume
+        } else {
+            Transition::Pause
+        };
+        advance(&state, &mut next_state, transition);
+    }
+}
+
+fn pause_time(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    localization: Res<Localization>,
+    rng: Res<GameRng>,
+    mut selection: ResMut<PauseSelection>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    time.pause();
+    selection.0 = 0;
+    for (i, item) in PauseItem::ALL.iter().enumerate() {
+        commands.spawn((
+            TextBundle::from_section(
+                localization.get(item.key()),
+                TextStyle {
+                    font: asset_server.load("fonts/plop.ttf"),
+                    font_size: 35.0,
+                    color: if i == 0 { SELECTED_COLOR } else { MENU_COLOR },
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(52.0 + 8.0 * i as f32),
+                left: Val::Percent(40.0),
+                ..default()
+            }),
+            PausedText,
+            PauseMenuText(i),
+            LocalizedText(item.key()),
+        ));
+    }
+    commands.spawn((
+        TextBundle::from_section(
+            format!("{}: {}", localization.get("seed"), rng.seed),
+            TextStyle {
+                font: asset_server.load("fonts/plop.ttf"),
+                font_size: 30.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Percent(10.0),
+            left: Val::Percent(40.0),
+            ..default()
+        }),
+        PausedText,
+    ));
+    commands.spawn((
+        TextBundle::from_section(
+            localization.get("paused"),
+            TextStyle {
+                font: asset_server.load("fonts/plop.ttf"),
+                font_size: 50.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(40.0),
+            left: Val::Percent(40.0),
+            ..default()
+        }),
+        PausedText,
+        LocalizedText("paused"),
+    ));
+}
+
+fn resume_time(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    text: Query<Entity, With<PausedText>>,
+) {
+    time.unpause();
+    for entity in text.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// virtual time is stopped while paused, so the repeat runs on real time
+fn navigate_pause_menu(
+    actions: Actions,
+    time: Res<Time<Real>>,
+    repeat: Res<MenuRepeat>,
+    mut hold: Local<MenuHold>,
+    mut selection: ResMut<PauseSelection>,
+) {
+    let count = PauseItem::ALL.len();
+    match hold.step(&actions, &repeat, time.delta_seconds()) {
+        Some(Action::MoveDown) => selection.0 = (selection.0 + 1) % count,
+        Some(Action::MoveUp) => selection.0 = (selection.0 + count - 1) % count,
+        _ => {}
+    }
+}
+
+fn activate_pause_menu(
+    actions: Actions,
+    selection: Res<PauseSelection>,
+    mut restart: ResMut<RestartRun>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !actions.just_pressed(Action::Confirm) {
+        return;
+    }
+    if PauseItem::ALL[selection.0] == PauseItem::Restart {
+        restart.0 = true;
+    }
+    advance(&state, &mut next_state, Transition::Resume);
+}
+
+fn highlight_pause_menu(
+    selection: Res<PauseSelection>,
+    mut texts: Query<(&PauseMenuText, &mut Text)>,
+) {
+    if !selection.is_changed() {
+        return;
+    }
+    for (item, mut text) in &mut texts {
+        text.sections[0].style.color = if item.0 == selection.0 {
+            SELECTED_COLOR
+        } else {
+            MENU_COLOR
+        };
+    }
+}
+
+fn reset_resource<R: Resource + Default>(mut resource: ResMut<R>) {
+    *resource = R::default();
+}
+
+fn finish_restart(mut restart: ResMut<RestartRun>) {
+    restart.0 = false;
+}
+
+fn snapshot_timers<R: PausableTimers>(mut commands: Commands, mut resource: ResMut<R>) {
+    let elapsed = resource
+        .timers_mut()
+        .into_iter()
+        .map(|timer| timer.elapsed())
+        .collect();
+    commands.insert_resource(TimerSnapshot::<R> {
+        elapsed,
+        marker: PhantomData,
+    });
+}
+
+fn restore_timers<R: PausableTimers>(
+    mut commands: Commands,
+    snapshot: Option<Res<TimerSnapshot<R>>>,
+    mut resource: ResMut<R>,
+) {
+    if let Some(snapshot) = snapshot {
+        for (timer, elapsed) in resource.timers_mut().into_iter().zip(&snapshot.elapsed) {
+            timer.set_elapsed(*elapsed);
+        }
+        commands.remove_resource::<TimerSnapshot<R>>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entities::{player::GameStats, wave::WaveState},
+        testing::{advance, enter_state, headless_app, press},
+    };
+
+    fn app_with_restart() -> App {
+        let mut app = headless_app();
+        app.reset_on_restart::<GameStats>()
+            .reset_on_restart::<WaveState>()
+            .add_systems(
+                OnEnter(GameState::GamePlay),
+                finish_restart.after(RestartSet),
+            );
+        app.world.resource_mut::<GameStats>().enemies_killed = 12;
+        app.world.resource_mut::<WaveState>().current = 2;
+        enter_state(&mut app, GameState::Pause);
+        app
+    }
+
+    #[test]
+    fn restart_resets_run_state() {
+        let mut app = app_with_restart();
+        app.world.resource_mut::<RestartRun>().0 = true;
+        enter_state(&mut app, GameState::GamePlay);
+
+        assert_eq!(app.world.resource::<GameStats>().enemies_killed, 0);
+        assert_eq!(app.world.resource::<WaveState>().current, 0);
+        assert!(!app.world.resource::<RestartRun>().0);
+    }
+
+    #[test]
+    fn resuming_keeps_run_state() {
+        let mut app = app_with_restart();
+        enter_state(&mut app, GameState::GamePlay);
+
+        assert_eq!(app.world.resource::<GameStats>().enemies_killed, 12);
+        assert_eq!(app.world.resource::<WaveState>().current, 2);
+    }
+
+    #[test]
+    fn back_closes_the_pause_menu() {
+        let mut app = headless_app();
+        app.add_systems(Update, toggle_pause);
+        enter_state(&mut app, GameState::Pause);
+        press(&mut app, KeyCode::Back);
+        advance(&mut app, 2);
+
+        assert_eq!(
+            *app.world.resource::<State<GameState>>().get(),
+            GameState::GamePlay
+        );
+    }
+}
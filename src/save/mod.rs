@@ -0,0 +1,350 @@
+use bevy::{app::AppExit, ecs::system::SystemParam, prelude::*, window::WindowCloseRequested};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    animation::{AnimationComponent, EnemyAnimations},
+    data::{
+        mode::GameMode, persist, playfield::PlayfieldOrientation, settings::Endless,
+        state::GameState,
+    },
+    entities::{
+        enemy::{spawn_named_enemy, Enemy, EnemyTypes},
+        player::{ElapsedGameplay, GameStats, PlayerData, Shield},
+        time_attack::TimeAttack,
+        wall::Wall,
+        wave::WaveState,
+    },
+    input::{Action, Actions},
+};
+
+const SAVE_FILE: &str = "run.json";
+const SUMMARY_FILE: &str = "runs.json";
+const SAVE_VERSION: u32 = 2;
+
+pub struct SavePlugin;
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedEnemy {
+    pub name: String,
+    pub x: f32,
+    pub y: f32,
+    pub health: i32,
+    pub speed: f32,
+    pub reward: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SaveGame {
+    pub version: u32,
+    pub health: i32,
+    pub max_health: i32,
+    pub lives: i32,
+    pub shield_charges: i32,
+    pub wall_hp: i32,
+    pub stats: GameStats,
+    pub elapsed: f32,
+    pub mode: GameMode,
+    pub time_left: f32,
+    pub wave: usize,
+    pub wave_spawned: usize,
+    pub enemies: Vec<SavedEnemy>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RunOutcome {
+    Aborted,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RunSummary {
+    pub stats: GameStats,
+    pub elapsed: f32,
+    pub outcome: RunOutcome,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct RunHistory {
+    pub runs: Vec<RunSummary>,
+}
+
+#[derive(Resource, Default)]
+struct SummaryWritten(bool);
+
+#[derive(Resource, Default)]
+pub struct ResumeRun {
+    pub save: Option<SaveGame>,
+}
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ResumeRun>()
+            .init_resource::<SummaryWritten>()
+            .add_systems(Update, save_and_quit.run_if(in_state(GameState::Pause)))
+            .add_systems(Update, record_aborted_run.run_if(run_in_progress))
+            .add_systems(OnExit(GameState::TransitionToGamePlay), resume_run);
+    }
+}
+
+pub fn has_save() -> bool {
+    persist::exists(SAVE_FILE) && load_save().is_some()
+}
+
+pub fn load_save() -> Option<SaveGame> {
+    let save = persist::load::<Option<SaveGame>>(SAVE_FILE)?;
+    if save.version != SAVE_VERSION {
+        warn!(
+            "save is version {}, expected {}, starting a new run",
+            save.version, SAVE_VERSION
+        );
+        return None;
+    }
+    Some(save)
+}
+
+pub fn record_run(stats: &GameStats, elapsed: &ElapsedGameplay, outcome: RunOutcome) {
+    let mut history = persist::load::<RunHistory>(SUMMARY_FILE);
+    history.runs.push(RunSummary {
+        stats: stats.clone(),
+        elapsed: elapsed.seconds,
+        outcome,
+    });
+    persist::save(SUMMARY_FILE, &history);
+}
+
+// from the camera pan on the way in until the run is over. the death sequence
+// plays out inside GamePlay, so a close during it still counts
+fn run_in_progress(state: Res<State<GameState>>) -> bool {
+    matches!(
+        state.get(),
+        GameState::TransitionToGamePlay | GameState::GamePlay | GameState::Pause
+    )
+}
+
+fn record_aborted_run(
+    mut close_requests: EventReader<WindowCloseRequested>,
+    stats: Res<GameStats>,
+    elapsed: Res<ElapsedGameplay>,
+    mut written: ResMut<SummaryWritten>,
+) {
+    if close_requests.read().count() == 0 || written.0 {
+        return;
+    }
+    written.0 = true;
+    record_run(&stats, &elapsed, RunOutcome::Aborted);
+}
+
+// everything a run needs to carry on where it was left
+#[derive(SystemParam)]
+struct RunSnapshot<'w, 's> {
+    player_data: Res<'w, PlayerData>,
+    shield: Res<'w, Shield>,
+    wall: Res<'w, Wall>,
+    stats: Res<'w, GameStats>,
+    elapsed: Res<'w, ElapsedGameplay>,
+    mode: Res<'w, GameMode>,
+    time_attack: Res<'w, TimeAttack>,
+    waves: Res<'w, WaveState>,
+    enemies: Query<
+        'w,
+        's,
+        (
+            &'static Enemy,
+            &'static Transform,
+            &'static AnimationComponent,
+        ),
+    >,
+}
+
+impl RunSnapshot<'_, '_> {
+    fn save(&self) -> SaveGame {
+        SaveGame {
+            version: SAVE_VERSION,
+            health: self.player_data.health,
+            max_health: self.player_data.max_health,
+            lives: self.player_data.lives,
+            shield_charges: self.shield.charges,
+            wall_hp: self.wall.hp,
+            stats: self.stats.clone(),
+            elapsed: self.elapsed.seconds,
+            mode: self.mode.clone(),
+            time_left: self.time_attack.remaining,
+            wave: self.waves.current,
+            wave_spawned: self.waves.spawned,
+            enemies: self
+                .enemies
+                .iter()
+                .filter(|(_, _, anim)| !anim.state.is_dying())
+                .map(|(enemy, transform, _)| SavedEnemy {
+                    name: enemy.name.clone(),
+                    x: transform.translation.x,
+                    y: transform.translation.y,
+                    health: enemy.health,
+                    speed: enemy.speed,
+                    reward: enemy.reward,
+                })
+                .collect(),
+        }
+    }
+}
+
+fn save_and_quit(actions: Actions, snapshot: RunSnapshot, mut app_exit: EventWriter<AppExit>) {
+    if !actions.just_pressed(Action::Quit) {
+        return;
+    }
+    persist::save(SAVE_FILE, &snapshot.save());
+    app_exit.send(AppExit);
+}
+
+fn resume_run(
+    mut commands: Commands,
+    mut resume: ResMut<ResumeRun>,
+    enemy_anims: Res<EnemyAnimations>,
+    enemy_types: Res<EnemyTypes>,
+    orientation: Res<PlayfieldOrientation>,
+    mut player_data: ResMut<PlayerData>,
+    mut shield: ResMut<Shield>,
+    mut wall: ResMut<Wall>,
+    mut stats: ResMut<GameStats>,
+    mut elapsed: ResMut<ElapsedGameplay>,
+    mut time_attack: ResMut<TimeAttack>,
+    mut waves: ResMut<WaveState>,
+    endless: Res<Endless>,
+) {
+    let Some(save) = resume.save.take() else {
+        return;
+    };
+    persist::delete(SAVE_FILE);
+    player_data.max_health = save.max_health;
+    player_data.health = save.health;
+    player_data.lives = save.lives;
+    shield.charges = save.shield_charges;
+    wall.hp = save.wall_hp;
+    *stats = save.stats;
+    elapsed.seconds = save.elapsed;
+    time_attack.remaining = save.time_left;
+    waves.current = save.wave;
+    if endless.0 {
+        waves.extend_endless();
+    }
+    waves.spawned = save.wave_spawned;
+    for saved in save.enemies {
+        let Some(info) = enemy_types.types.get(&saved.name) else {
+            warn!("saved enemy `{}` no longer exists, skipping", saved.name);
+            continue;
+        };
+        let enemy = Enemy {
+            name: saved.name,
+            speed: saved.speed,
+            health: saved.health,
+            reward: saved.reward,
+        };
+        spawn_named_enemy(
+            &mut commands,
+            &enemy_anims,
+            enemy,
+            info,
+            Vec3::new(saved.x, saved.y, 0.0),
+            *orientation,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::{
+        animation::AnimState,
+        data::mode::VILLAGER_LIMIT,
+        testing::{advance, headless_app, TEST_ENEMY},
+    };
+
+    fn run_app() -> App {
+        let mut app = headless_app();
+        app.init_resource::<Shield>()
+            .init_resource::<Wall>()
+            .init_resource::<TimeAttack>()
+            .init_resource::<ResumeRun>();
+        app
+    }
+
+    fn enemies(app: &mut App) -> Vec<(Vec2, i32)> {
+        let mut enemies: Vec<(Vec2, i32)> = app
+            .world
+            .query::<(&Enemy, &Transform)>()
+            .iter(&app.world)
+            .map(|(enemy, transform)| (transform.translation.truncate(), enemy.health))
+            .collect();
+        enemies.sort_by(|a, b| a.0.x.total_cmp(&b.0.x));
+        enemies
+    }
+
+    #[test]
+    fn closing_the_window_mid_run_records_it_once() {
+        let mut app = headless_app();
+        app.add_event::<WindowCloseRequested>()
+            .init_resource::<SummaryWritten>()
+            .add_systems(Update, record_aborted_run.run_if(run_in_progress));
+        app.world.resource_mut::<GameStats>().villagers_saved = 7;
+        for _ in 0..2 {
+            app.world.send_event(WindowCloseRequested {
+                window: Entity::PLACEHOLDER,
+            });
+            advance(&mut app, 1);
+        }
+
+        let history = persist::load::<RunHistory>(SUMMARY_FILE);
+        assert_eq!(history.runs.len(), 1);
+        assert_eq!(history.runs[0].stats.villagers_saved, 7);
+        assert!(history.runs[0].outcome == RunOutcome::Aborted);
+    }
+
+    #[test]
+    fn saved_run_resumes_as_it_was() {
+        let mut app = run_app();
+        let mode = GameMode {
+            waves: false,
+            villager_limit: Some(VILLAGER_LIMIT),
+            time_attack: Some(60.0),
+        };
+        app.insert_resource(mode.clone());
+        app.world.resource_mut::<PlayerData>().health = 4;
+        app.world.resource_mut::<PlayerData>().lives = 2;
+        app.world.resource_mut::<Shield>().charges = 1;
+        app.world.resource_mut::<Wall>().hp = 7;
+        app.world.resource_mut::<TimeAttack>().remaining = 12.5;
+        for (x, health) in [(100.0, 1), (250.0, 3)] {
+            app.world.spawn((
+                Enemy {
+                    name: TEST_ENEMY.to_string(),
+                    speed: 75.0,
+                    health,
+                    reward: 1,
+                },
+                Transform::from_xyz(x, -40.0, 0.0),
+                AnimationComponent::new(AnimState::Walking),
+            ));
+        }
+
+        let save = app
+            .world
+            .run_system_once(|snapshot: RunSnapshot| snapshot.save());
+        let json = serde_json::to_vec(&save).unwrap();
+        let save: SaveGame = serde_json::from_slice(&json).unwrap();
+        assert_eq!(save.mode, mode);
+
+        let mut resumed = run_app();
+        resumed.world.resource_mut::<ResumeRun>().save = Some(save);
+        resumed.world.run_system_once(resume_run);
+
+        assert_eq!(enemies(&mut resumed), enemies(&mut app));
+        let player_data = resumed.world.resource::<PlayerData>();
+        assert_eq!((player_data.health, player_data.lives), (4, 2));
+        assert_eq!(resumed.world.resource::<Shield>().charges, 1);
+        assert_eq!(resumed.world.resource::<Wall>().hp, 7);
+        assert_eq!(resumed.world.resource::<TimeAttack>().remaining, 12.5);
+    }
+}
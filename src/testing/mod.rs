@@ -0,0 +1,187 @@
+use std::time::Duration;
+
+use bevy::{
+    gizmos::GizmoPlugin, prelude::*, render::render_resource::Shader, time::TimeUpdateStrategy,
+};
+use bevy_rapier2d::{prelude::*, rapier::geometry::CollisionEventFlags};
+
+use crate::{
+    animation::{AnimState, AnimationHandles, ContactBehavior, DeathStyle, EnemyAnimations},
+    audio::Sfx,
+    data::{
+        layout::ScreenLayout,
+        localization::Localization,
+        mode::{GameMode, RunModifiers},
+        playfield::{DefenseLine, PlayfieldBounds, PlayfieldOrientation},
+        rng::GameRng,
+        settings::{Difficulty, Endless},
+        state::GameState,
+        theme::Theme,
+    },
+    effects::{EffectBudget, LiveEffects},
+    entities::{
+        boss::BossIntro,
+        enemy::{
+            EnemyInfo, EnemySet, EnemySpawnData, EnemyTypes, ManualSpawn, SpawnConfig,
+            SpawningEnabled,
+        },
+        player::{DamagePlayer, DeathSequence, ElapsedGameplay, GameStats, PlayerData},
+        unlock::Unlocks,
+        wave::WaveState,
+    },
+    input::InputMap,
+    pause::{restart_requested, RestartRun, RestartSet},
+    CameraConfig, GameplayStart,
+};
+
+pub const TEST_ENEMY: &str = "test_enemy";
+pub const TEST_STEP: f32 = 1.0 / 60.0;
+
+pub struct HeadlessTestPlugin;
+
+impl Plugin for HeadlessTestPlugin {
+    fn build(&self, app: &mut App) {
+        let camera = CameraConfig::default();
+        let bounds = PlayfieldBounds::default();
+        let orientation = PlayfieldOrientation::default();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Shader>()
+            .init_asset::<Font>()
+            .add_plugins(GizmoPlugin)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+                TEST_STEP,
+            )))
+            .insert_resource(GameplayStart::new(&camera))
+            .insert_resource(DefenseLine::new(
+                camera.gameplay_pos.x,
+                &bounds,
+                orientation,
+            ))
+            .insert_resource(camera)
+            .insert_resource(bounds)
+            .insert_resource(orientation)
+            .init_resource::<GameMode>()
+            .init_resource::<RunModifiers>()
+            .init_resource::<Difficulty>()
+            .init_resource::<Endless>()
+            .init_resource::<Theme>()
+            .init_resource::<Localization>()
+            .init_resource::<SpawnConfig>()
+            .init_resource::<EnemySpawnData>()
+            .init_resource::<ManualSpawn>()
+            .init_resource::<SpawningEnabled>()
+            .insert_resource(GameRng::from_seed(0))
+            .init_resource::<ScreenLayout>()
+            .init_resource::<GameStats>()
+            .init_resource::<ElapsedGameplay>()
+            .init_resource::<PlayerData>()
+            .init_resource::<DeathSequence>()
+            .init_resource::<Unlocks>()
+            .init_resource::<BossIntro>()
+            .init_resource::<WaveState>()
+            .init_resource::<InputMap>()
+            .init_resource::<Input<KeyCode>>()
+            .init_resource::<Input<GamepadButton>>()
+            .init_resource::<Gamepads>()
+            .init_resource::<EffectBudget>()
+            .init_resource::<LiveEffects>()
+            .insert_resource(stub_enemy_types())
+            .insert_resource(stub_enemy_animations())
+            .add_event::<CollisionEvent>()
+            .add_event::<DamagePlayer>()
+            .add_event::<Sfx>()
+            .init_resource::<RestartRun>()
+            .add_state::<GameState>()
+            .configure_sets(
+                Update,
+                (EnemySet::Spawn, EnemySet::React, EnemySet::Cleanup).chain(),
+            )
+            .configure_sets(
+                OnEnter(GameState::GamePlay),
+                RestartSet.run_if(restart_requested),
+            );
+    }
+}
+
+// windowless app already in GamePlay, add the plugins under test before advancing
+pub fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(HeadlessTestPlugin);
+    enter_state(&mut app, GameState::GamePlay);
+    app
+}
+
+// every handle points at nothing, which is enough for spawning not to unwrap-panic
+pub fn stub_enemy_animations() -> EnemyAnimations {
+    let mut handles = AnimationHandles::default();
+    for state in [
+        AnimState::Walking,
+        AnimState::Idle,
+        AnimState::Hurting,
+        AnimState::Dying,
+    ] {
+        handles.add_handle(state.to_string(), Handle::default());
+    }
+    let mut anims = EnemyAnimations::default();
+    anims.enemies.insert(TEST_ENEMY.to_string(), handles);
+    anims
+}
+
+pub fn stub_enemy_types() -> EnemyTypes {
+    let mut types = EnemyTypes::default();
+    types.types.insert(
+        TEST_ENEMY.to_string(),
+        EnemyInfo {
+            health: 1,
+            speed: 75.0,
+            reward: 1,
+            weight: 1.0,
+            wall_damage: 1,
+            unlock_score: None,
+            max_concurrent: None,
+            frenzy: None,
+            drain_aura: None,
+            blinker: None,
+            aggro: None,
+            ranged: None,
+            drops: Vec::new(),
+            death_style: DeathStyle::Flash,
+            contact_behavior: ContactBehavior::PassThrough,
+        },
+    );
+    types
+}
+
+pub fn enter_state(app: &mut App, state: GameState) {
+    app.world.resource_mut::<NextState<GameState>>().set(state);
+    app.update();
+}
+
+pub fn advance(app: &mut App, frames: usize) {
+    for _ in 0..frames {
+        app.update();
+    }
+}
+
+pub fn advance_secs(app: &mut App, seconds: f32) {
+    advance(app, (seconds / TEST_STEP).ceil() as usize);
+}
+
+pub fn collide(app: &mut App, a: Entity, b: Entity) {
+    app.world
+        .send_event(CollisionEvent::Started(a, b, CollisionEventFlags::SENSOR));
+}
+
+// there is no input plugin to clear last frame's presses, so this starts
+// from a clean frame
+pub fn press(app: &mut App, key: KeyCode) {
+    let mut keys = app.world.resource_mut::<Input<KeyCode>>();
+    keys.clear();
+    keys.press(key);
+}
+
+pub fn release(app: &mut App, key: KeyCode) {
+    let mut keys = app.world.resource_mut::<Input<KeyCode>>();
+    keys.clear();
+    keys.release(key);
+}